@@ -0,0 +1,53 @@
+#![no_std]
+#![cfg_attr(not(test), no_main)]
+
+use arduino_hal;
+use led_strip::button::{Button, ButtonEvent};
+use led_strip::effect::{Effect, Scheduler};
+use led_strip::led::{wheel, Color, LedStrip};
+
+const NUM_LEDS: usize = 12;
+const FRAME_TIME: u32 = 20; // milliseconds
+
+#[arduino_hal::entry]
+fn main() -> ! {
+    let dp = arduino_hal::Peripherals::take().unwrap();
+    let pins = arduino_hal::pins!(dp);
+
+    // Switch between d2 and GND, a press pulls the input low.
+    let switch = pins.d2.into_pull_up_input();
+    let mut button = Button::new();
+    let mut led_strip = LedStrip::new(NUM_LEDS, pins.d4);
+
+    let rainbow = |frame: u32, led_index: usize| wheel((frame as usize + led_index * 256 / NUM_LEDS) as u8);
+    let spinner = |frame: u32, led_index: usize| match (frame as usize / 4 + led_index) % NUM_LEDS {
+        0 => Color::Cyan,
+        1 => Color::Cyan.opacity(0.25),
+        _ => Color::Black,
+    };
+    let solid = |_, _| Color::Orange;
+    let mut scheduler: Scheduler<3> = Scheduler::new();
+    scheduler.add(Effect::new("rainbow", &rainbow));
+    scheduler.add(Effect::new("spinner", &spinner));
+    scheduler.add(Effect::new("solid", &solid));
+
+    let mut time: u32 = 0;
+
+    loop {
+        // A short press shows the next effect, a long press turns the strip off and on.
+        match button.update(switch.is_low(), time) {
+            Some(ButtonEvent::Press) => scheduler.next(),
+            Some(ButtonEvent::LongPress) => scheduler.set_enabled(!scheduler.is_enabled()),
+            None => {},
+        }
+
+        scheduler.show(&mut led_strip);
+        led_strip.rest(FRAME_TIME * 1_000);
+        time = time.wrapping_add(FRAME_TIME);
+    }
+}
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
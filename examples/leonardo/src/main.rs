@@ -0,0 +1,26 @@
+#![no_std]
+#![cfg_attr(not(test), no_main)]
+
+use arduino_hal;
+use led_strip::led::{wheel, LedStrip};
+
+const NUM_LEDS: usize = 12;
+const FRAME_TIME: u32 = 20_000; // microseconds
+
+// Rainbow on the d6 pin of an Arduino Leonardo (ATmega32U4).
+#[arduino_hal::entry]
+fn main() -> ! {
+    let dp = arduino_hal::Peripherals::take().unwrap();
+
+    let mut led_strip = LedStrip::new(NUM_LEDS, arduino_hal::pins!(dp).d6);
+
+    loop {
+        led_strip.each_frame(|led_index, frame, _| wheel((frame as usize + led_index * 256 / NUM_LEDS) as u8));
+        led_strip.rest(FRAME_TIME);
+    }
+}
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
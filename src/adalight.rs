@@ -0,0 +1,239 @@
+use crate::buffer::DoubleBufferedStrip;
+use crate::led::{Color, LedStrip, Transmitter, LATCH_TIME};
+use crate::stream::stage;
+
+/// Greeting to write on the serial port at startup so the host software detects the device.
+pub const HELLO: &[u8] = b"Ada\n";
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum State {
+    Magic(u8),
+    High,
+    Low,
+    Checksum,
+    Data,
+    Sum,
+}
+
+/// Incremental parser of the Adalight protocol, fed one byte at a time
+/// (e.g. from the UART), each frame being `Ada`, the LED count minus one
+/// (2 bytes, big-endian), their XOR with 0x55, then the RGB channels.
+///
+/// With `with_checksum()`, each frame ends with the sum of its RGB
+/// channels (wrapping around), checked by `push_checked()`.
+pub struct Adalight {
+    state: State,
+    high: u8,
+    low: u8,
+    led_count: usize,
+    led_index: usize,
+    channels: [u8; 3],
+    channel: usize,
+    checksum: bool,
+    sum: u8,
+    valid: Option<bool>,
+}
+
+impl Adalight {
+    pub fn new() -> Adalight {
+        Adalight {
+            state: State::Magic(0),
+            high: 0,
+            low: 0,
+            led_count: 0,
+            led_index: 0,
+            channels: [0; 3],
+            channel: 0,
+            checksum: false,
+            sum: 0,
+            valid: None,
+        }
+    }
+
+    /// Expect a checksum byte after the RGB channels of each frame.
+    pub fn with_checksum(mut self) -> Adalight {
+        self.checksum = true;
+
+        self
+    }
+
+    /// LED count announced by the header of the current frame.
+    pub fn led_count(&self) -> usize {
+        self.led_count
+    }
+
+    /// Whether the parser is between frames, waiting for the `Ada` magic.
+    pub fn is_idle(&self) -> bool {
+        self.state == State::Magic(0)
+    }
+
+    /// Parse the next byte, return the LED index and color once a pixel is complete.
+    pub fn feed(&mut self, byte: u8) -> Option<(usize, Color)> {
+        match self.state {
+            State::Magic(position) => {
+                self.state = match (b"Ada"[position as usize] == byte, position) {
+                    (true, 2) => State::High,
+                    (true, _) => State::Magic(position + 1),
+                    (false, _) if byte == b'A' => State::Magic(1),
+                    (false, _) => State::Magic(0),
+                };
+            },
+            State::High => {
+                self.high = byte;
+                self.state = State::Low;
+            },
+            State::Low => {
+                self.low = byte;
+                self.state = State::Checksum;
+            },
+            State::Checksum => {
+                self.state = match self.high ^ self.low ^ 0x55 == byte {
+                    true => {
+                        self.led_count = usize::from(u16::from_be_bytes([self.high, self.low])) + 1;
+                        self.led_index = 0;
+                        self.channel = 0;
+                        self.sum = 0;
+
+                        State::Data
+                    },
+                    false => State::Magic(u8::from(byte == b'A')),
+                };
+            },
+            State::Data => {
+                self.channels[self.channel] = byte;
+                self.channel += 1;
+                self.sum = self.sum.wrapping_add(byte);
+
+                if self.channel == 3 {
+                    let led_index = self.led_index;
+                    let [red, green, blue] = self.channels;
+                    self.channel = 0;
+                    self.led_index += 1;
+
+                    if self.led_index == self.led_count {
+                        self.state = match self.checksum {
+                            true => State::Sum,
+                            false => {
+                                self.valid = Some(true);
+
+                                State::Magic(0)
+                            },
+                        };
+                    }
+
+                    return Some((led_index, Color::RGB(red, green, blue)));
+                }
+            },
+            State::Sum => {
+                self.valid = Some(byte == self.sum);
+                self.state = State::Magic(0);
+            },
+        }
+
+        None
+    }
+
+    /// Parse the next byte and send each complete pixel to the strip,
+    /// latching after the last one, return `true` when a frame was displayed.
+    ///
+    /// Pixels beyond the strip length are sent too, they are simply not displayed.
+    pub fn push<T: Transmitter>(&mut self, byte: u8, led_strip: &mut LedStrip<T>) -> bool {
+        match self.feed(byte) {
+            Some((led_index, color)) => {
+                led_strip.color(color);
+
+                if led_index + 1 == self.led_count {
+                    led_strip.rest(LATCH_TIME);
+
+                    return true;
+                }
+
+                false
+            },
+            None => false,
+        }
+    }
+
+    /// Parse the next byte into the back buffer, showing the frame once
+    /// complete, or dropping it if its checksum is wrong; return the `ACK`
+    /// or `NAK` byte to reply to the host at the end of each frame.
+    pub fn push_checked<T: Transmitter, const N: usize>(
+        &mut self,
+        byte: u8,
+        led_strip: &mut DoubleBufferedStrip<T, N>,
+    ) -> Option<u8> {
+        let pixel = self.feed(byte);
+
+        stage(led_strip, pixel, self.valid.take())
+    }
+}
+
+impl Default for Adalight {
+    fn default() -> Self {
+        Adalight::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adalight::Adalight;
+    use crate::buffer::DoubleBufferedStrip;
+    use crate::led::{Color, LedStrip};
+    use crate::mock::MockPin;
+    use crate::stream::{ACK, NAK};
+
+    #[test]
+    fn parse() {
+        let mut adalight = Adalight::new();
+        let mut pixels = [None; 2];
+        let stream = [
+            b'A', b'd', b'A', b'd', b'a', 0, 1, 0x54, 1, 2, 3, 4, 5, 6,
+            b'A', b'd', b'a', 0, 0, 0x00, 7, 8, 9,
+        ];
+
+        for byte in stream {
+            if let Some((led_index, color)) = adalight.feed(byte) {
+                assert!(pixels[led_index].is_none());
+                pixels[led_index] = Some(color);
+            }
+        }
+
+        assert_eq!(pixels, [Some(Color::RGB(1, 2, 3)), Some(Color::RGB(4, 5, 6))]);
+        assert_eq!(adalight.led_count(), 2);
+    }
+
+    #[test]
+    fn push() {
+        let mut adalight = Adalight::new();
+        let mut led_strip = LedStrip::with_transmitter(1, MockPin::<64>::new());
+        let frames = [b'A', b'd', b'a', 0, 0, 0x55, 10, 20, 30]
+            .iter()
+            .filter(|&&byte| adalight.push(byte, &mut led_strip))
+            .count();
+
+        assert_eq!(frames, 1);
+        assert!(led_strip.transmitter().bytes().eq([20, 10, 30]));
+        assert_eq!(led_strip.transmitter().latches().count(), 1);
+    }
+
+    #[test]
+    fn checksum() {
+        let mut adalight = Adalight::new().with_checksum();
+        let mut led_strip = DoubleBufferedStrip::<_, 1>::new(LedStrip::with_transmitter(1, MockPin::<128>::new()));
+        let mut replies = [None; 2];
+        let stream = [
+            b'A', b'd', b'a', 0, 0, 0x55, 10, 20, 30, 60,
+            b'A', b'd', b'a', 0, 0, 0x55, 10, 99, 30, 60,
+        ];
+
+        for byte in stream {
+            if let Some(reply) = adalight.push_checked(byte, &mut led_strip) {
+                replies[usize::from(replies[0].is_some())] = Some(reply);
+            }
+        }
+
+        assert_eq!(replies, [Some(ACK), Some(NAK)]);
+        assert_eq!(led_strip.front(), &[Color::RGB(10, 20, 30)]);
+        assert_eq!(led_strip.strip().transmitter().latches().count(), 1);
+    }
+}
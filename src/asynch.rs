@@ -0,0 +1,222 @@
+use core::cell::Cell;
+use core::future::{poll_fn, Future};
+use core::task::{Poll, Waker};
+use critical_section::Mutex;
+use heapless::Vec;
+use crate::error::LedError;
+use crate::led::{Color, LedStrip, Transmitter, LATCH_TIME};
+
+/// Peripheral sending whole frames without blocking (DMA, PIO, RMT...),
+/// for async executors such as embassy.
+pub trait AsyncTransmitter {
+    /// Send the bytes of a frame, in order.
+    fn write(&mut self, bytes: &[u8]) -> impl Future<Output = ()>;
+
+    /// Hold the line low for `us` microseconds so the strip displays the
+    /// frame, e.g. awaiting a timer so other tasks run meanwhile.
+    fn reset(&mut self, us: u32) -> impl Future<Output = ()>;
+}
+
+/// Transmitter keeping the bytes of the frame being rendered, up to `BYTES`
+/// (3 per LED), the ones after being dropped.
+pub struct FrameBuffer<const BYTES: usize> {
+    bytes: Vec<u8, BYTES>,
+    reset_us: Option<u32>,
+    dropped: usize,
+}
+
+impl<const BYTES: usize> FrameBuffer<BYTES> {
+    pub const fn new() -> FrameBuffer<BYTES> {
+        FrameBuffer {
+            bytes: Vec::new(),
+            reset_us: None,
+            dropped: 0,
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Whether bytes were dropped from the frame because `BYTES` is too small.
+    pub fn overflowed(&self) -> bool {
+        self.dropped > 0
+    }
+}
+
+impl<const BYTES: usize> Transmitter for FrameBuffer<BYTES> {
+    fn send_byte(&mut self, byte: u8) {
+        if self.bytes.push(byte).is_err() {
+            self.dropped += 1;
+        }
+    }
+
+    fn reset(&mut self, us: u32) {
+        self.reset_us = Some(us);
+    }
+}
+
+impl<const BYTES: usize> Default for FrameBuffer<BYTES> {
+    fn default() -> Self {
+        FrameBuffer::new()
+    }
+}
+
+/// Strip rendered like `LedStrip` (brightness, correction, power limit...)
+/// into a frame buffer, then sent by an `AsyncTransmitter`.
+pub struct AsyncLedStrip<T: AsyncTransmitter, const BYTES: usize> {
+    strip: LedStrip<FrameBuffer<BYTES>>,
+    transmitter: T,
+}
+
+impl<T: AsyncTransmitter, const BYTES: usize> AsyncLedStrip<T, BYTES> {
+    pub fn new(led_count: usize, transmitter: T) -> AsyncLedStrip<T, BYTES> {
+        AsyncLedStrip {
+            strip: LedStrip::with_transmitter(led_count, FrameBuffer::new()),
+            transmitter,
+        }
+    }
+
+    /// Rendering settings, shared with the blocking driver.
+    pub fn strip(&self) -> &LedStrip<FrameBuffer<BYTES>> {
+        &self.strip
+    }
+
+    pub fn strip_mut(&mut self) -> &mut LedStrip<FrameBuffer<BYTES>> {
+        &mut self.strip
+    }
+
+    pub fn transmitter_mut(&mut self) -> &mut T {
+        &mut self.transmitter
+    }
+
+    /// Render a frame then send it, yielding while it is sent and latched.
+    pub async fn show<F>(&mut self, callback: F) -> Result<(), LedError> where F: (Fn(usize) -> Color) {
+        self.strip.each(callback);
+        self.flush().await
+    }
+
+    /// Send the pixels rendered since the last latch, if any, failing with
+    /// `LedError::BufferTooSmall` when the end of the frame had to be dropped.
+    pub async fn flush(&mut self) -> Result<(), LedError> {
+        self.strip.latch();
+
+        let buffer = self.strip.transmitter_mut();
+        let dropped = core::mem::take(&mut buffer.dropped);
+
+        if let Some(us) = buffer.reset_us.take() {
+            self.transmitter.write(&buffer.bytes).await;
+            self.transmitter.reset(us.max(LATCH_TIME)).await;
+            buffer.bytes.clear();
+        }
+
+        match dropped {
+            0 => Ok(()),
+            _ => Err(LedError::BufferTooSmall { needed: BYTES + dropped, len: BYTES }),
+        }
+    }
+}
+
+/// Signal set from an interrupt (e.g. DMA transfer complete) and awaited
+/// by an `AsyncTransmitter`, its state only accessed in critical sections
+/// so the interrupt can't preempt the task in the middle of an update.
+pub struct Completion {
+    done: Mutex<Cell<bool>>,
+    waker: Mutex<Cell<Option<Waker>>>,
+}
+
+impl Completion {
+    pub const fn new() -> Completion {
+        Completion {
+            done: Mutex::new(Cell::new(false)),
+            waker: Mutex::new(Cell::new(None)),
+        }
+    }
+
+    /// Wake the task waiting for the completion, to call from the interrupt.
+    pub fn complete(&self) {
+        let waker = critical_section::with(|cs| {
+            self.done.borrow(cs).set(true);
+            self.waker.borrow(cs).take()
+        });
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Wait until `complete()` is called, then reset for the next transfer.
+    pub fn wait(&self) -> impl Future<Output = ()> + '_ {
+        poll_fn(|context| critical_section::with(|cs| {
+            if self.done.borrow(cs).replace(false) {
+                return Poll::Ready(());
+            }
+
+            self.waker.borrow(cs).set(Some(context.waker().clone()));
+
+            Poll::Pending
+        }))
+    }
+}
+
+impl Default for Completion {
+    fn default() -> Self {
+        Completion::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use crate::asynch::{AsyncLedStrip, AsyncTransmitter, Completion};
+    use crate::error::LedError;
+    use crate::led::Color;
+
+    struct Recorder {
+        bytes: [u8; 6],
+        resets: u32,
+    }
+
+    impl AsyncTransmitter for Recorder {
+        async fn write(&mut self, bytes: &[u8]) {
+            self.bytes[..bytes.len()].copy_from_slice(bytes);
+        }
+
+        async fn reset(&mut self, _us: u32) {
+            self.resets += 1;
+        }
+    }
+
+    fn poll<F: Future>(future: F) -> Poll<F::Output> {
+        pin!(future).poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn show() {
+        let mut strip: AsyncLedStrip<Recorder, 6> = AsyncLedStrip::new(2, Recorder { bytes: [0; 6], resets: 0 });
+        strip.strip_mut().set_brightness(128);
+
+        assert_eq!(poll(strip.show(|led_index| [Color::Red, Color::Blue][led_index])), Poll::Ready(Ok(())));
+        assert_eq!(strip.transmitter_mut().bytes, [0, 128, 0, 0, 0, 128]);
+        assert_eq!(poll(strip.flush()), Poll::Ready(Ok(())));
+        assert_eq!(strip.transmitter_mut().resets, 1);
+
+        let mut short: AsyncLedStrip<Recorder, 3> = AsyncLedStrip::new(2, Recorder { bytes: [0; 6], resets: 0 });
+        let error = LedError::BufferTooSmall { needed: 6, len: 3 };
+        assert_eq!(poll(short.show(|_| Color::White)), Poll::Ready(Err(error)));
+    }
+
+    #[test]
+    fn completion() {
+        let completion = Completion::new();
+        let mut wait = pin!(completion.wait());
+        let mut context = Context::from_waker(Waker::noop());
+
+        assert!(wait.as_mut().poll(&mut context).is_pending());
+        completion.complete();
+        assert!(wait.as_mut().poll(&mut context).is_ready());
+        assert!(poll(completion.wait()).is_pending());
+    }
+}
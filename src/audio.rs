@@ -0,0 +1,316 @@
+use crate::error::LedError;
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Highest value of the 10-bit AVR ADC.
+pub const ADC_MAX: u16 = 1023;
+
+/// Color of the bar at `position` (0 at the bottom, 255 at the top): green, yellow then red.
+pub fn level_color(position: u8) -> Color {
+    match position {
+        0..=127 => Color::Green.lerp(Color::Yellow, position * 2),
+        _ => Color::Yellow.lerp(Color::Red, (position - 128) * 2 + 1),
+    }
+}
+
+/// Sound level bar with a peak indicator, fed with ADC samples of a
+/// microphone or envelope module, e.g. `vu_meter.sample(mic.analog_read(&mut adc))`.
+///
+/// The bar follows the loudest sample and falls back by `decay` per frame,
+/// the peak stays `peak_hold` frames before falling the same way.
+pub struct VuMeter {
+    led_count: usize,
+    center: Option<u16>,
+    decay: u16,
+    peak_hold: u8,
+    level: u16,
+    peak: u16,
+    peak_age: u8,
+}
+
+impl VuMeter {
+    pub fn new(led_count: usize) -> VuMeter {
+        VuMeter {
+            led_count,
+            center: None,
+            decay: 16,
+            peak_hold: 30,
+            level: 0,
+            peak: 0,
+            peak_age: 0,
+        }
+    }
+
+    /// Measure the distance to `center` (e.g. 512 for the biased output of a
+    /// microphone amplifier) instead of the raw value of an envelope output.
+    pub fn centered(mut self, center: u16) -> VuMeter {
+        self.center = Some(center);
+
+        self
+    }
+
+    pub fn decay(mut self, decay: u16) -> VuMeter {
+        self.decay = decay;
+
+        self
+    }
+
+    pub fn peak_hold(mut self, frames: u8) -> VuMeter {
+        self.peak_hold = frames;
+
+        self
+    }
+
+    /// Level of the full bar.
+    pub fn max(&self) -> u16 {
+        match self.center {
+            Some(center) => center.max(ADC_MAX - center),
+            None => ADC_MAX,
+        }
+    }
+
+    pub fn level(&self) -> u16 {
+        self.level
+    }
+
+    pub fn peak(&self) -> u16 {
+        self.peak
+    }
+
+    pub fn sample(&mut self, value: u16) {
+        let amplitude = match self.center {
+            Some(center) => value.abs_diff(center),
+            None => value,
+        }.min(self.max());
+
+        self.level = self.level.max(amplitude);
+
+        if amplitude >= self.peak {
+            self.peak = amplitude;
+            self.peak_age = 0;
+        }
+    }
+
+    /// Apply the decay, call it once per displayed frame.
+    pub fn next_frame(&mut self) {
+        self.level = self.level.saturating_sub(self.decay);
+
+        match self.peak_age < self.peak_hold {
+            true => self.peak_age += 1,
+            false => self.peak = self.peak.saturating_sub(self.decay).max(self.level),
+        }
+    }
+
+    fn leds(&self, value: u16) -> usize {
+        (u32::from(value) * self.led_count as u32).div_ceil(u32::from(self.max())) as usize
+    }
+
+    pub fn color(&self, led_index: usize) -> Color {
+        let position = (led_index * 255 / self.led_count.saturating_sub(1).max(1)).min(255) as u8;
+
+        match led_index < self.leds(self.level) || led_index + 1 == self.leds(self.peak) {
+            true => level_color(position),
+            false => Color::Black,
+        }
+    }
+
+    /// Send the bar then apply the decay.
+    pub fn show<T: Transmitter>(&mut self, led_strip: &mut LedStrip<T>) {
+        led_strip.each(|led_index| self.color(led_index));
+        self.next_frame();
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Beat {
+    /// Energy of the block relative to the average, 8.8 fixed-point (256 = average).
+    pub strength: u16,
+}
+
+/// Energy-based beat detector: samples are summed by blocks of `block_size`
+/// and a beat is reported when a block is louder than `threshold` times
+/// the average of the last `N` blocks.
+pub struct BeatDetector<const N: usize> {
+    center: u16,
+    block_size: u16,
+    threshold: u16,
+    cooldown: u16,
+    history: [u32; N],
+    filled: usize,
+    index: usize,
+    count: u16,
+    energy: u32,
+    since_beat: u16,
+    interval: Option<u16>,
+}
+
+impl<const N: usize> BeatDetector<N> {
+    /// Detector for samples centered on `center` (e.g. 512 for a biased
+    /// microphone output), panics if `N` or `block_size` is 0, see `try_new()`.
+    pub fn new(center: u16, block_size: u16) -> BeatDetector<N> {
+        assert!(N > 0 && block_size > 0);
+
+        BeatDetector {
+            center,
+            block_size,
+            threshold: 384,
+            cooldown: 4,
+            history: [0; N],
+            filled: 0,
+            index: 0,
+            count: 0,
+            energy: 0,
+            since_beat: u16::MAX,
+            interval: None,
+        }
+    }
+
+    /// Same as `new()`, failing if `N` or `block_size` is 0.
+    pub fn try_new(center: u16, block_size: u16) -> Result<BeatDetector<N>, LedError> {
+        match N > 0 && block_size > 0 {
+            true => Ok(BeatDetector::new(center, block_size)),
+            false => Err(LedError::BufferTooSmall { needed: 1, len: N.min(block_size as usize) }),
+        }
+    }
+
+    /// Ratio to the average energy a block must exceed, 8.8 fixed-point (default 1.5).
+    pub fn threshold(mut self, threshold: u16) -> BeatDetector<N> {
+        self.threshold = threshold;
+
+        self
+    }
+
+    /// Minimum number of blocks between 2 beats.
+    pub fn cooldown(mut self, blocks: u16) -> BeatDetector<N> {
+        self.cooldown = blocks;
+
+        self
+    }
+
+    /// Blocks since the last beat, e.g. to fade a flash out.
+    pub fn since_beat(&self) -> u16 {
+        self.since_beat
+    }
+
+    /// Smoothed number of blocks between beats.
+    pub fn interval(&self) -> Option<u16> {
+        self.interval
+    }
+
+    /// Tempo from the beat interval, given the block rate (sample rate / `block_size`).
+    pub fn bpm(&self, blocks_per_second: u16) -> Option<u16> {
+        self.interval.map(|interval| (60 * u32::from(blocks_per_second) / u32::from(interval.max(1))) as u16)
+    }
+
+    fn average(&self) -> u32 {
+        self.history.iter().sum::<u32>() / N as u32
+    }
+
+    pub fn sample(&mut self, value: u16) -> Option<Beat> {
+        self.energy += u32::from(value.abs_diff(self.center));
+        self.count += 1;
+
+        if self.count < self.block_size {
+            return None;
+        }
+
+        let energy = self.energy;
+        let average = self.average();
+        let full = self.filled == N;
+        self.energy = 0;
+        self.count = 0;
+        self.since_beat = self.since_beat.saturating_add(1);
+        self.history[self.index] = energy;
+        self.index = (self.index + 1) % N;
+        self.filled = (self.filled + 1).min(N);
+
+        // Require some sound so that noise in silence doesn't trigger beats.
+        if !full || energy < u32::from(self.block_size) * 4 || self.since_beat < self.cooldown {
+            return None;
+        }
+
+        if u64::from(energy) * 256 <= u64::from(average) * u64::from(self.threshold) {
+            return None;
+        }
+
+        if self.since_beat != u16::MAX {
+            self.interval = Some(match self.interval {
+                Some(interval) => ((u32::from(interval) * 3 + u32::from(self.since_beat)) / 4) as u16,
+                None => self.since_beat,
+            });
+        }
+
+        self.since_beat = 0;
+
+        Some(Beat {
+            strength: (u64::from(energy) * 256 / u64::from(average.max(1))).min(u64::from(u16::MAX)) as u16,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::audio::{level_color, BeatDetector, VuMeter};
+    use crate::led::Color;
+
+    #[test]
+    fn gradient() {
+        assert_eq!(level_color(0), Color::RGB(0, 255, 0));
+        assert_eq!(level_color(64), Color::RGB(128, 255, 0));
+        assert_eq!(level_color(128), Color::RGB(255, 254, 0));
+        assert_eq!(level_color(255), Color::RGB(255, 0, 0));
+    }
+
+    #[test]
+    fn peak_hold() {
+        let mut vu_meter = VuMeter::new(10).centered(512).decay(100).peak_hold(2);
+        assert_eq!(vu_meter.max(), 512);
+
+        vu_meter.sample(612);
+        vu_meter.sample(12);
+        vu_meter.sample(400);
+
+        assert_eq!((vu_meter.level(), vu_meter.peak()), (500, 500));
+        assert_eq!(vu_meter.color(0), Color::RGB(0, 255, 0));
+        assert_eq!(vu_meter.color(9), Color::RGB(255, 0, 0));
+
+        vu_meter.next_frame();
+        vu_meter.next_frame();
+        assert_eq!((vu_meter.level(), vu_meter.peak()), (300, 500));
+        assert_ne!(vu_meter.color(5), Color::Black);
+        assert_eq!(vu_meter.color(6), Color::Black);
+        assert_eq!(vu_meter.color(8), Color::Black);
+        assert_eq!(vu_meter.color(9), Color::RGB(255, 0, 0));
+
+        vu_meter.next_frame();
+        assert_eq!((vu_meter.level(), vu_meter.peak()), (200, 400));
+    }
+
+    #[test]
+    fn beats() {
+        let mut detector: BeatDetector<4> = BeatDetector::new(512, 2);
+        let mut beats = [0; 3];
+        let mut count = 0;
+
+        for block in 0..40 {
+            let amplitude = match block % 10 {
+                0 => 300,
+                _ => 20,
+            };
+
+            for sample in [512 + amplitude, 512 - amplitude] {
+                if let Some(beat) = detector.sample(sample) {
+                    assert!(count < 3);
+                    assert!(beat.strength > 384);
+                    beats[count] = block;
+                    count += 1;
+                }
+            }
+        }
+
+        assert_eq!(&beats[..count], &[10, 20, 30]);
+        assert_eq!(detector.interval(), Some(10));
+        assert_eq!(detector.bpm(20), Some(120));
+        assert_eq!(detector.since_beat(), 9);
+    }
+}
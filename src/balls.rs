@@ -0,0 +1,166 @@
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Position and speed of a falling object, in 1/256 of LED and of LED per
+/// frame, for gravity based effects.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ball {
+    pub position: i32,
+    pub velocity: i32,
+}
+
+impl Ball {
+    /// Advance one frame, bouncing on 0 while keeping `damping / 256` of the
+    /// speed. Return whether it bounced.
+    pub fn step(&mut self, gravity: i32, damping: u8) -> bool {
+        self.velocity -= gravity;
+        self.position += self.velocity;
+
+        if self.position >= 0 {
+            return false;
+        }
+
+        self.position = 0;
+        self.velocity = -self.velocity * i32::from(damping) / 256;
+
+        true
+    }
+
+    /// Nearest LED.
+    pub fn led(&self) -> usize {
+        ((self.position + 128) >> 8).max(0) as usize
+    }
+}
+
+/// Speed needed to rise up `height` (in 1/256 of LED), frame by frame.
+fn launch_velocity(gravity: i32, height: i32) -> i32 {
+    let (gravity, height) = (gravity.max(1) as u32, height.max(0) as u32);
+
+    ((2 * gravity * height + gravity * gravity / 4).isqrt() + gravity / 2) as i32
+}
+
+/// Up to `N` balls bouncing on LED 0 (e.g. the bottom of a stair or tube),
+/// each losing a bit more speed than the previous one so they drift apart,
+/// and thrown up again once at rest.
+pub struct BouncingBalls<const N: usize> {
+    led_count: usize,
+    balls: [Ball; N],
+    colors: [Color; N],
+    gravity: i32,
+    damping: u8,
+}
+
+impl<const N: usize> BouncingBalls<N> {
+    pub fn new(led_count: usize) -> BouncingBalls<N> {
+        let palette = [Color::Red, Color::Green, Color::Blue, Color::Yellow];
+        let mut balls = BouncingBalls {
+            led_count,
+            balls: [Ball { position: 0, velocity: 0 }; N],
+            colors: core::array::from_fn(|index| palette[index % palette.len()]),
+            gravity: 8,
+            damping: 230,
+        };
+        balls.launch_all();
+
+        balls
+    }
+
+    /// Speed lost every frame, in 1/256 of LED per frame.
+    pub fn gravity(mut self, gravity: i32) -> BouncingBalls<N> {
+        self.gravity = gravity.max(1);
+        self.launch_all();
+
+        self
+    }
+
+    /// Part of the speed the first ball keeps at each bounce, out of 256.
+    pub fn damping(mut self, damping: u8) -> BouncingBalls<N> {
+        self.damping = damping;
+
+        self
+    }
+
+    pub fn colors(mut self, colors: [Color; N]) -> BouncingBalls<N> {
+        self.colors = colors;
+
+        self
+    }
+
+    pub fn balls(&self) -> &[Ball; N] {
+        &self.balls
+    }
+
+    fn launch_all(&mut self) {
+        let velocity = self.top_velocity();
+
+        for ball in &mut self.balls {
+            *ball = Ball { position: 0, velocity };
+        }
+    }
+
+    /// Speed to reach the last LED of the strip.
+    fn top_velocity(&self) -> i32 {
+        launch_velocity(self.gravity, (self.led_count.saturating_sub(1) as i32) << 8)
+    }
+
+    pub fn next_frame(&mut self) {
+        let top_velocity = self.top_velocity();
+
+        for (index, ball) in self.balls.iter_mut().enumerate() {
+            let damping = self.damping.saturating_sub(index as u8 * 12);
+
+            // Too slow to visibly bounce anymore.
+            if ball.step(self.gravity, damping) && ball.velocity < self.gravity * 4 {
+                ball.velocity = top_velocity;
+            }
+        }
+    }
+
+    pub fn color(&self, led_index: usize) -> Color {
+        self.balls.iter().zip(self.colors)
+            .filter(|(ball, _)| ball.led() == led_index)
+            .fold(Color::Black, |color, (_, ball_color)| color + ball_color)
+    }
+
+    pub fn show<T: Transmitter>(&self, led_strip: &mut LedStrip<T>) {
+        led_strip.each(|led_index| self.color(led_index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::balls::{Ball, BouncingBalls};
+    use crate::led::Color;
+
+    #[test]
+    fn bounce() {
+        let mut ball = Ball { position: 200, velocity: -100 };
+
+        assert!(!ball.step(10, 128));
+        assert_eq!(ball, Ball { position: 90, velocity: -110 });
+        assert!(ball.step(10, 128));
+        assert_eq!(ball, Ball { position: 0, velocity: 60 });
+        assert_eq!(Ball { position: 383, velocity: 0 }.led(), 1);
+        assert_eq!(Ball { position: 384, velocity: 0 }.led(), 2);
+    }
+
+    #[test]
+    fn balls() {
+        let mut balls = BouncingBalls::<2>::new(30);
+        let mut highest = 0;
+        let mut relaunched = false;
+
+        for _ in 0..2_000 {
+            let before = balls.balls()[0].velocity;
+            balls.next_frame();
+            highest = highest.max(balls.balls()[0].led());
+            relaunched |= before < 0 && balls.balls()[0].velocity > 150;
+
+            assert!(balls.balls().iter().all(|ball| ball.led() < 30));
+        }
+
+        assert_eq!(highest, 29);
+        assert!(relaunched);
+        assert_eq!(BouncingBalls::<1>::new(10).color(0), Color::RGB(255, 0, 0));
+    }
+}
@@ -0,0 +1,186 @@
+use crate::led::Transmitter;
+
+/// Output stage receiving the WS2812 signal one bit at a time, most
+/// significant first, for backends where the bits are encoded by other
+/// hardware (SPI, a timer, an external encoder...). Wrap it in `Bits` to
+/// use it as a `Transmitter`.
+///
+/// A 74HC595 output can't be used: shifting 8 bits and latching them to
+/// change the level takes longer than the whole WS2812 bit, so the shift
+/// register of the SPI peripheral itself is the fastest path, see `SpiEncoder`.
+pub trait BitSink {
+    fn send_bit(&mut self, bit: bool);
+
+    /// Hold the line low for `us` microseconds so the strip displays the frame.
+    fn reset(&mut self, us: u32);
+}
+
+/// Transmitter sending each byte to a `BitSink`.
+pub struct Bits<S: BitSink>(pub S);
+
+impl<S: BitSink> Transmitter for Bits<S> {
+    fn send_byte(&mut self, byte: u8) {
+        for i in (0..8).rev() {
+            self.0.send_bit(byte >> i & 1 == 1);
+        }
+    }
+
+    fn reset(&mut self, us: u32) {
+        self.0.reset(us);
+    }
+}
+
+/// Duration of an SPI bit at 2.4 MHz, in nanoseconds.
+const SPI_BIT_NS: u32 = 417;
+
+/// WS2812 signal encoded on the MOSI pin of an SPI peripheral clocked at
+/// 2.4 MHz, each bit becoming 3 SPI bits (`100` for 0, `110` for 1, of
+/// about 417 ns), the bytes being written with `write`:
+///
+/// ```ignore
+/// let (mut spi, _) = Spi::new(dp.SPI, sck, mosi, miso, cs, settings);
+/// let sink = SpiEncoder::new(|byte| { nb::block!(spi.send(byte)).ok(); });
+/// let mut led_strip = LedStrip::with_transmitter(NUM_LEDS, Bits(sink));
+/// ```
+///
+/// The MOSI pin must idle low between the bytes, and the bytes must follow
+/// each other without gaps longer than a few microseconds.
+pub struct SpiEncoder<W: FnMut(u8)> {
+    write: W,
+    pending: u8,
+    pending_bits: u8,
+}
+
+impl<W: FnMut(u8)> SpiEncoder<W> {
+    pub fn new(write: W) -> SpiEncoder<W> {
+        SpiEncoder {
+            write,
+            pending: 0,
+            pending_bits: 0,
+        }
+    }
+
+    fn push(&mut self, bit: bool) {
+        self.pending = self.pending << 1 | u8::from(bit);
+        self.pending_bits += 1;
+
+        if self.pending_bits == 8 {
+            (self.write)(self.pending);
+            self.pending = 0;
+            self.pending_bits = 0;
+        }
+    }
+}
+
+impl<W: FnMut(u8)> BitSink for SpiEncoder<W> {
+    fn send_bit(&mut self, bit: bool) {
+        self.push(true);
+        self.push(bit);
+        self.push(false);
+    }
+
+    /// Send low SPI bytes for `us`, no delay being needed.
+    fn reset(&mut self, us: u32) {
+        while self.pending_bits != 0 {
+            self.push(false);
+        }
+
+        for _ in 0..(us * 1_000).div_ceil(8 * SPI_BIT_NS) {
+            (self.write)(0);
+        }
+    }
+}
+
+/// WS2812 signal encoded by a UART at 2.4 Mbaud, 7 data bits, no parity,
+/// one stop bit and its TX output inverted (in the peripheral or with a
+/// transistor), 3 bits being sent per UART frame: the start bit becomes the
+/// high part of the first one and the stop bit the low part of the last one.
+///
+/// The line then idles low, `delay_us` waiting for the latch: `write`
+/// should only return once the byte is sent, or the latch will be shortened
+/// by the bytes still in the FIFO.
+pub struct UartEncoder<W: FnMut(u8)> {
+    write: W,
+    delay_us: fn(u32),
+    pending: u8,
+    pending_bits: u8,
+}
+
+impl<W: FnMut(u8)> UartEncoder<W> {
+    pub fn new(write: W, delay_us: fn(u32)) -> UartEncoder<W> {
+        UartEncoder {
+            write,
+            delay_us,
+            pending: 0,
+            pending_bits: 0,
+        }
+    }
+
+    /// UART byte sending `bits`, the first in the lowest bit, data bits
+    /// being inverted on the line: `!b0 1 0 !b1 1 0 !b2`, lowest first.
+    fn encode(bits: u8) -> u8 {
+        let inverted = !bits;
+
+        0b001_0010 | inverted & 1 | (inverted >> 1 & 1) << 3 | (inverted >> 2 & 1) << 6
+    }
+}
+
+impl<W: FnMut(u8)> BitSink for UartEncoder<W> {
+    fn send_bit(&mut self, bit: bool) {
+        self.pending |= u8::from(bit) << self.pending_bits;
+        self.pending_bits += 1;
+
+        if self.pending_bits == 3 {
+            (self.write)(Self::encode(self.pending));
+            self.pending = 0;
+            self.pending_bits = 0;
+        }
+    }
+
+    fn reset(&mut self, us: u32) {
+        // Frames are always a multiple of 3 bits (24 per LED), padding only
+        // matters for partial bytes, read by no LED.
+        if self.pending_bits != 0 {
+            (self.write)(Self::encode(self.pending));
+            self.pending = 0;
+            self.pending_bits = 0;
+        }
+
+        (self.delay_us)(us);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use heapless::Vec;
+    use crate::bits::{Bits, SpiEncoder, UartEncoder};
+    use crate::led::{Color, LedStrip};
+
+    #[test]
+    fn spi() {
+        let mut bytes: Vec<u8, 32> = Vec::new();
+        let sink = SpiEncoder::new(|byte| { bytes.push(byte).ok(); });
+        let mut led_strip = LedStrip::with_transmitter(1, Bits(sink));
+
+        led_strip.color(Color::RGB(0, 0x80, 0xFF));
+        led_strip.rest(50);
+        drop(led_strip);
+
+        assert_eq!(bytes[..9], [0xD2, 0x49, 0x24, 0x92, 0x49, 0x24, 0xDB, 0x6D, 0xB6]);
+        assert_eq!(bytes.len(), 9 + 15);
+        assert!(bytes[9..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn uart() {
+        let mut bytes: Vec<u8, 16> = Vec::new();
+        let sink = UartEncoder::new(|byte| { bytes.push(byte).ok(); }, |_| {});
+        let mut led_strip = LedStrip::with_transmitter(1, Bits(sink));
+
+        led_strip.color(Color::RGB(0, 0x80, 0xFF));
+        led_strip.rest(50);
+        drop(led_strip);
+
+        assert_eq!(bytes[..], [0x5A, 0x5B, 0x5B, 0x5B, 0x5B, 0x13, 0x12, 0x12]);
+    }
+}
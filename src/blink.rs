@@ -0,0 +1,95 @@
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Status or error code shown as `count` blinks of `color` followed by a pause,
+/// e.g. `BlinkCode::new(Color::Red, 3)` for a sensor fault.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BlinkCode {
+    color: Color,
+    count: u8,
+    on_ms: u16,
+    off_ms: u16,
+    pause_ms: u16,
+}
+
+impl BlinkCode {
+    pub const fn new(color: Color, count: u8) -> BlinkCode {
+        BlinkCode {
+            color,
+            count,
+            on_ms: 200,
+            off_ms: 300,
+            pause_ms: 1500,
+        }
+    }
+
+    /// Duration of each blink, of the gap between blinks and of the pause before repeating.
+    pub const fn timing(mut self, on_ms: u16, off_ms: u16, pause_ms: u16) -> BlinkCode {
+        self.on_ms = on_ms;
+        self.off_ms = off_ms;
+        self.pause_ms = pause_ms;
+
+        self
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn count(&self) -> u8 {
+        self.count
+    }
+
+    /// Duration of the full pattern, pause included.
+    pub fn period(&self) -> u32 {
+        u32::from(self.count) * (u32::from(self.on_ms) + u32::from(self.off_ms)) + u32::from(self.pause_ms)
+    }
+
+    /// Whether the LED is lit at `now_ms` (e.g. milliseconds since boot).
+    pub fn is_on(&self, now_ms: u32) -> bool {
+        let blink = u32::from(self.on_ms) + u32::from(self.off_ms);
+        let time = now_ms % self.period().max(1);
+
+        time < u32::from(self.count) * blink && time % blink < u32::from(self.on_ms)
+    }
+
+    /// Color of the status pixel at `now_ms`.
+    pub fn color_at(&self, now_ms: u32) -> Color {
+        match self.is_on(now_ms) {
+            true => self.color,
+            false => Color::Black,
+        }
+    }
+
+    /// Send only the first pixel and latch, the other LEDs keep what they last received.
+    pub fn show<T: Transmitter>(&self, led_strip: &mut LedStrip<T>, now_ms: u32) {
+        led_strip.color(self.color_at(now_ms));
+        led_strip.rest(300);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blink::BlinkCode;
+    use crate::led::{Color, LedStrip};
+    use crate::mock::MockPin;
+
+    #[test]
+    fn pattern() {
+        let code = BlinkCode::new(Color::Red, 3).timing(100, 100, 400);
+        let lit = (0..1000).step_by(50).filter(|&time| code.is_on(time)).count();
+
+        assert_eq!(code.period(), 1000);
+        assert_eq!(lit, 6);
+        assert!(code.is_on(0));
+        assert!(!code.is_on(100));
+        assert!(code.is_on(450));
+        assert!(!code.is_on(500));
+        assert!(code.is_on(1020));
+
+        let mut led_strip = LedStrip::with_transmitter(8, MockPin::<64>::new());
+        code.show(&mut led_strip, 1020);
+
+        assert!(led_strip.transmitter().bytes().eq([0, 255, 0]));
+    }
+}
@@ -0,0 +1,271 @@
+use core::ops::Range;
+
+use crate::error::LedError;
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Dim all `pixels` by `amount` (255 turning them off), for trails and effects
+/// where lit pixels fade out over the next frames.
+pub fn fade_to_black_by(pixels: &mut [Color], amount: u8) {
+    for pixel in pixels {
+        *pixel = *pixel * (255 - amount);
+    }
+}
+
+/// Strip keeping the color of its `N` pixels in memory (3 bytes each), so
+/// pixels can be set one by one, then sent with `show()`.
+pub struct BufferedStrip<T: Transmitter, const N: usize> {
+    strip: LedStrip<T>,
+    pixels: [Color; N],
+}
+
+impl<T: Transmitter, const N: usize> BufferedStrip<T, N> {
+    pub fn new(strip: LedStrip<T>) -> BufferedStrip<T, N> {
+        BufferedStrip {
+            strip,
+            pixels: [Color::Black; N],
+        }
+    }
+
+    pub fn strip(&self) -> &LedStrip<T> {
+        &self.strip
+    }
+
+    pub fn strip_mut(&mut self) -> &mut LedStrip<T> {
+        &mut self.strip
+    }
+
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    pub fn pixels(&self) -> &[Color; N] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [Color; N] {
+        &mut self.pixels
+    }
+
+    pub fn set_pixel(&mut self, led_index: usize, color: Color) -> Result<(), LedError> {
+        let pixel = self.pixels.get_mut(led_index).ok_or(LedError::OutOfRange { index: led_index, len: N })?;
+        *pixel = color;
+
+        Ok(())
+    }
+
+    pub fn get_pixel(&self, led_index: usize) -> Result<Color, LedError> {
+        self.pixels.get(led_index).copied().ok_or(LedError::OutOfRange { index: led_index, len: N })
+    }
+
+    pub fn fill(&mut self, color: Color) {
+        self.pixels.fill(color);
+    }
+
+    /// Set the pixels from `range.start` to `range.end` (excluded), nothing
+    /// is changed if the range goes past the end of the buffer.
+    pub fn fill_range(&mut self, range: Range<usize>, color: Color) -> Result<(), LedError> {
+        if range.end > N {
+            return Err(LedError::OutOfRange { index: range.end - 1, len: N });
+        }
+
+        for pixel in self.pixels.get_mut(range).unwrap_or_default() {
+            *pixel = color;
+        }
+
+        Ok(())
+    }
+
+    /// Dim all pixels by `amount`, see `fade_to_black_by()`.
+    pub fn fade(&mut self, amount: u8) {
+        fade_to_black_by(&mut self.pixels, amount);
+    }
+
+    /// Copy the buffer, e.g. before drawing a notification over it.
+    pub fn snapshot(&self, snapshot: &mut [Color; N]) {
+        *snapshot = self.pixels;
+    }
+
+    /// Go back to the image saved by `snapshot()`.
+    pub fn restore(&mut self, snapshot: &[Color; N]) {
+        self.pixels = *snapshot;
+    }
+
+    /// Send the buffer and latch, LEDs past the buffer get black.
+    pub fn show(&mut self) {
+        let pixels = &self.pixels;
+
+        self.strip.each(|led_index| pixels.get(led_index).copied().unwrap_or(Color::Black));
+        self.strip.latch();
+    }
+
+    /// Send only the first `led_count` pixels and latch, the LEDs after them
+    /// keep what they last received, e.g. to refresh a status LED at the
+    /// start of a long strip without sending all of it.
+    pub fn show_first(&mut self, led_count: usize) {
+        for &color in self.pixels.iter().take(led_count.min(self.strip.led_count())) {
+            self.strip.color(color);
+        }
+
+        self.strip.latch();
+    }
+}
+
+/// Strip with a front buffer, the image sent by `show()`, and a back buffer
+/// where the next frame is drawn, so a partly drawn frame is never sent.
+///
+/// After `swap_and_show()`, the back buffer holds the frame before the one
+/// shown, redraw it entirely or `copy_front()` first.
+pub struct DoubleBufferedStrip<T: Transmitter, const N: usize> {
+    front: BufferedStrip<T, N>,
+    back: [Color; N],
+}
+
+impl<T: Transmitter, const N: usize> DoubleBufferedStrip<T, N> {
+    pub fn new(strip: LedStrip<T>) -> DoubleBufferedStrip<T, N> {
+        DoubleBufferedStrip {
+            front: BufferedStrip::new(strip),
+            back: [Color::Black; N],
+        }
+    }
+
+    pub fn strip(&self) -> &LedStrip<T> {
+        self.front.strip()
+    }
+
+    pub fn strip_mut(&mut self) -> &mut LedStrip<T> {
+        self.front.strip_mut()
+    }
+
+    pub fn front(&self) -> &[Color; N] {
+        self.front.pixels()
+    }
+
+    pub fn back(&self) -> &[Color; N] {
+        &self.back
+    }
+
+    pub fn back_mut(&mut self) -> &mut [Color; N] {
+        &mut self.back
+    }
+
+    /// Set a pixel of the back buffer.
+    pub fn set_pixel(&mut self, led_index: usize, color: Color) -> Result<(), LedError> {
+        let pixel = self.back.get_mut(led_index).ok_or(LedError::OutOfRange { index: led_index, len: N })?;
+        *pixel = color;
+
+        Ok(())
+    }
+
+    /// Start the next frame from the one currently shown.
+    pub fn copy_front(&mut self) {
+        self.back = *self.front.pixels();
+    }
+
+    /// Send the front buffer again, e.g. after a glitch or to refresh
+    /// LEDs that were unplugged.
+    pub fn show(&mut self) {
+        self.front.show();
+    }
+
+    /// Make the back buffer the front one and send it.
+    pub fn swap_and_show(&mut self) {
+        core::mem::swap(self.front.pixels_mut(), &mut self.back);
+        self.front.show();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::{BufferedStrip, DoubleBufferedStrip};
+    use crate::error::LedError;
+    use crate::led::{Color, LedStrip};
+    use crate::mock::MockPin;
+
+    #[test]
+    fn pixels() {
+        let mut strip = BufferedStrip::<_, 3>::new(LedStrip::with_transmitter(3, MockPin::<256>::new()));
+
+        assert_eq!(strip.set_pixel(1, Color::Red), Ok(()));
+        assert_eq!(strip.set_pixel(3, Color::Red), Err(LedError::OutOfRange { index: 3, len: 3 }));
+        assert_eq!(strip.get_pixel(1), Ok(Color::Red));
+        assert_eq!(strip.get_pixel(7), Err(LedError::OutOfRange { index: 7, len: 3 }));
+
+        strip.show();
+
+        assert!(strip.strip().transmitter().bytes().eq([0, 0, 0, 0, 255, 0, 0, 0, 0]));
+        assert!(strip.strip().is_latched());
+    }
+
+    #[test]
+    fn fill() {
+        let mut strip = BufferedStrip::<_, 4>::new(LedStrip::with_transmitter(4, MockPin::<8>::new()));
+
+        strip.fill(Color::Blue);
+        assert_eq!(strip.fill_range(1..3, Color::Red), Ok(()));
+        assert_eq!(strip.fill_range(2..5, Color::Green), Err(LedError::OutOfRange { index: 4, len: 4 }));
+        assert_eq!(strip.pixels(), &[Color::Blue, Color::Red, Color::Red, Color::Blue]);
+
+        assert_eq!(strip.fill_range(3..3, Color::Green), Ok(()));
+        assert_eq!(strip.get_pixel(3), Ok(Color::Blue));
+    }
+
+    #[test]
+    fn show_first() {
+        let mut strip = BufferedStrip::<_, 8>::new(LedStrip::with_transmitter(8, MockPin::<256>::new()));
+
+        strip.fill(Color::Green);
+        strip.show_first(2);
+        strip.show_first(0);
+
+        assert!(strip.strip().transmitter().bytes().eq([255, 0, 0, 255, 0, 0]));
+        assert_eq!(strip.strip().transmitter().latches().count(), 1);
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut strip = BufferedStrip::<_, 3>::new(LedStrip::with_transmitter(3, MockPin::<8>::new()));
+        let mut snapshot = [Color::Black; 3];
+
+        strip.set_pixel(0, Color::Orange).unwrap();
+        strip.snapshot(&mut snapshot);
+        strip.fill(Color::White);
+        strip.restore(&snapshot);
+
+        assert_eq!(strip.pixels(), &[Color::Orange, Color::Black, Color::Black]);
+    }
+
+    #[test]
+    fn double_buffering() {
+        let mut strip = DoubleBufferedStrip::<_, 2>::new(LedStrip::with_transmitter(2, MockPin::<512>::new()));
+
+        strip.set_pixel(0, Color::Red).unwrap();
+        strip.show();
+        assert_eq!(strip.front(), &[Color::Black; 2]);
+
+        strip.swap_and_show();
+        assert_eq!(strip.front(), &[Color::Red, Color::Black]);
+        assert_eq!(strip.back(), &[Color::Black; 2]);
+
+        strip.copy_front();
+        strip.set_pixel(1, Color::Blue).unwrap();
+        strip.swap_and_show();
+        assert_eq!(strip.front(), &[Color::Red, Color::Blue]);
+
+        assert!(strip.strip().transmitter().bytes().skip(6).eq([0, 255, 0, 0, 0, 0, 0, 255, 0, 0, 0, 255]));
+        assert_eq!(strip.strip().transmitter().latches().count(), 3);
+    }
+
+    #[test]
+    fn fade() {
+        let mut strip = BufferedStrip::<_, 2>::new(LedStrip::with_transmitter(2, MockPin::<8>::new()));
+
+        strip.fill(Color::RGB(200, 100, 0));
+        strip.fade(127);
+
+        assert_eq!(strip.pixels(), &[Color::RGB(100, 50, 0); 2]);
+    }
+}
@@ -0,0 +1,120 @@
+/// Time the input must stay stable to be taken into account, in milliseconds.
+pub const DEBOUNCE_MS: u32 = 20;
+
+/// Time the button must be held to send a `LongPress`, in milliseconds.
+pub const LONG_PRESS_MS: u32 = 800;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ButtonEvent {
+    /// Released before the long press delay.
+    Press,
+    /// Held for the long press delay, sent once while still held, no `Press` follows.
+    LongPress,
+}
+
+/// Debounced push button, updated with its level and the current time.
+pub struct Button {
+    raw: bool,
+    pressed: bool,
+    changed_at: u32,
+    pressed_at: u32,
+    long_press_sent: bool,
+    long_press_ms: u32,
+}
+
+impl Button {
+    pub fn new() -> Button {
+        Button {
+            raw: false,
+            pressed: false,
+            changed_at: 0,
+            pressed_at: 0,
+            long_press_sent: false,
+            long_press_ms: LONG_PRESS_MS,
+        }
+    }
+
+    pub fn long_press(mut self, milliseconds: u32) -> Button {
+        self.long_press_ms = milliseconds;
+
+        self
+    }
+
+    /// Debounced state.
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    /// Update with the button level (`true` when pressed, i.e. low with a
+    /// pull-up) and a millisecond timestamp, call it at least every few ms.
+    pub fn update(&mut self, pressed: bool, now_ms: u32) -> Option<ButtonEvent> {
+        if pressed != self.raw {
+            self.raw = pressed;
+            self.changed_at = now_ms;
+        }
+
+        if self.raw != self.pressed && now_ms.wrapping_sub(self.changed_at) >= DEBOUNCE_MS {
+            self.pressed = self.raw;
+
+            if self.pressed {
+                self.pressed_at = now_ms;
+                self.long_press_sent = false;
+            } else if !self.long_press_sent {
+                return Some(ButtonEvent::Press);
+            }
+        }
+
+        if self.pressed && !self.long_press_sent && now_ms.wrapping_sub(self.pressed_at) >= self.long_press_ms {
+            self.long_press_sent = true;
+
+            return Some(ButtonEvent::LongPress);
+        }
+
+        None
+    }
+}
+
+impl Default for Button {
+    fn default() -> Self {
+        Button::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::button::{Button, ButtonEvent};
+
+    fn run(button: &mut Button, levels: &[(bool, u32)], time: &mut u32) -> [Option<ButtonEvent>; 2] {
+        let mut events = [None; 2];
+        let mut count = 0;
+
+        for &(pressed, duration) in levels {
+            for _ in 0..duration {
+                if let Some(event) = button.update(pressed, *time) {
+                    events[count] = Some(event);
+                    count += 1;
+                }
+
+                *time += 1;
+            }
+        }
+
+        events
+    }
+
+    #[test]
+    fn events() {
+        let mut button = Button::new().long_press(500);
+        let mut time = 0;
+
+        assert_eq!(run(&mut button, &[(true, 5), (false, 5), (true, 2), (false, 50)], &mut time), [None; 2]);
+        assert_eq!(
+            run(&mut button, &[(true, 100), (false, 3), (true, 100), (false, 50)], &mut time),
+            [Some(ButtonEvent::Press), None],
+        );
+        assert_eq!(run(&mut button, &[(true, 600)], &mut time), [Some(ButtonEvent::LongPress), None]);
+        assert!(button.is_pressed());
+        assert_eq!(run(&mut button, &[(false, 50)], &mut time), [None; 2]);
+        assert!(!button.is_pressed());
+    }
+}
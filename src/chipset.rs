@@ -0,0 +1,425 @@
+use core::fmt;
+
+use crate::error::LedError;
+use crate::led::{set_low_for, DataPin, Inverted, Transmitter, LATCH_TIME};
+
+/// Clock of the board selected by the cargo features, 8 MHz for the
+/// ATtiny85 Trinket, 16 MHz for the Uno, Mega 2560, Leonardo and Trinket Pro.
+#[cfg(feature = "trinket")]
+pub const CPU_HZ: u32 = 8_000_000;
+#[cfg(not(feature = "trinket"))]
+pub const CPU_HZ: u32 = 16_000_000;
+
+/// Bit timing of a clockless chipset, in pin toggles (about 208 ns each on
+/// a 16 MHz AVR, so a 1.25 µs bit is 6 toggles).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Timing {
+    /// Toggles the line stays high for a 0.
+    pub zero_high: u8,
+    /// Toggles the line stays high for a 1.
+    pub one_high: u8,
+    /// Toggles of a whole bit.
+    pub period: u8,
+}
+
+impl Timing {
+    /// WS2812B, SK6812 and compatibles, 800 kHz.
+    pub const WS2812: Timing = Timing { zero_high: 1, one_high: 3, period: 6 };
+    /// TM1814, 800 kHz with a shorter 0.
+    pub const TM1814: Timing = Timing { zero_high: 2, one_high: 4, period: 6 };
+    /// UCS1903 (and UCS1903B in low speed mode), 400 kHz.
+    pub const UCS1903: Timing = Timing { zero_high: 2, one_high: 10, period: 12 };
+    /// WS2812B on an 8 MHz CPU, toggles being twice as long.
+    pub const WS2812_8MHZ: Timing = Timing { zero_high: 1, one_high: 2, period: 3 };
+    /// WS2812B timing the pins send with at `CPU_HZ`.
+    #[cfg(feature = "trinket")]
+    pub const NATIVE: Timing = Timing::WS2812_8MHZ;
+    #[cfg(not(feature = "trinket"))]
+    pub const NATIVE: Timing = Timing::WS2812;
+
+    /// Duration of a pin toggle at `cpu_hz`, a toggle taking about 3.3 cycles.
+    pub const fn toggle_ns(cpu_hz: u32) -> u32 {
+        (10_000_000_000 / (3 * cpu_hz as u64)) as u32
+    }
+
+    /// Whether the timing fits the chip `spec` on a CPU running at `cpu_hz`.
+    pub const fn check(&self, spec: &TimingSpec, cpu_hz: u32) -> Result<(), TimingError> {
+        self.check_toggle_ns(spec, Timing::toggle_ns(cpu_hz))
+    }
+
+    /// Shortest timing fitting the chip `spec` with toggles lasting
+    /// `toggle_ns`, e.g. as measured by `measure_toggle_ns()`.
+    pub const fn fit(spec: &TimingSpec, toggle_ns: u32) -> Result<Timing, TimingError> {
+        let zero_high = toggles(spec.zero_high_ns.0, toggle_ns);
+        let one_high = toggles(spec.one_high_ns.0, toggle_ns);
+        let period = toggles(spec.period_ns.0, toggle_ns);
+        let timing = Timing {
+            zero_high,
+            one_high,
+            period: match period > one_high {
+                true => period,
+                false => one_high.saturating_add(1),
+            },
+        };
+
+        match timing.check_toggle_ns(spec, toggle_ns) {
+            Ok(()) => Ok(timing),
+            Err(error) => Err(error),
+        }
+    }
+
+    const fn check_toggle_ns(&self, spec: &TimingSpec, toggle_ns: u32) -> Result<(), TimingError> {
+        if !within(self.zero_high, toggle_ns, spec.zero_high_ns) {
+            return Err(TimingError::ZeroHigh);
+        }
+
+        if !within(self.one_high, toggle_ns, spec.one_high_ns) {
+            return Err(TimingError::OneHigh);
+        }
+
+        if !within(self.period, toggle_ns, spec.period_ns) {
+            return Err(TimingError::Period);
+        }
+
+        Ok(())
+    }
+
+    /// Fail to compile when used in a `const` and the timing doesn't fit the
+    /// chip, see `assert_timing!`.
+    pub const fn assert_achievable(&self, spec: &TimingSpec, cpu_hz: u32) {
+        match self.check(spec, cpu_hz) {
+            Ok(()) => {},
+            Err(TimingError::ZeroHigh) => panic!("0 bits high time out of the chip tolerance at this CPU frequency"),
+            Err(TimingError::OneHigh) => panic!("1 bits high time out of the chip tolerance at this CPU frequency"),
+            Err(TimingError::Period) => panic!("bit period out of the chip tolerance at this CPU frequency"),
+        }
+    }
+}
+
+/// Toggles needed to last at least `ns`.
+const fn toggles(ns: u16, toggle_ns: u32) -> u8 {
+    let toggles = (ns as u32).div_ceil(if toggle_ns == 0 { 1 } else { toggle_ns });
+
+    if toggles > u8::MAX as u32 { u8::MAX } else { toggles as u8 }
+}
+
+const fn within(toggles: u8, toggle_ns: u32, (min, max): (u16, u16)) -> bool {
+    let ns = toggles as u32 * toggle_ns;
+
+    ns >= min as u32 && ns <= max as u32
+}
+
+/// Pulse durations a chip accepts, in nanoseconds (minimum, maximum).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimingSpec {
+    pub zero_high_ns: (u16, u16),
+    pub one_high_ns: (u16, u16),
+    pub period_ns: (u16, u16),
+}
+
+impl TimingSpec {
+    pub const WS2812: TimingSpec = TimingSpec {
+        zero_high_ns: (150, 500),
+        one_high_ns: (550, 1_000),
+        period_ns: (1_000, 6_000),
+    };
+    pub const TM1814: TimingSpec = TimingSpec {
+        zero_high_ns: (250, 450),
+        one_high_ns: (600, 900),
+        period_ns: (1_150, 6_000),
+    };
+    pub const UCS1903: TimingSpec = TimingSpec {
+        zero_high_ns: (350, 650),
+        one_high_ns: (1_850, 2_150),
+        period_ns: (2_350, 6_000),
+    };
+}
+
+/// Pulse of a `Timing` out of the chip tolerance.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TimingError {
+    ZeroHigh,
+    OneHigh,
+    Period,
+}
+
+impl fmt::Display for TimingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimingError::ZeroHigh => f.write_str("0 bits high time out of the chip tolerance"),
+            TimingError::OneHigh => f.write_str("1 bits high time out of the chip tolerance"),
+            TimingError::Period => f.write_str("bit period out of the chip tolerance"),
+        }
+    }
+}
+
+/// Check at compile time that a timing works for a chip at the CPU frequency,
+/// e.g. `assert_timing!(Timing::UCS1903, TimingSpec::UCS1903, 16_000_000);`.
+#[macro_export]
+macro_rules! assert_timing {
+    ($timing:expr, $spec:expr, $cpu_hz:expr) => {
+        const _: () = $timing.assert_achievable(&$spec, $cpu_hz);
+    };
+}
+
+assert_timing!(Timing::NATIVE, TimingSpec::WS2812, CPU_HZ);
+
+pub fn send_timed_byte<P: DataPin>(led: &mut P, byte: u8, timing: Timing) {
+    for i in (0..8).rev() {
+        let high = match byte >> i & 1 {
+            0 => timing.zero_high,
+            _ => timing.one_high,
+        };
+
+        for _ in 0..high {
+            led.set_high();
+        }
+
+        for _ in high..timing.period {
+            led.set_low();
+        }
+    }
+}
+
+/// Bytes sent by `measure_toggle_ns()`, about 850 µs on a 16 MHz AVR.
+const CALIBRATION_BYTES: u32 = 64;
+
+/// Measure the actual duration of a pin toggle in the bit loop with `micros`
+/// (e.g. a timer counting microseconds), whatever the compiler output and
+/// the clock accuracy. Black pixels are sent meanwhile then latched.
+pub fn measure_toggle_ns<P: DataPin>(led: &mut P, micros: fn() -> u32) -> u32 {
+    let timing = Timing { zero_high: 1, one_high: 1, period: 8 };
+    let start = micros();
+
+    for _ in 0..CALIBRATION_BYTES {
+        send_timed_byte(led, 0, timing);
+    }
+
+    let elapsed_us = micros().wrapping_sub(start);
+    set_low_for(led, LATCH_TIME * 1_000);
+
+    (u64::from(elapsed_us) * 1_000 / u64::from(CALIBRATION_BYTES * 8 * timing.period as u32)) as u32
+}
+
+/// Clockless strip with its own bit timing, e.g. UCS1903 strips
+/// (usually in RGB order):
+///
+/// ```ignore
+/// let mut led_strip = LedStrip::with_transmitter(30, Clockless::new(pins.d4.into_output(), Timing::UCS1903));
+/// led_strip.set_order(ColorOrder::Rgb);
+/// ```
+pub struct Clockless<P: DataPin> {
+    pin: P,
+    timing: Timing,
+}
+
+impl<P: DataPin> Clockless<P> {
+    pub fn new(pin: P, timing: Timing) -> Clockless<P> {
+        Clockless {
+            pin,
+            timing,
+        }
+    }
+
+    /// Strip whose timing is checked against the chip `spec` at `cpu_hz`,
+    /// e.g. when both are only known at runtime.
+    pub fn checked(pin: P, timing: Timing, spec: &TimingSpec, cpu_hz: u32) -> Result<Clockless<P>, LedError> {
+        timing.check(spec, cpu_hz)?;
+
+        Ok(Clockless::new(pin, timing))
+    }
+
+    /// Strip with the shortest timing fitting the chip `spec`, for the toggle
+    /// duration measured on the pin, see `measure_toggle_ns()`.
+    pub fn calibrated(mut pin: P, spec: &TimingSpec, micros: fn() -> u32) -> Result<Clockless<P>, LedError> {
+        let timing = Timing::fit(spec, measure_toggle_ns(&mut pin, micros))?;
+
+        Ok(Clockless::new(pin, timing))
+    }
+
+    pub fn pin(&self) -> &P {
+        &self.pin
+    }
+
+    pub fn timing(&self) -> Timing {
+        self.timing
+    }
+}
+
+impl<P: DataPin> Transmitter for Clockless<P> {
+    fn send_byte(&mut self, byte: u8) {
+        send_timed_byte(&mut self.pin, byte, self.timing);
+    }
+
+    fn reset(&mut self, us: u32) {
+        set_low_for(&mut self.pin, us * 1_000);
+    }
+}
+
+/// TM1814 RGBW strip, its line being inverted (idle high).
+///
+/// Each frame starts with the constant current of the W, R, G and B
+/// outputs, from 0 (6.5 mA) to 63 (38 mA), the white channel being the
+/// common part of red, green and blue. Channels are expected in RGB order
+/// (`ColorOrder::Rgb`).
+pub struct Tm1814<P: DataPin> {
+    pin: Inverted<P>,
+    currents: [u8; 4],
+    channels: [u8; 3],
+    channel: usize,
+    pixel_count: usize,
+}
+
+impl<P: DataPin> Tm1814<P> {
+    pub fn new(pin: P) -> Tm1814<P> {
+        Tm1814 {
+            pin: Inverted(pin),
+            currents: [25; 4],
+            channels: [0; 3],
+            channel: 0,
+            pixel_count: 0,
+        }
+    }
+
+    pub fn pin(&self) -> &P {
+        &self.pin.0
+    }
+
+    pub fn currents(&self) -> [u8; 4] {
+        self.currents
+    }
+
+    /// Current settings sent with the next frames, in W, R, G, B order.
+    pub fn set_currents(&mut self, currents: [u8; 4]) {
+        self.currents = currents.map(|current| current.min(63));
+    }
+}
+
+impl<P: DataPin> Transmitter for Tm1814<P> {
+    fn send_byte(&mut self, byte: u8) {
+        self.channels[self.channel] = byte;
+        self.channel += 1;
+
+        if self.channel < 3 {
+            return;
+        }
+
+        let led = &mut self.pin;
+
+        if self.pixel_count == 0 {
+            // The settings are followed by their complement as a check.
+            for byte in self.currents.into_iter().chain(self.currents.map(|current| !current)) {
+                send_timed_byte(led, byte, Timing::TM1814);
+            }
+        }
+
+        let [red, green, blue] = self.channels;
+        let white = red.min(green).min(blue);
+
+        for byte in [white, red - white, green - white, blue - white] {
+            send_timed_byte(led, byte, Timing::TM1814);
+        }
+
+        self.channel = 0;
+        self.pixel_count += 1;
+    }
+
+    fn reset(&mut self, us: u32) {
+        self.channel = 0;
+        self.pixel_count = 0;
+        set_low_for(&mut self.pin, us * 1_000);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chipset::{send_timed_byte, Clockless, Timing, TimingError, TimingSpec, Tm1814};
+    use crate::error::LedError;
+    use crate::led::{send_byte, Color, ColorOrder, LedStrip};
+    use crate::mock::{MockPin, Run};
+
+    #[test]
+    fn timing() {
+        let mut timed = MockPin::<64>::new();
+        let mut default = MockPin::<64>::new();
+
+        send_timed_byte(&mut timed, 0xA5, Timing::WS2812);
+        send_byte(&mut default, 0xA5);
+        assert_eq!(timed.runs(), default.runs());
+
+        let mut led_strip = LedStrip::with_transmitter(1, Clockless::new(MockPin::<64>::new(), Timing::UCS1903));
+        led_strip.set_order(ColorOrder::Rgb);
+        led_strip.color(Color::RGB(0x80, 0, 0));
+
+        assert_eq!(led_strip.transmitter().pin().runs()[..3], [
+            Run { high: true, ticks: 10, ns: 0 },
+            Run { high: false, ticks: 2, ns: 0 },
+            Run { high: true, ticks: 2, ns: 0 },
+        ]);
+    }
+
+    crate::assert_timing!(Timing::WS2812, TimingSpec::WS2812, 16_000_000);
+    crate::assert_timing!(Timing::TM1814, TimingSpec::TM1814, 16_000_000);
+    crate::assert_timing!(Timing::UCS1903, TimingSpec::UCS1903, 16_000_000);
+    crate::assert_timing!(Timing::WS2812_8MHZ, TimingSpec::WS2812, 8_000_000);
+
+    #[test]
+    fn tolerance() {
+        assert_eq!(Timing::toggle_ns(16_000_000), 208);
+        assert_eq!(Timing::WS2812.check(&TimingSpec::WS2812, 8_000_000), Err(TimingError::OneHigh));
+        assert_eq!(Timing::WS2812.check(&TimingSpec::WS2812, 24_000_000), Err(TimingError::ZeroHigh));
+        assert_eq!(Timing::UCS1903.check(&TimingSpec::WS2812, 16_000_000), Err(TimingError::OneHigh));
+        assert_eq!(Timing::WS2812_8MHZ.check(&TimingSpec::WS2812, 16_000_000), Err(TimingError::OneHigh));
+
+        let slow = Timing { zero_high: 1, one_high: 3, period: 30 };
+        assert_eq!(slow.check(&TimingSpec::WS2812, 16_000_000), Err(TimingError::Period));
+
+        let checked = Clockless::checked(MockPin::<4>::new(), Timing::WS2812, &TimingSpec::WS2812, 8_000_000);
+        assert_eq!(checked.err(), Some(LedError::UnsupportedTiming(TimingError::OneHigh)));
+    }
+
+    #[test]
+    fn calibration() {
+        use core::sync::atomic::{AtomicU32, Ordering};
+
+        static MICROS: AtomicU32 = AtomicU32::new(0);
+        let micros = || MICROS.fetch_add(852, Ordering::Relaxed);
+
+        assert_eq!(Timing::fit(&TimingSpec::WS2812, 208), Ok(Timing { zero_high: 1, one_high: 3, period: 5 }));
+        assert_eq!(Timing::fit(&TimingSpec::WS2812, 416), Ok(Timing { zero_high: 1, one_high: 2, period: 3 }));
+        assert_eq!(Timing::fit(&TimingSpec::TM1814, 500), Err(TimingError::ZeroHigh));
+
+        let clockless = Clockless::calibrated(MockPin::<64>::new(), &TimingSpec::WS2812, micros);
+        assert_eq!(clockless.map(|clockless| clockless.timing()).ok(), Some(Timing { zero_high: 1, one_high: 3, period: 5 }));
+    }
+
+    #[test]
+    fn tm1814() {
+        let mut tm1814 = Tm1814::new(MockPin::<512>::new());
+        tm1814.set_currents([0, 63, 70, 1]);
+
+        let mut led_strip = LedStrip::with_transmitter(1, tm1814);
+        led_strip.set_order(ColorOrder::Rgb);
+        led_strip.color(Color::RGB(200, 50, 10));
+        led_strip.rest(300);
+
+        let runs = led_strip.transmitter().pin().runs();
+
+        // Inverted: a 0 bit starts by pulling the line low for 2 toggles.
+        assert_eq!(runs[0], Run { high: false, ticks: 2, ns: 0 });
+        assert_eq!(runs.last(), Some(&Run { high: true, ticks: 5, ns: 300_000 }));
+
+        // A 1 is low for 4 toggles, keep the 12 bytes sent.
+        let bits = runs.iter().filter(|run| !run.high && run.ns == 0).map(|run| run.ticks > 2);
+        let mut bytes = [0u8; 12];
+
+        for (index, bit) in bits.enumerate() {
+            bytes[index / 8] = bytes[index / 8] << 1 | u8::from(bit);
+        }
+
+        assert_eq!(bytes, [0, 63, 63, 1, 255, 192, 192, 254, 10, 190, 40, 0]);
+    }
+}
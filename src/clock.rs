@@ -0,0 +1,137 @@
+use crate::layer::BlendMode;
+use crate::led::{Color, LedStrip, Transmitter};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Time {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+}
+
+impl Time {
+    pub fn new(hours: u8, minutes: u8, seconds: u8) -> Time {
+        Time {
+            hours,
+            minutes,
+            seconds,
+        }
+    }
+
+    /// Time of the day from a duration, e.g. milliseconds since midnight / 1000.
+    pub fn from_seconds(seconds: u32) -> Time {
+        Time {
+            hours: (seconds / 3600 % 24) as u8,
+            minutes: (seconds / 60 % 60) as u8,
+            seconds: (seconds % 60) as u8,
+        }
+    }
+}
+
+/// Clock drawn on a ring of LEDs (e.g. 12, 24 or 60), a hand lighting the
+/// LED it points to, overlapping hands being blended with `mode`.
+pub struct RingClock {
+    led_count: usize,
+    offset: usize,
+    hour: Option<Color>,
+    minute: Option<Color>,
+    second: Option<Color>,
+    markers: Option<Color>,
+    mode: BlendMode,
+}
+
+impl RingClock {
+    pub fn new(led_count: usize) -> RingClock {
+        RingClock {
+            led_count,
+            offset: 0,
+            hour: Some(Color::Red),
+            minute: Some(Color::Green),
+            second: Some(Color::Blue),
+            markers: None,
+            mode: BlendMode::Add,
+        }
+    }
+
+    /// Index of the LED at 12 o'clock.
+    pub fn offset(mut self, offset: usize) -> RingClock {
+        self.offset = offset;
+
+        self
+    }
+
+    /// Colors of the hour, minute and second hands, `None` to hide one.
+    pub fn hands(mut self, hour: Option<Color>, minute: Option<Color>, second: Option<Color>) -> RingClock {
+        self.hour = hour;
+        self.minute = minute;
+        self.second = second;
+
+        self
+    }
+
+    /// Color of the 12 hour markers, drawn below the hands.
+    pub fn markers(mut self, markers: Option<Color>) -> RingClock {
+        self.markers = markers;
+
+        self
+    }
+
+    pub fn mode(mut self, mode: BlendMode) -> RingClock {
+        self.mode = mode;
+
+        self
+    }
+
+    /// LED pointed at the given fraction of a turn, `position` out of `steps`.
+    fn led(&self, position: u32, steps: u32) -> usize {
+        (position % steps * self.led_count as u32 / steps) as usize
+    }
+
+    pub fn color(&self, time: Time, led_index: usize) -> Color {
+        let position = (led_index + self.led_count - self.offset % self.led_count) % self.led_count;
+        let minutes = u32::from(time.hours % 12) * 60 + u32::from(time.minutes);
+        let seconds = u32::from(time.minutes) * 60 + u32::from(time.seconds);
+        let hands = [
+            (self.hour, self.led(minutes, 720)),
+            (self.minute, self.led(seconds, 3600)),
+            (self.second, self.led(u32::from(time.seconds), 60)),
+        ];
+        let background = match self.markers {
+            Some(marker) if (position * 12).is_multiple_of(self.led_count) => marker,
+            _ => Color::Black,
+        };
+
+        hands.iter().fold(background, |color, &(hand, led)| match hand {
+            Some(hand) if led == position => self.mode.blend(hand, color),
+            _ => color,
+        })
+    }
+
+    pub fn show<T: Transmitter>(&self, led_strip: &mut LedStrip<T>, time: Time) {
+        led_strip.each(|led_index| self.color(time, led_index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::{RingClock, Time};
+    use crate::led::Color;
+
+    #[test]
+    fn hands() {
+        let clock = RingClock::new(60);
+        let time = Time::new(15, 30, 15);
+
+        assert_eq!(clock.color(time, 0), Color::Black);
+        assert_eq!(clock.color(time, 17), Color::RGB(255, 0, 0));
+        assert_eq!(clock.color(time, 30), Color::RGB(0, 255, 0));
+        assert_eq!(clock.color(time, 15), Color::RGB(0, 0, 255));
+        assert_eq!(Time::from_seconds(86_400 + 3_723), Time::new(1, 2, 3));
+
+        let clock = RingClock::new(12).offset(6).markers(Some(Color::RGB(8, 8, 8)));
+        let time = Time::new(0, 2, 4);
+
+        assert_eq!(clock.color(time, 6), Color::RGB(255, 255, 255));
+        assert_eq!(clock.color(time, 7), Color::RGB(8, 8, 8));
+    }
+}
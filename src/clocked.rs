@@ -0,0 +1,271 @@
+use crate::led::{DataPin, Transmitter};
+
+/// Byte encoding of a two-wire (data + clock) chipset, the bytes being
+/// shifted out most significant bit first.
+pub trait Encoder {
+    /// Bytes sent before the first pixel of a frame.
+    fn start_frame(&mut self, _write: &mut dyn FnMut(u8)) {}
+
+    /// Bytes of a pixel, `channels` being in the order set on the strip
+    /// (`LedStrip::set_order()`).
+    fn pixel(&mut self, channels: [u8; 3], write: &mut dyn FnMut(u8));
+
+    /// Bytes sent after the `pixel_count` pixels of a frame.
+    fn end_frame(&mut self, _pixel_count: usize, _write: &mut dyn FnMut(u8)) {}
+}
+
+/// APA102 / SK9822, with a 5-bit global brightness sent with each pixel,
+/// channels expected in BGR order.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Apa102 {
+    brightness: u8,
+}
+
+impl Apa102 {
+    /// `brightness` from 0 to 31.
+    pub fn new(brightness: u8) -> Apa102 {
+        Apa102 {
+            brightness: brightness.min(31),
+        }
+    }
+}
+
+impl Default for Apa102 {
+    fn default() -> Apa102 {
+        Apa102::new(31)
+    }
+}
+
+impl Encoder for Apa102 {
+    fn start_frame(&mut self, write: &mut dyn FnMut(u8)) {
+        [0; 4].into_iter().for_each(write);
+    }
+
+    fn pixel(&mut self, channels: [u8; 3], write: &mut dyn FnMut(u8)) {
+        write(0xE0 | self.brightness);
+        channels.into_iter().for_each(write);
+    }
+
+    fn end_frame(&mut self, pixel_count: usize, write: &mut dyn FnMut(u8)) {
+        // Data is delayed by half a clock per LED, push it through.
+        (0..pixel_count.div_ceil(16)).for_each(|_| write(0));
+    }
+}
+
+/// LPD8806, 7 bits per channel, channels expected in GRB order.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Lpd8806;
+
+impl Encoder for Lpd8806 {
+    fn pixel(&mut self, channels: [u8; 3], write: &mut dyn FnMut(u8)) {
+        channels.into_iter().for_each(|channel| write(0x80 | channel >> 1));
+    }
+
+    fn end_frame(&mut self, pixel_count: usize, write: &mut dyn FnMut(u8)) {
+        (0..pixel_count.div_ceil(32)).for_each(|_| write(0));
+    }
+}
+
+/// P9813 (e.g. Grove chainable LEDs), channels expected in BGR order.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct P9813;
+
+impl Encoder for P9813 {
+    fn start_frame(&mut self, write: &mut dyn FnMut(u8)) {
+        [0; 4].into_iter().for_each(write);
+    }
+
+    fn pixel(&mut self, channels: [u8; 3], write: &mut dyn FnMut(u8)) {
+        // Flag byte: 11 then the inverted 2 high bits of each channel.
+        let flag = channels.iter().fold(0b11, |flag, channel| flag << 2 | (!channel >> 6));
+
+        write(flag);
+        channels.into_iter().for_each(write);
+    }
+
+    fn end_frame(&mut self, _pixel_count: usize, write: &mut dyn FnMut(u8)) {
+        [0; 4].into_iter().for_each(write);
+    }
+}
+
+/// Two-wire strip, bit-banged on a data and a clock pin, used as the
+/// transmitter of a `LedStrip`:
+///
+/// ```ignore
+/// let spi = ClockedStrip::new(pins.d11.into_output(), pins.d13.into_output(), Apa102::default());
+/// let mut led_strip = LedStrip::with_transmitter(60, spi);
+/// led_strip.set_order(ColorOrder::Bgr);
+/// ```
+///
+/// Clocked strips don't need the line to rest to display a frame, `rest()`
+/// only waits.
+pub struct ClockedStrip<DATA: DataPin, CLK: DataPin, E: Encoder> {
+    data: DATA,
+    clock: CLK,
+    encoder: E,
+    channels: [u8; 3],
+    channel: usize,
+    pixel_count: usize,
+}
+
+impl<DATA: DataPin, CLK: DataPin, E: Encoder> ClockedStrip<DATA, CLK, E> {
+    pub fn new(data: DATA, clock: CLK, encoder: E) -> ClockedStrip<DATA, CLK, E> {
+        ClockedStrip {
+            data,
+            clock,
+            encoder,
+            channels: [0; 3],
+            channel: 0,
+            pixel_count: 0,
+        }
+    }
+
+    pub fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    pub fn encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Pixels sent since the start of the frame.
+    pub fn pixel_count(&self) -> usize {
+        self.pixel_count
+    }
+}
+
+/// Shift a byte out, most significant bit first, the chip reading the data
+/// line on the rising edge of the clock.
+fn shift_out<DATA: DataPin, CLK: DataPin>(data: &mut DATA, clock: &mut CLK, byte: u8) {
+    for i in (0..8).rev() {
+        match byte >> i & 1 {
+            0 => data.set_low(),
+            _ => data.set_high(),
+        }
+
+        clock.set_high();
+        clock.set_low();
+    }
+}
+
+impl<DATA: DataPin, CLK: DataPin, E: Encoder> Transmitter for ClockedStrip<DATA, CLK, E> {
+    fn send_byte(&mut self, byte: u8) {
+        self.channels[self.channel] = byte;
+        self.channel += 1;
+
+        if self.channel < 3 {
+            return;
+        }
+
+        let (data, clock) = (&mut self.data, &mut self.clock);
+        let mut write = |byte| shift_out(data, clock, byte);
+
+        if self.pixel_count == 0 {
+            self.encoder.start_frame(&mut write);
+        }
+
+        self.encoder.pixel(self.channels, &mut write);
+        self.channel = 0;
+        self.pixel_count += 1;
+    }
+
+    fn reset(&mut self, us: u32) {
+        if self.pixel_count > 0 {
+            let (data, clock) = (&mut self.data, &mut self.clock);
+
+            self.encoder.end_frame(self.pixel_count, &mut |byte| shift_out(data, clock, byte));
+        }
+
+        self.channel = 0;
+        self.pixel_count = 0;
+        self.data.set_low();
+        self.data.delay_ns(us * 1_000);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use crate::clocked::{Apa102, ClockedStrip, Encoder, Lpd8806, P9813};
+    use crate::led::{Color, ColorOrder, DataPin, LedStrip};
+
+    /// Data bits read on each rising edge of the clock.
+    #[derive(Default)]
+    struct Bus {
+        data: Cell<bool>,
+        bits: Cell<u128>,
+        count: Cell<u32>,
+    }
+
+    struct Data<'a>(&'a Bus);
+
+    struct Clock<'a>(&'a Bus);
+
+    impl DataPin for Data<'_> {
+        fn set_high(&mut self) {
+            self.0.data.set(true);
+        }
+
+        fn set_low(&mut self) {
+            self.0.data.set(false);
+        }
+
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    impl DataPin for Clock<'_> {
+        fn set_high(&mut self) {
+            let bus = self.0;
+
+            bus.bits.set(bus.bits.get() << 1 | u128::from(bus.data.get()));
+            bus.count.set(bus.count.get() + 1);
+        }
+
+        fn set_low(&mut self) {}
+
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    fn encode<E: Encoder>(encoder: &mut E, channels: [u8; 3]) -> ([u8; 4], usize) {
+        let mut bytes = [0; 4];
+        let mut len = 0;
+
+        encoder.pixel(channels, &mut |byte| {
+            bytes[len] = byte;
+            len += 1;
+        });
+
+        (bytes, len)
+    }
+
+    #[test]
+    fn encoders() {
+        assert_eq!(encode(&mut Apa102::new(40), [1, 2, 3]), ([0xFF, 1, 2, 3], 4));
+        assert_eq!(encode(&mut Apa102::new(3), [1, 2, 3]), ([0xE3, 1, 2, 3], 4));
+        assert_eq!(encode(&mut Lpd8806, [255, 0, 128]), ([0xFF, 0x80, 0xC0, 0], 3));
+        assert_eq!(encode(&mut P9813, [255, 0, 64]), ([0b1100_1110, 255, 0, 64], 4));
+    }
+
+    #[test]
+    fn frame() {
+        let bus = Bus::default();
+        let mut led_strip = LedStrip::with_transmitter(2, ClockedStrip::new(Data(&bus), Clock(&bus), Apa102::new(1)));
+        led_strip.set_order(ColorOrder::Bgr);
+
+        led_strip.each(|led_index| match led_index {
+            0 => Color::RGB(1, 2, 3),
+            _ => Color::Blue,
+        });
+
+        assert_eq!(led_strip.transmitter().pixel_count(), 2);
+
+        led_strip.rest(0);
+
+        // Start frame, 2 pixels, then 1 byte for the end frame.
+        assert_eq!(bus.count.get(), (4 + 4 + 4 + 1) * 8);
+        assert_eq!(bus.bits.get() as u64, 0x03_02_01_E1_FF_00_00_00);
+        assert_eq!((bus.bits.get() >> 64) as u32, 0xE1);
+        assert_eq!(led_strip.transmitter().pixel_count(), 0);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::led::Color;
+
+/// Color held at 16 bits per channel, for smooth gradients and long fades.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Color16 {
+    pub red: u16,
+    pub green: u16,
+    pub blue: u16,
+}
+
+impl Color16 {
+    pub fn new(red: u16, green: u16, blue: u16) -> Color16 {
+        Color16 {
+            red,
+            green,
+            blue,
+        }
+    }
+
+    /// Nearest 8-bit color, use `LedStrip::color16()` to get it dithered instead.
+    pub fn to_color(self) -> Color {
+        let round = |channel: u16| ((u32::from(channel) + 128) / 257) as u8;
+
+        Color::RGB(round(self.red), round(self.green), round(self.blue))
+    }
+
+    /// Scale all channels by `scale / 65535`.
+    pub fn scale(self, scale: u16) -> Color16 {
+        let channel = |value: u16| ((u32::from(value) * u32::from(scale)) / 65_535) as u16;
+
+        Color16::new(channel(self.red), channel(self.green), channel(self.blue))
+    }
+
+    /// Interpolate from `self` (0) to `other` (65535).
+    pub fn lerp(self, other: Color16, fraction: u16) -> Color16 {
+        let channel = |from: u16, to: u16| {
+            (i64::from(from) + (i64::from(to) - i64::from(from)) * i64::from(fraction) / 65_535) as u16
+        };
+
+        Color16::new(
+            channel(self.red, other.red),
+            channel(self.green, other.green),
+            channel(self.blue, other.blue),
+        )
+    }
+
+    pub fn mix(self, other: Color16) -> Color16 {
+        let channel = |first: u16, second: u16| ((u32::from(first) + u32::from(second)) / 2) as u16;
+
+        Color16::new(
+            channel(self.red, other.red),
+            channel(self.green, other.green),
+            channel(self.blue, other.blue),
+        )
+    }
+
+    /// Gamma correction approximated with an exponent of 2.
+    pub fn gamma(self) -> Color16 {
+        let channel = |value: u16| ((u32::from(value) * u32::from(value)) / 65_535) as u16;
+
+        Color16::new(channel(self.red), channel(self.green), channel(self.blue))
+    }
+}
+
+impl From<Color> for Color16 {
+    fn from(color: Color) -> Color16 {
+        let (red, green, blue) = color.to_rgb();
+
+        Color16::new(u16::from(red) * 257, u16::from(green) * 257, u16::from(blue) * 257)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color16::Color16;
+    use crate::led::Color;
+
+    #[test]
+    fn convert() {
+        assert_eq!(Color16::from(Color::Orange), Color16::new(65_535, 32_639, 0));
+        assert_eq!(Color16::from(Color::Orange).to_color().to_rgb(), (255, 127, 0));
+        assert_eq!(Color16::new(384, 385, 65_535).to_color().to_rgb(), (1, 1, 255));
+    }
+
+    #[test]
+    fn math() {
+        let black = Color16::new(0, 0, 0);
+        let white = Color16::new(65_535, 65_535, 65_535);
+
+        assert_eq!(black.lerp(white, 0), black);
+        assert_eq!(black.lerp(white, 65_535), white);
+        assert_eq!(white.lerp(black, 1_000), Color16::new(64_535, 64_535, 64_535));
+        assert_eq!(white.scale(32_768), Color16::new(32_768, 32_768, 32_768));
+        assert_eq!(white.mix(black), Color16::new(32_767, 32_767, 32_767));
+        assert_eq!(Color16::new(32_768, 0, 65_535).gamma(), Color16::new(16_384, 0, 65_535));
+    }
+}
@@ -0,0 +1,171 @@
+use core::fmt;
+use core::str;
+use heapless::Vec;
+use crate::led::{parse, Color, ColorParseError};
+
+/// Command of the serial interface, one per line, the letter can be
+/// followed by a space: `B128`, `C FF8800`, `E fire`, `F 30`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Command<'a> {
+    /// `B`: brightness, 0 to 255.
+    Brightness(u8),
+    /// `C`: color, as accepted by `Color::try_from_hex()`.
+    Color(Color),
+    /// `E`: effect name.
+    Effect(&'a str),
+    /// `F`: frame rate, in frames per second.
+    FrameRate(u8),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CommandError {
+    Empty,
+    UnknownCommand(u8),
+    MissingArgument,
+    InvalidNumber,
+    InvalidColor(ColorParseError),
+    /// Line longer than the buffer, or not valid UTF-8.
+    InvalidLine,
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Empty => f.write_str("empty command"),
+            CommandError::UnknownCommand(_) => f.write_str("unknown command, expected B, C, E or F"),
+            CommandError::MissingArgument => f.write_str("missing argument"),
+            CommandError::InvalidNumber => f.write_str("expected a number from 0 to 255"),
+            CommandError::InvalidColor(error) => error.fmt(f),
+            CommandError::InvalidLine => f.write_str("invalid line"),
+        }
+    }
+}
+
+pub fn parse_command(line: &str) -> Result<Command<'_>, CommandError> {
+    let line = line.trim();
+    let letter = *line.as_bytes().first().ok_or(CommandError::Empty)?;
+    let argument = line.get(1..).unwrap_or("").trim();
+
+    if argument.is_empty() {
+        return Err(match letter.to_ascii_uppercase() {
+            b'B' | b'C' | b'E' | b'F' => CommandError::MissingArgument,
+            _ => CommandError::UnknownCommand(letter),
+        });
+    }
+
+    let number = || argument.parse::<u8>().map_err(|_| CommandError::InvalidNumber);
+
+    match letter.to_ascii_uppercase() {
+        b'B' => Ok(Command::Brightness(number()?)),
+        b'C' => match parse(argument) {
+            Ok([red, green, blue]) => Ok(Command::Color(Color::RGB(red, green, blue))),
+            Err(error) => Err(CommandError::InvalidColor(error)),
+        },
+        b'E' => Ok(Command::Effect(argument)),
+        b'F' => Ok(Command::FrameRate(number()?)),
+        _ => Err(CommandError::UnknownCommand(letter)),
+    }
+}
+
+/// Line buffer fed one byte at a time (e.g. from the UART), parsing each
+/// line ended by `\n` (a `\r` before it is ignored) of up to `N` bytes.
+pub struct CommandLine<const N: usize> {
+    buffer: Vec<u8, N>,
+    overflowed: bool,
+    complete: bool,
+}
+
+impl<const N: usize> CommandLine<N> {
+    pub fn new() -> CommandLine<N> {
+        CommandLine {
+            buffer: Vec::new(),
+            overflowed: false,
+            complete: false,
+        }
+    }
+
+    /// Add the next byte, return the parsed command at the end of a non-empty line.
+    pub fn feed(&mut self, byte: u8) -> Option<Result<Command<'_>, CommandError>> {
+        if self.complete {
+            self.buffer.clear();
+            self.overflowed = false;
+            self.complete = false;
+        }
+
+        match byte {
+            b'\n' => {
+                self.complete = true;
+
+                if self.overflowed {
+                    return Some(Err(CommandError::InvalidLine));
+                }
+
+                match str::from_utf8(&self.buffer) {
+                    Ok(line) if line.trim().is_empty() => None,
+                    Ok(line) => Some(parse_command(line)),
+                    Err(_) => Some(Err(CommandError::InvalidLine)),
+                }
+            },
+            b'\r' => None,
+            _ => {
+                if self.buffer.push(byte).is_err() {
+                    self.overflowed = true;
+                }
+
+                None
+            },
+        }
+    }
+}
+
+impl<const N: usize> Default for CommandLine<N> {
+    fn default() -> Self {
+        CommandLine::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::{parse_command, Command, CommandError, CommandLine};
+    use crate::led::{Color, ColorParseError};
+
+    #[test]
+    fn commands() {
+        assert_eq!(parse_command("B128"), Ok(Command::Brightness(128)));
+        assert_eq!(parse_command("b 0"), Ok(Command::Brightness(0)));
+        assert_eq!(parse_command("C FF8800"), Ok(Command::Color(Color::RGB(255, 136, 0))));
+        assert_eq!(parse_command("C#0af"), Ok(Command::Color(Color::RGB(0, 170, 255))));
+        assert_eq!(parse_command("E fire "), Ok(Command::Effect("fire")));
+        assert_eq!(parse_command("F 30"), Ok(Command::FrameRate(30)));
+
+        assert_eq!(parse_command(" "), Err(CommandError::Empty));
+        assert_eq!(parse_command("X 1"), Err(CommandError::UnknownCommand(b'X')));
+        assert_eq!(parse_command("E"), Err(CommandError::MissingArgument));
+        assert_eq!(parse_command("B256"), Err(CommandError::InvalidNumber));
+        assert_eq!(parse_command("C 12"), Err(CommandError::InvalidColor(ColorParseError::InvalidLength)));
+    }
+
+    #[test]
+    fn lines() {
+        let mut command_line: CommandLine<8> = CommandLine::new();
+
+        for &byte in b"\r\nE rain" {
+            assert_eq!(command_line.feed(byte), None);
+        }
+
+        assert_eq!(command_line.feed(b'\r'), None);
+        assert_eq!(command_line.feed(b'\n'), Some(Ok(Command::Effect("rain"))));
+
+        for &byte in b"E rainbow" {
+            assert_eq!(command_line.feed(byte), None);
+        }
+
+        assert_eq!(command_line.feed(b'\n'), Some(Err(CommandError::InvalidLine)));
+
+        for &byte in b"F5" {
+            assert_eq!(command_line.feed(byte), None);
+        }
+
+        assert_eq!(command_line.feed(b'\n'), Some(Ok(Command::FrameRate(5))));
+    }
+}
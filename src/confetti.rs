@@ -0,0 +1,92 @@
+use crate::buffer::fade_to_black_by;
+use crate::led::Color;
+use crate::random::Random;
+
+/// Random pixels popping to random colors, then slowly fading out, drawn
+/// over a pixel buffer (e.g. `BufferedStrip::pixels_mut()`):
+///
+/// ```ignore
+/// confetti.draw(led_strip.pixels_mut());
+/// led_strip.show();
+/// ```
+pub struct Confetti<'a> {
+    random: Random,
+    spawn_rate: u8,
+    fade: u8,
+    palette: Option<&'a [Color]>,
+}
+
+impl<'a> Confetti<'a> {
+    pub fn new(random: Random) -> Confetti<'a> {
+        Confetti {
+            random,
+            spawn_rate: 128,
+            fade: 10,
+            palette: None,
+        }
+    }
+
+    /// Chance for a pixel to pop each frame, out of 256.
+    pub fn spawn_rate(mut self, spawn_rate: u8) -> Confetti<'a> {
+        self.spawn_rate = spawn_rate;
+
+        self
+    }
+
+    /// How much all pixels dim each frame, see `fade_to_black_by()`.
+    pub fn fade(mut self, fade: u8) -> Confetti<'a> {
+        self.fade = fade;
+
+        self
+    }
+
+    /// Colors to pick from, instead of any saturated hue.
+    pub fn palette(mut self, palette: &'a [Color]) -> Confetti<'a> {
+        self.palette = Some(palette).filter(|palette| !palette.is_empty());
+
+        self
+    }
+
+    /// Fade the previous frame and pop a new pixel, maybe.
+    pub fn draw(&mut self, pixels: &mut [Color]) {
+        fade_to_black_by(pixels, self.fade);
+
+        if pixels.is_empty() || self.random.random8() >= self.spawn_rate {
+            return;
+        }
+
+        let led_index = usize::from(self.random.random16_below(pixels.len().min(usize::from(u16::MAX)) as u16));
+        let color = match self.palette {
+            Some(palette) => palette[usize::from(self.random.random8_below(palette.len().min(255) as u8))],
+            None => Color::HSV(self.random.random8(), 200, 255),
+        };
+
+        pixels[led_index] = color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::confetti::Confetti;
+    use crate::led::Color;
+    use crate::random::Random;
+
+    #[test]
+    fn pops_and_fades() {
+        let mut pixels = [Color::Black; 8];
+        let palette = [Color::Blue, Color::Cyan];
+        let mut confetti = Confetti::new(Random::new(7)).spawn_rate(255).fade(0).palette(&palette);
+
+        confetti.draw(&mut pixels);
+        confetti.draw(&mut pixels);
+
+        let lit = pixels.iter().filter(|&&pixel| pixel != Color::RGB(0, 0, 0)).count();
+        assert!((1..=2).contains(&lit));
+        assert!(pixels.iter().all(|pixel| [(0, 0, 0), (0, 0, 255), (0, 255, 255)].contains(&pixel.to_rgb())));
+
+        let mut confetti = Confetti::new(Random::new(7)).spawn_rate(0).fade(255);
+        confetti.draw(&mut pixels);
+
+        assert_eq!(pixels, [Color::RGB(0, 0, 0); 8]);
+    }
+}
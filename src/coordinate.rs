@@ -0,0 +1,193 @@
+use crate::led::Color;
+use crate::math::sin8;
+
+/// Layout of the LEDs in 2D, so effects written for `(x, y)` or polar
+/// coordinates run on matrices, rings or letters made of strips alike.
+pub trait CoordinateMap {
+    /// LED at column `x` and row `y`, `None` where there is none.
+    fn index(&self, x: u8, y: u8) -> Option<usize>;
+
+    /// Column and row of the LED at `led_index`, `None` past the last one.
+    fn position(&self, led_index: usize) -> Option<(u8, u8)>;
+
+    /// Angle (a full turn in 256 steps) and distance from the center
+    /// (255 at the edge) of the LED at `led_index`, for round layouts.
+    fn polar(&self, _led_index: usize) -> Option<(u8, u8)> {
+        None
+    }
+}
+
+/// Matrix of `width` by `height` LEDs wired row by row, every other row
+/// going back when `zig_zag` (serpentine wiring).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Grid {
+    pub width: u8,
+    pub height: u8,
+    pub zig_zag: bool,
+}
+
+impl CoordinateMap for Grid {
+    fn index(&self, x: u8, y: u8) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let column = match self.zig_zag && y % 2 == 1 {
+            true => self.width - 1 - x,
+            false => x,
+        };
+
+        Some(usize::from(y) * usize::from(self.width) + usize::from(column))
+    }
+
+    fn position(&self, led_index: usize) -> Option<(u8, u8)> {
+        let width = usize::from(self.width);
+
+        if width == 0 || led_index >= width * usize::from(self.height) {
+            return None;
+        }
+
+        let (y, column) = ((led_index / width) as u8, (led_index % width) as u8);
+
+        match self.zig_zag && y % 2 == 1 {
+            true => Some((self.width - 1 - column, y)),
+            false => Some((column, y)),
+        }
+    }
+}
+
+/// Position of each LED in wiring order, for irregular layouts
+/// (spirals, letters...).
+pub struct Table<'a>(pub &'a [(u8, u8)]);
+
+impl CoordinateMap for Table<'_> {
+    fn index(&self, x: u8, y: u8) -> Option<usize> {
+        self.0.iter().position(|&position| position == (x, y))
+    }
+
+    fn position(&self, led_index: usize) -> Option<(u8, u8)> {
+        self.0.get(led_index).copied()
+    }
+}
+
+/// Concentric rings with the given LED counts, wired from the outer ring
+/// to the center, each starting at the same angle, e.g.
+/// `Rings(&[24, 16, 12, 8, 1])`.
+///
+/// Positions are on a 256 by 256 area centered on `(128, 128)`.
+pub struct Rings<'a>(pub &'a [u8]);
+
+impl Rings<'_> {
+    pub fn led_count(&self) -> usize {
+        self.0.iter().map(|&count| usize::from(count)).sum()
+    }
+
+    /// LED of the ring at `radius` nearest to `angle`.
+    pub fn at(&self, angle: u8, radius: u8) -> Option<usize> {
+        let last = self.0.len().checked_sub(1)?;
+        let ring = last - (usize::from(radius) * last + 127) / 255;
+        let start: usize = self.0[..ring].iter().map(|&count| usize::from(count)).sum();
+        let count = usize::from(self.0[ring]);
+
+        match count {
+            0 => None,
+            _ => Some(start + (usize::from(angle) * count + 128) / 256 % count),
+        }
+    }
+
+    /// Ring of the LED at `led_index` and its rank in the ring.
+    fn locate(&self, mut led_index: usize) -> Option<(usize, usize)> {
+        for (ring, &count) in self.0.iter().enumerate() {
+            match led_index < usize::from(count) {
+                true => return Some((ring, led_index)),
+                false => led_index -= usize::from(count),
+            }
+        }
+
+        None
+    }
+}
+
+impl CoordinateMap for Rings<'_> {
+    fn index(&self, x: u8, y: u8) -> Option<usize> {
+        let distance = |(led_x, led_y): (u8, u8)| {
+            let (dx, dy) = (i32::from(led_x) - i32::from(x), i32::from(led_y) - i32::from(y));
+
+            dx * dx + dy * dy
+        };
+
+        (0..self.led_count()).min_by_key(|&led_index| self.position(led_index).map_or(i32::MAX, distance))
+    }
+
+    fn position(&self, led_index: usize) -> Option<(u8, u8)> {
+        let (angle, radius) = self.polar(led_index)?;
+        let offset = |sine: u8| (i32::from(sine) - 128) * i32::from(radius) / 255;
+
+        Some(((128 + offset(sin8(angle.wrapping_add(64)))) as u8, (128 + offset(sin8(angle))) as u8))
+    }
+
+    fn polar(&self, led_index: usize) -> Option<(u8, u8)> {
+        let (ring, rank) = self.locate(led_index)?;
+        let last = self.0.len() - 1;
+        let angle = (rank * 256 / usize::from(self.0[ring])) as u8;
+
+        match last {
+            0 => Some((angle, 255)),
+            _ => Some((angle, ((last - ring) * 255 / last) as u8)),
+        }
+    }
+}
+
+/// Render a 2D `effect` of `(x, y)` LED by LED, black where the map has no position.
+pub fn render<'a>(map: &'a dyn CoordinateMap, effect: &'a dyn Fn(u8, u8) -> Color) -> impl Fn(usize) -> Color + 'a {
+    move |led_index| map.position(led_index).map_or(Color::Black, |(x, y)| effect(x, y))
+}
+
+/// Render an `effect` of `(angle, radius)` LED by LED, black where the map has no polar coordinates.
+pub fn render_polar<'a>(map: &'a dyn CoordinateMap, effect: &'a dyn Fn(u8, u8) -> Color) -> impl Fn(usize) -> Color + 'a {
+    move |led_index| map.polar(led_index).map_or(Color::Black, |(angle, radius)| effect(angle, radius))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coordinate::{render, CoordinateMap, Grid, Rings, Table};
+    use crate::led::Color;
+
+    #[test]
+    fn grid() {
+        let grid = Grid { width: 4, height: 3, zig_zag: true };
+
+        assert_eq!(grid.index(1, 0), Some(1));
+        assert_eq!(grid.index(1, 1), Some(6));
+        assert_eq!(grid.index(4, 1), None);
+        assert_eq!(grid.position(6), Some((1, 1)));
+        assert_eq!(grid.position(12), None);
+        assert_eq!(Grid { zig_zag: false, ..grid }.position(6), Some((2, 1)));
+    }
+
+    #[test]
+    fn table() {
+        let letter = Table(&[(0, 0), (0, 1), (0, 2), (1, 2)]);
+        let effect = |x, _| [Color::Red, Color::Blue][usize::from(x)];
+        let render = render(&letter, &effect);
+
+        assert_eq!(letter.index(1, 2), Some(3));
+        assert_eq!(letter.index(1, 0), None);
+        assert_eq!([0, 3, 4].map(render), [Color::Red, Color::Blue, Color::Black]);
+    }
+
+    #[test]
+    fn rings() {
+        let rings = Rings(&[8, 4, 1]);
+
+        assert_eq!(rings.led_count(), 13);
+        assert_eq!(rings.polar(2), Some((64, 255)));
+        assert_eq!(rings.polar(10), Some((128, 127)));
+        assert_eq!(rings.polar(12), Some((0, 0)));
+        assert_eq!(rings.polar(13), None);
+        assert_eq!(rings.at(64, 255), Some(2));
+        assert_eq!(rings.at(250, 120), Some(8));
+        assert_eq!(rings.position(12), Some((128, 128)));
+        assert_eq!(rings.index(128, 255), Some(2));
+    }
+}
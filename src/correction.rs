@@ -0,0 +1,165 @@
+use crate::math::scale8;
+
+/// Blackbody colors from 1000 K to 10000 K by steps of 500 K.
+const BLACKBODY: [(u8, u8, u8); 19] = [
+    (255, 56, 0),
+    (255, 109, 0),
+    (255, 137, 18),
+    (255, 161, 72),
+    (255, 180, 107),
+    (255, 196, 137),
+    (255, 209, 163),
+    (255, 219, 186),
+    (255, 228, 206),
+    (255, 236, 224),
+    (255, 243, 239),
+    (255, 249, 253),
+    (245, 243, 255),
+    (235, 238, 255),
+    (227, 233, 255),
+    (220, 229, 255),
+    (214, 225, 255),
+    (208, 222, 255),
+    (204, 219, 255),
+];
+
+/// Color temperature of the light the strip should imitate.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Temperature {
+    /// 1900 K
+    Candle,
+    /// 2600 K
+    Tungsten40W,
+    /// 2850 K
+    Tungsten100W,
+    /// 3200 K
+    Halogen,
+    /// 5400 K
+    Daylight,
+    /// 7000 K
+    OvercastSky,
+    /// Any temperature from 1000 K to 10000 K
+    Kelvin(u16),
+    Uncorrected,
+}
+
+impl Temperature {
+    /// Per-channel scale to apply, 255 leaving the channel unchanged.
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            Temperature::Candle => (255, 147, 41),
+            Temperature::Tungsten40W => (255, 197, 143),
+            Temperature::Tungsten100W => (255, 214, 170),
+            Temperature::Halogen => (255, 241, 224),
+            Temperature::Daylight => (255, 255, 251),
+            Temperature::OvercastSky => (201, 226, 255),
+            Temperature::Kelvin(kelvin) => {
+                let kelvin = usize::from(kelvin.clamp(1_000, 10_000) - 1_000);
+                let index = kelvin / 500;
+                let fraction = (kelvin % 500) as i32;
+                let (red, green, blue) = BLACKBODY[index];
+                let (next_red, next_green, next_blue) = BLACKBODY[(index + 1).min(BLACKBODY.len() - 1)];
+                let lerp = |from: u8, to: u8| {
+                    (i32::from(from) + (i32::from(to) - i32::from(from)) * fraction / 500) as u8
+                };
+
+                (lerp(red, next_red), lerp(green, next_green), lerp(blue, next_blue))
+            },
+            Temperature::Uncorrected => (255, 255, 255),
+        }
+    }
+}
+
+/// Typical color correction of common LED packages.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Correction {
+    TypicalSMD5050,
+    TypicalLEDStrip,
+    Typical8mmPixel,
+    TypicalPixelString,
+    Uncorrected,
+}
+
+impl Correction {
+    /// Per-channel scale to apply, 255 leaving the channel unchanged.
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            Correction::TypicalSMD5050 | Correction::TypicalLEDStrip => (255, 176, 240),
+            Correction::Typical8mmPixel | Correction::TypicalPixelString => (255, 224, 140),
+            Correction::Uncorrected => (255, 255, 255),
+        }
+    }
+}
+
+/// Curve from the brightness setting to the brightness applied, so a
+/// setting looks proportionally bright instead of the upper half of the
+/// range barely changing.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DimmingCurve {
+    Linear,
+    /// Lightness of the CIE 1931 color space, the closest to perception.
+    Cie1931,
+    Square,
+}
+
+impl DimmingCurve {
+    /// Brightness to apply for the `brightness` setting, only 0 turning the LEDs off.
+    pub fn apply(self, brightness: u8) -> u8 {
+        let value = u64::from(brightness);
+        let applied = match self {
+            DimmingCurve::Linear => return brightness,
+            DimmingCurve::Square => value * value / 255,
+            // Lightness L* = 100 * brightness / 255 below 8 is linear, cubic above.
+            DimmingCurve::Cie1931 if value * 100 <= 8 * 255 => value * 1_000 / 9_033,
+            DimmingCurve::Cie1931 => {
+                let t = value * 100 + 16 * 255;
+
+                (t * t * t * 255 + 29_580 * 29_580 * 29_580 / 2) / (29_580 * 29_580 * 29_580)
+            },
+        };
+
+        match brightness {
+            0 => 0,
+            _ => applied.max(1) as u8,
+        }
+    }
+}
+
+/// Multiply two sets of per-channel scales.
+pub fn combine(first: (u8, u8, u8), second: (u8, u8, u8)) -> (u8, u8, u8) {
+    (
+        scale8(first.0, second.0),
+        scale8(first.1, second.1),
+        scale8(first.2, second.2),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::correction::{combine, Correction, DimmingCurve, Temperature};
+
+    #[test]
+    fn kelvin() {
+        assert_eq!(Temperature::Kelvin(1_000).to_rgb(), (255, 56, 0));
+        assert_eq!(Temperature::Kelvin(500).to_rgb(), (255, 56, 0));
+        assert_eq!(Temperature::Kelvin(6_750).to_rgb(), (250, 246, 254));
+        assert_eq!(Temperature::Kelvin(10_000).to_rgb(), (204, 219, 255));
+        assert_eq!(Temperature::Kelvin(40_000).to_rgb(), (204, 219, 255));
+    }
+
+    #[test]
+    fn combined() {
+        assert_eq!(combine(Correction::TypicalLEDStrip.to_rgb(), (255, 255, 255)), (255, 176, 240));
+        assert_eq!(combine(Correction::TypicalSMD5050.to_rgb(), Temperature::Candle.to_rgb()), (255, 101, 39));
+    }
+
+    #[test]
+    fn dimming() {
+        assert_eq!([0, 1, 128, 255].map(|value| DimmingCurve::Linear.apply(value)), [0, 1, 128, 255]);
+        assert_eq!([0, 1, 128, 255].map(|value| DimmingCurve::Square.apply(value)), [0, 1, 64, 255]);
+        assert_eq!([0, 1, 20, 128, 255].map(|value| DimmingCurve::Cie1931.apply(value)), [0, 1, 2, 47, 255]);
+    }
+}
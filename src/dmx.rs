@@ -0,0 +1,196 @@
+use crate::error::LedError;
+use crate::led::{Color, LedStrip, Transmitter, LATCH_TIME};
+
+/// Slots in a DMX512 universe, after the start code.
+pub const UNIVERSE_SIZE: u16 = 512;
+
+/// Slots each pixel reads, from its first address.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Layout {
+    /// 1 slot, white level.
+    Intensity,
+    /// 3 slots, red, green, blue.
+    Rgb,
+    /// 4 slots, red, green, blue, then a dimmer applied to the 3 others.
+    RgbDimmer,
+}
+
+impl Layout {
+    pub fn channels(&self) -> u16 {
+        match self {
+            Layout::Intensity => 1,
+            Layout::Rgb => 3,
+            Layout::RgbDimmer => 4,
+        }
+    }
+
+    fn color(&self, slots: [u8; 4]) -> Color {
+        match self {
+            Layout::Intensity => Color::RGB(slots[0], slots[0], slots[0]),
+            Layout::Rgb => Color::RGB(slots[0], slots[1], slots[2]),
+            Layout::RgbDimmer => Color::RGB(slots[0], slots[1], slots[2]) * slots[3],
+        }
+    }
+}
+
+/// DMX512 receiver mapping the slots from `start_address` (1 to 512) onto
+/// `led_count` pixels.
+///
+/// Open the UART at 250 000 baud, 8N2, feed each received byte to `feed()`
+/// and call `break_received()` when the UART reports a frame error
+/// (`FE0` in `UCSR0A` on AVR), which is how the break starting each packet
+/// shows up. Packets with a start code other than 0 are ignored.
+pub struct Dmx {
+    start_address: u16,
+    layout: Layout,
+    led_count: usize,
+    /// Slot of the next byte, 0 being the start code, `None` until the next break.
+    slot: Option<u16>,
+    slots: [u8; 4],
+}
+
+impl Dmx {
+    /// Panics if `start_address` is not a slot of the universe, see `try_new()`.
+    pub fn new(start_address: u16, layout: Layout, led_count: usize) -> Dmx {
+        assert!((1..=UNIVERSE_SIZE).contains(&start_address));
+
+        Dmx {
+            start_address,
+            layout,
+            led_count,
+            slot: None,
+            slots: [0; 4],
+        }
+    }
+
+    /// Receiver starting at `start_address`, from 1 to `UNIVERSE_SIZE`.
+    pub fn try_new(start_address: u16, layout: Layout, led_count: usize) -> Result<Dmx, LedError> {
+        match (1..=UNIVERSE_SIZE).contains(&start_address) {
+            true => Ok(Dmx::new(start_address, layout, led_count)),
+            false => Err(LedError::OutOfRange { index: start_address as usize, len: UNIVERSE_SIZE as usize + 1 }),
+        }
+    }
+
+    pub fn start_address(&self) -> u16 {
+        self.start_address
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Start a new packet.
+    pub fn break_received(&mut self) {
+        self.slot = Some(0);
+    }
+
+    /// Parse the next byte, return the LED index and color once a pixel is complete.
+    pub fn feed(&mut self, byte: u8) -> Option<(usize, Color)> {
+        let slot = self.slot?;
+
+        self.slot = match (slot, byte) {
+            (0, 0) => Some(1),
+            (0, _) | (UNIVERSE_SIZE, _) => None,
+            _ => Some(slot + 1),
+        };
+
+        if slot < self.start_address {
+            return None;
+        }
+
+        let channels = self.layout.channels();
+        let offset = usize::from(slot - self.start_address);
+        let led_index = offset / usize::from(channels);
+
+        if led_index >= self.led_count {
+            self.slot = None;
+
+            return None;
+        }
+
+        let channel = offset % usize::from(channels);
+        self.slots[channel] = byte;
+
+        match channel + 1 == usize::from(channels) {
+            true => Some((led_index, self.layout.color(self.slots))),
+            false => None,
+        }
+    }
+
+    /// Parse the next byte, send each complete pixel to the strip and latch
+    /// after the last one, return `true` when a frame was displayed.
+    ///
+    /// A packet too short to reach the last pixel leaves its frame unlatched.
+    pub fn push<T: Transmitter>(&mut self, byte: u8, led_strip: &mut LedStrip<T>) -> bool {
+        match self.feed(byte) {
+            Some((led_index, color)) => {
+                led_strip.color(color);
+
+                if led_index + 1 == self.led_count {
+                    led_strip.rest(LATCH_TIME);
+
+                    return true;
+                }
+
+                false
+            },
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dmx::{Dmx, Layout};
+    use crate::error::LedError;
+    use crate::led::Color;
+
+    #[test]
+    fn mapping() {
+        let mut dmx = Dmx::new(3, Layout::Rgb, 2);
+        let mut events = [None; 2];
+        let mut count = 0;
+
+        assert_eq!(dmx.feed(0), None);
+        dmx.break_received();
+
+        for byte in [0, 9, 9, 1, 2, 3, 4, 5, 6, 7] {
+            if let Some(event) = dmx.feed(byte) {
+                events[count] = Some(event);
+                count += 1;
+            }
+        }
+
+        assert_eq!(events, [
+            Some((0, Color::RGB(1, 2, 3))),
+            Some((1, Color::RGB(4, 5, 6))),
+        ]);
+
+        dmx.break_received();
+        assert_eq!(dmx.feed(0xCC), None);
+        assert_eq!(dmx.feed(0), None);
+        assert_eq!(dmx.feed(0), None);
+    }
+
+    #[test]
+    fn layouts() {
+        let mut dmx = Dmx::new(1, Layout::RgbDimmer, 1);
+        dmx.break_received();
+
+        let events = [0, 255, 128, 0, 128].map(|byte| dmx.feed(byte));
+
+        assert_eq!(events[4], Some((0, Color::RGB(128, 64, 0))));
+
+        let mut dmx = Dmx::new(512, Layout::Intensity, 4);
+        dmx.break_received();
+
+        for _ in 0..512 {
+            assert_eq!(dmx.feed(0), None);
+        }
+
+        assert_eq!(dmx.feed(200), Some((0, Color::RGB(200, 200, 200))));
+        assert_eq!(dmx.feed(200), None);
+
+        assert_eq!(Dmx::try_new(513, Layout::Rgb, 1).err(), Some(LedError::OutOfRange { index: 513, len: 513 }));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::led::{ColorOrder, Transmitter};
+
+/// Transmitter wrapper keeping a copy of the bytes of each frame (up to `N`) and,
+/// once the frame is latched, writing them as hex through `write` (e.g. to the UART),
+/// one line per frame, ending with `+` if the frame was longer than `N` bytes.
+///
+/// Writing only happens after the reset so slow serial output doesn't break
+/// the strip timing in the middle of a frame.
+pub struct FrameDump<T: Transmitter, W: FnMut(u8), const N: usize> {
+    transmitter: T,
+    write: W,
+    buffer: [u8; N],
+    len: usize,
+    truncated: bool,
+    enabled: bool,
+}
+
+impl<T: Transmitter, W: FnMut(u8), const N: usize> FrameDump<T, W, N> {
+    pub fn new(transmitter: T, write: W) -> FrameDump<T, W, N> {
+        FrameDump {
+            transmitter,
+            write,
+            buffer: [0; N],
+            len: 0,
+            truncated: false,
+            enabled: true,
+        }
+    }
+
+    pub fn transmitter(&self) -> &T {
+        &self.transmitter
+    }
+
+    pub fn transmitter_mut(&mut self) -> &mut T {
+        &mut self.transmitter
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turn the dump on or off at runtime, frames are still transmitted either way.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.len = 0;
+        self.truncated = false;
+    }
+
+    fn flush(&mut self) {
+        for &byte in &self.buffer[..self.len] {
+            (self.write)(hex_digit(byte >> 4));
+            (self.write)(hex_digit(byte & 0xF));
+        }
+
+        if self.truncated {
+            (self.write)(b'+');
+        }
+
+        (self.write)(b'\r');
+        (self.write)(b'\n');
+        self.len = 0;
+        self.truncated = false;
+    }
+}
+
+impl<T: Transmitter, W: FnMut(u8), const N: usize> Transmitter for FrameDump<T, W, N> {
+    fn send_byte(&mut self, byte: u8) {
+        self.transmitter.send_byte(byte);
+
+        if self.enabled {
+            match self.buffer.get_mut(self.len) {
+                Some(slot) => {
+                    *slot = byte;
+                    self.len += 1;
+                },
+                None => self.truncated = true,
+            }
+        }
+    }
+
+    fn reset(&mut self, us: u32) {
+        self.transmitter.reset(us);
+
+        if self.enabled {
+            self.flush();
+        }
+    }
+
+    fn set_order(&mut self, order: ColorOrder) {
+        self.transmitter.set_order(order);
+    }
+}
+
+fn hex_digit(nibble: u8) -> u8 {
+    match nibble {
+        0..=9 => b'0' + nibble,
+        _ => b'A' + nibble - 10,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dump::FrameDump;
+    use crate::led::{Color, LedStrip};
+    use crate::mock::MockPin;
+
+    #[test]
+    fn hex_lines() {
+        let mut output = [0u8; 32];
+        let mut len = 0;
+        let write = |byte| {
+            output[len] = byte;
+            len += 1;
+        };
+        {
+            let dump: FrameDump<_, _, 4> = FrameDump::new(MockPin::<128>::new(), write);
+            let mut led_strip = LedStrip::with_transmitter(2, dump);
+
+            led_strip.each(|_| Color::Orange);
+            led_strip.rest(50);
+            assert_eq!(led_strip.transmitter().transmitter().bytes().count(), 6);
+
+            led_strip.transmitter_mut().set_enabled(false);
+            led_strip.each(|_| Color::Blue);
+            led_strip.rest(50);
+        }
+
+        assert_eq!(&output[..len], b"7FFF007F+\r\n");
+    }
+}
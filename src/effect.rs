@@ -0,0 +1,224 @@
+use heapless::Vec;
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Named animation, rendering the color of each LED for a given frame number.
+#[derive(Copy, Clone)]
+pub struct Effect<'a> {
+    name: &'a str,
+    render: &'a dyn Fn(u32, usize) -> Color,
+}
+
+impl<'a> Effect<'a> {
+    pub fn new(name: &'a str, render: &'a dyn Fn(u32, usize) -> Color) -> Effect<'a> {
+        Effect {
+            name,
+            render,
+        }
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn color(&self, frame: u32, led_index: usize) -> Color {
+        (self.render)(frame, led_index)
+    }
+}
+
+/// Up to `N` effects played one at a time, switched at runtime
+/// (e.g. from the serial commands or a remote).
+pub struct Scheduler<'a, const N: usize> {
+    effects: Vec<Effect<'a>, N>,
+    current: usize,
+    frame: u32,
+    enabled: bool,
+}
+
+impl<'a, const N: usize> Scheduler<'a, N> {
+    pub fn new() -> Scheduler<'a, N> {
+        Scheduler {
+            effects: Vec::new(),
+            current: 0,
+            frame: 0,
+            enabled: true,
+        }
+    }
+
+    /// Add an effect after the others, return its index or `None` when full.
+    pub fn add(&mut self, effect: Effect<'a>) -> Option<usize> {
+        self.effects.push(effect).ok()?;
+
+        Some(self.effects.len() - 1)
+    }
+
+    /// Take the effect at `index` out of the playlist, the following ones move down.
+    pub fn remove(&mut self, index: usize) -> Option<Effect<'a>> {
+        if index >= self.effects.len() {
+            return None;
+        }
+
+        let effect = self.effects.remove(index);
+
+        if index < self.current || self.current >= self.effects.len() {
+            self.current = self.current.saturating_sub(1);
+        }
+
+        Some(effect)
+    }
+
+    pub fn effects(&self) -> &[Effect<'a>] {
+        &self.effects
+    }
+
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    pub fn current(&self) -> Option<&Effect<'a>> {
+        self.effects.get(self.current)
+    }
+
+    pub fn index(&self) -> usize {
+        self.current
+    }
+
+    /// Frames shown since the current effect was selected.
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// When disabled, frames are shown black.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Switch to the effect at `index` and restart it, return `false` if there is none.
+    pub fn select(&mut self, index: usize) -> bool {
+        if index >= self.effects.len() {
+            return false;
+        }
+
+        self.current = index;
+        self.frame = 0;
+
+        true
+    }
+
+    /// Switch to the effect with the given name, return `false` if there is none.
+    pub fn select_name(&mut self, name: &str) -> bool {
+        match self.effects.iter().position(|effect| effect.name == name) {
+            Some(index) => self.select(index),
+            None => false,
+        }
+    }
+
+    pub fn next(&mut self) {
+        if !self.is_empty() {
+            self.select((self.current + 1) % self.len());
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.is_empty() {
+            self.select((self.current + self.len() - 1) % self.len());
+        }
+    }
+
+    pub fn color(&self, led_index: usize) -> Color {
+        match (self.enabled, self.current()) {
+            (true, Some(effect)) => effect.color(self.frame, led_index),
+            _ => Color::Black,
+        }
+    }
+
+    /// Send the current frame of the current effect and move to the next frame.
+    pub fn show<T: Transmitter>(&mut self, led_strip: &mut LedStrip<T>) {
+        led_strip.each(|led_index| self.color(led_index));
+        self.frame = self.frame.wrapping_add(1);
+    }
+}
+
+impl<const N: usize> Default for Scheduler<'_, N> {
+    fn default() -> Self {
+        Scheduler::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::effect::{Effect, Scheduler};
+    use crate::led::{Color, LedStrip};
+    use crate::mock::MockPin;
+
+    #[test]
+    fn switch() {
+        let solid = |_, _| Color::Red;
+        let chase = |frame, led_index| match frame as usize % 2 == led_index {
+            true => Color::White,
+            false => Color::Black,
+        };
+        let mut scheduler: Scheduler<3> = Scheduler::new();
+
+        assert!(scheduler.current().is_none());
+        assert_eq!(scheduler.color(0), Color::Black);
+        scheduler.next();
+
+        assert_eq!(scheduler.add(Effect::new("solid", &solid)), Some(0));
+        assert_eq!(scheduler.add(Effect::new("chase", &chase)), Some(1));
+        assert_eq!(scheduler.len(), 2);
+        assert_eq!(scheduler.color(0), Color::Red);
+
+        scheduler.previous();
+        assert_eq!(scheduler.current().map(Effect::name), Some("chase"));
+        scheduler.next();
+        assert_eq!(scheduler.index(), 0);
+
+        assert!(scheduler.select_name("chase"));
+        assert!(!scheduler.select_name("fire"));
+        assert!(!scheduler.select(2));
+        assert_eq!(scheduler.index(), 1);
+
+        scheduler.set_enabled(false);
+        assert_eq!(scheduler.color(0), Color::Black);
+    }
+
+    #[test]
+    fn playlist() {
+        let solid = |_, _| Color::Red;
+        let mut scheduler: Scheduler<2> = Scheduler::new();
+        scheduler.add(Effect::new("red", &solid));
+        scheduler.add(Effect::new("fire", &solid));
+
+        assert_eq!(scheduler.add(Effect::new("chase", &solid)), None);
+        assert!(scheduler.select(1));
+        assert_eq!(scheduler.remove(0).map(|effect| effect.name()), Some("red"));
+        assert_eq!(scheduler.current().map(Effect::name), Some("fire"));
+        assert!(scheduler.remove(1).is_none());
+        assert_eq!(scheduler.effects().len(), 1);
+    }
+
+    #[test]
+    fn frames() {
+        let chase = |frame, led_index| match frame as usize % 2 == led_index {
+            true => Color::White,
+            false => Color::Black,
+        };
+        let mut scheduler: Scheduler<1> = Scheduler::new();
+        let mut led_strip = LedStrip::with_transmitter(2, MockPin::<256>::new());
+        scheduler.add(Effect::new("chase", &chase));
+
+        scheduler.show(&mut led_strip);
+        scheduler.show(&mut led_strip);
+
+        assert_eq!(scheduler.frame(), 2);
+        assert!(led_strip.transmitter().bytes().eq([255, 255, 255, 0, 0, 0, 0, 0, 0, 255, 255, 255]));
+    }
+}
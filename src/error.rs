@@ -0,0 +1,66 @@
+use core::fmt;
+
+use crate::chipset::TimingError;
+use crate::led::ColorParseError;
+
+/// Error of the fallible parts of the API, so a failure can be reported
+/// (e.g. blinking the strip or over serial) instead of freezing in a panic.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LedError {
+    /// Index past the end of a strip, buffer or range of `len` items.
+    OutOfRange { index: usize, len: usize },
+    InvalidHex(ColorParseError),
+    /// `needed` items don't fit in a buffer of `len`.
+    BufferTooSmall { needed: usize, len: usize },
+    /// Pixels were sent since the last latch.
+    NotLatched,
+    /// The bit timing can't be produced within the chip tolerance.
+    UnsupportedTiming(TimingError),
+    /// Palette indices of this many bits can't be packed, only 1, 2, 4 or 8.
+    InvalidBits(u8),
+    /// A palette of `len` colors has more than the `max` indices can address.
+    PaletteTooLarge { len: usize, max: usize },
+}
+
+impl fmt::Display for LedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedError::OutOfRange { index, len } => write!(f, "index {} out of range, the length is {}", index, len),
+            LedError::InvalidHex(error) => write!(f, "invalid hex color: {}", error),
+            LedError::BufferTooSmall { needed, len } => write!(f, "buffer too small, {} needed but {} available", needed, len),
+            LedError::NotLatched => f.write_str("previous frame not latched"),
+            LedError::UnsupportedTiming(error) => write!(f, "unsupported timing: {}", error),
+            LedError::InvalidBits(bits) => write!(f, "palette indices of {} bits, expected 1, 2, 4 or 8", bits),
+            LedError::PaletteTooLarge { len, max } => write!(f, "palette of {} colors, at most {} can be indexed", len, max),
+        }
+    }
+}
+
+impl From<ColorParseError> for LedError {
+    fn from(error: ColorParseError) -> LedError {
+        LedError::InvalidHex(error)
+    }
+}
+
+impl From<TimingError> for LedError {
+    fn from(error: TimingError) -> LedError {
+        LedError::UnsupportedTiming(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chipset::TimingError;
+    use crate::error::LedError;
+    use crate::led::{parse, ColorParseError};
+
+    #[test]
+    fn conversions() {
+        let hex = |color| -> Result<[u8; 3], LedError> { Ok(parse(color)?) };
+
+        assert_eq!(hex("fff"), Ok([255, 255, 255]));
+        assert_eq!(hex("ff"), Err(LedError::InvalidHex(ColorParseError::InvalidLength)));
+        assert_eq!(LedError::from(TimingError::Period), LedError::UnsupportedTiming(TimingError::Period));
+    }
+}
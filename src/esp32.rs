@@ -0,0 +1,97 @@
+use embedded_hal::delay::DelayNs;
+use esp_hal::rmt::{Error, PulseCode, TxChannel};
+use heapless::Vec;
+use crate::led::Transmitter;
+
+/// Pulse durations in RMT ticks of 12.5 ns (80 MHz clock, divider of 1).
+const T0H: u16 = 32;
+const T0L: u16 = 68;
+const T1H: u16 = 64;
+const T1L: u16 = 36;
+
+/// WS2812B signal encoded by an ESP32 RMT channel, so `LedStrip` and the
+/// effects work unchanged:
+///
+/// ```ignore
+/// let rmt = Rmt::new(peripherals.RMT, 80.MHz())?;
+/// let config = TxChannelConfig { clk_divider: 1, ..TxChannelConfig::default() };
+/// let channel = rmt.channel0.configure(peripherals.GPIO2, config)?;
+/// let mut led_strip = LedStrip::with_transmitter(NUM_LEDS, Ws2812Rmt::<_, _, 577>::new(channel, Delay::new()));
+/// ```
+///
+/// Bytes are encoded into up to `N` pulse codes (8 per byte and an end
+/// marker) sent when full and at the latch: `N` of `24 * led_count + 1`
+/// sends each frame at once, a smaller buffer leaving a few microseconds
+/// gaps between the chunks, well under the latch time.
+pub struct Ws2812Rmt<C: TxChannel, D: DelayNs, const N: usize> {
+    channel: Option<C>,
+    delay: D,
+    pulses: Vec<u32, N>,
+    error: Option<Error>,
+}
+
+impl<C: TxChannel, D: DelayNs, const N: usize> Ws2812Rmt<C, D, N> {
+    /// Transmitter on a channel configured with a 12.5 ns tick, `N` must
+    /// hold at least one byte and the end marker (9 pulse codes).
+    pub fn new(channel: C, delay: D) -> Ws2812Rmt<C, D, N> {
+        const { assert!(N >= 9, "the RMT buffer must hold at least 9 pulse codes") };
+
+        Ws2812Rmt {
+            channel: Some(channel),
+            delay,
+            pulses: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Last transmission error, the channel being lost if it was not given back.
+    pub fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    fn flush(&mut self) {
+        if self.pulses.is_empty() {
+            return;
+        }
+
+        self.pulses.push(u32::empty()).ok();
+
+        if let Some(channel) = self.channel.take() {
+            self.channel = match channel.transmit(&self.pulses).map(|transaction| transaction.wait()) {
+                Ok(Ok(channel)) => Some(channel),
+                Ok(Err((error, channel))) => {
+                    self.error = Some(error);
+                    Some(channel)
+                },
+                Err(error) => {
+                    self.error = Some(error);
+                    None
+                },
+            };
+        }
+
+        self.pulses.clear();
+    }
+}
+
+impl<C: TxChannel, D: DelayNs, const N: usize> Transmitter for Ws2812Rmt<C, D, N> {
+    fn send_byte(&mut self, byte: u8) {
+        // Keep room for the end marker.
+        if self.pulses.len() + 9 > N {
+            self.flush();
+        }
+
+        for i in (0..8).rev() {
+            let pulse = match byte >> i & 1 {
+                0 => u32::new(true, T0H, false, T0L),
+                _ => u32::new(true, T1H, false, T1L),
+            };
+            self.pulses.push(pulse).ok();
+        }
+    }
+
+    fn reset(&mut self, us: u32) {
+        self.flush();
+        self.delay.delay_us(us);
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(all(target_arch = "avr", feature = "asm_experimental_arch"))]
+use core::arch::asm;
+
+/// Read-only byte storage, either in RAM or in program memory.
+pub trait ByteSource {
+    fn len(&self) -> usize;
+
+    fn byte(&self, index: usize) -> u8;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn u16_le(&self, index: usize) -> u16 {
+        u16::from(self.byte(index)) | (u16::from(self.byte(index + 1)) << 8)
+    }
+}
+
+impl ByteSource for [u8] {
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    fn byte(&self, index: usize) -> u8 {
+        self[index]
+    }
+}
+
+impl<const N: usize> ByteSource for [u8; N] {
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn byte(&self, index: usize) -> u8 {
+        self[index]
+    }
+}
+
+/// Bytes stored in flash, so they do not take any of the 2 KB of RAM.
+///
+/// ```ignore
+/// #[unsafe(link_section = ".progmem.data")]
+/// static FRAMES: ProgMem<4> = unsafe { ProgMem::new([0x0A, 0x00, 0b0001_1011, 0x00]) };
+/// ```
+#[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+#[repr(transparent)]
+pub struct ProgMem<const N: usize>([u8; N]);
+
+#[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+impl<const N: usize> ProgMem<N> {
+    /// # Safety
+    ///
+    /// The value must be a `static` placed in the `.progmem.data` section,
+    /// it's read with `lpm` which only addresses program memory.
+    pub const unsafe fn new(bytes: [u8; N]) -> ProgMem<N> {
+        ProgMem(bytes)
+    }
+}
+
+#[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+impl<const N: usize> ByteSource for ProgMem<N> {
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn byte(&self, index: usize) -> u8 {
+        read_byte(&self.0[index])
+    }
+}
+
+#[cfg(all(target_arch = "avr", feature = "asm_experimental_arch"))]
+fn read_byte(address: *const u8) -> u8 {
+    let byte: u8;
+
+    unsafe {
+        asm!(
+            "lpm {}, Z",
+            out(reg) byte,
+            in("Z") address,
+            options(pure, readonly, preserves_flags, nostack),
+        );
+    }
+
+    byte
+}
+
+#[cfg(not(target_arch = "avr"))]
+fn read_byte(address: &u8) -> u8 {
+    *address
+}
@@ -0,0 +1,103 @@
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Police-style pattern: the LEDs before the split flash `flashes` times in
+/// the first color, then the ones after it in the second color, and so on.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AlternatingFlash {
+    colors: (Color, Color),
+    split: usize,
+    flashes: u8,
+    on_ms: u16,
+    off_ms: u16,
+}
+
+impl AlternatingFlash {
+    /// Red and blue halves of a strip of `led_count` LEDs.
+    pub fn new(led_count: usize) -> AlternatingFlash {
+        AlternatingFlash {
+            colors: (Color::Red, Color::Blue),
+            split: led_count / 2,
+            flashes: 3,
+            on_ms: 50,
+            off_ms: 50,
+        }
+    }
+
+    pub fn colors(mut self, first: Color, second: Color) -> AlternatingFlash {
+        self.colors = (first, second);
+
+        self
+    }
+
+    /// Index of the first LED of the second group.
+    pub fn split(mut self, split: usize) -> AlternatingFlash {
+        self.split = split;
+
+        self
+    }
+
+    /// Flashes of a group before switching to the other.
+    pub fn flashes(mut self, flashes: u8) -> AlternatingFlash {
+        self.flashes = flashes.max(1);
+
+        self
+    }
+
+    /// Duration of each flash and of the gap after it.
+    pub fn timing(mut self, on_ms: u16, off_ms: u16) -> AlternatingFlash {
+        self.on_ms = on_ms;
+        self.off_ms = off_ms;
+
+        self
+    }
+
+    /// Duration of the full pattern, both groups included.
+    pub fn period(&self) -> u32 {
+        2 * u32::from(self.flashes) * (u32::from(self.on_ms) + u32::from(self.off_ms))
+    }
+
+    /// Group lit at `now_ms`, 0 for the first and 1 for the second,
+    /// `None` between flashes.
+    pub fn lit_group(&self, now_ms: u32) -> Option<usize> {
+        let flash = u32::from(self.on_ms) + u32::from(self.off_ms);
+        let time = now_ms % self.period().max(1);
+
+        match time % flash.max(1) < u32::from(self.on_ms) {
+            true => Some((time / flash.max(1) / u32::from(self.flashes)) as usize),
+            false => None,
+        }
+    }
+
+    pub fn color(&self, led_index: usize, now_ms: u32) -> Color {
+        match (self.lit_group(now_ms), led_index < self.split) {
+            (Some(0), true) => self.colors.0,
+            (Some(1), false) => self.colors.1,
+            _ => Color::Black,
+        }
+    }
+
+    pub fn show<T: Transmitter>(&self, led_strip: &mut LedStrip<T>, now_ms: u32) {
+        led_strip.each(|led_index| self.color(led_index, now_ms));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::flasher::AlternatingFlash;
+    use crate::led::Color;
+
+    #[test]
+    fn alternate() {
+        let flash = AlternatingFlash::new(10).split(4).flashes(2).timing(100, 50);
+
+        assert_eq!(flash.period(), 600);
+        assert_eq!([0, 120, 150, 299, 300, 460, 599, 600].map(|now| flash.lit_group(now)), [
+            Some(0), None, Some(0), None, Some(1), Some(1), None, Some(0),
+        ]);
+        assert_eq!(flash.color(3, 10), Color::Red);
+        assert_eq!(flash.color(4, 10), Color::Black);
+        assert_eq!(flash.color(4, 310), Color::Blue);
+        assert_eq!(flash.color(0, 310), Color::Black);
+    }
+}
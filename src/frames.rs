@@ -0,0 +1,443 @@
+#[cfg(feature = "std")]
+use std::{vec, vec::Vec};
+use crate::error::LedError;
+use crate::flash::ByteSource;
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Sequence of palette-compressed frames.
+///
+/// Each frame is stored as its delay in hundredths of second (2 bytes,
+/// little-endian, as in GIF files) followed by one palette index per LED
+/// packed on `bits` bits (1, 2, 4 or 8), lowest bits first.
+pub struct Animation<'a, S: ?Sized + ByteSource> {
+    palette: &'a [Color],
+    data: &'a S,
+    led_count: usize,
+    bits: u8,
+}
+
+impl<'a, S> Animation<'a, S> where S: ?Sized + ByteSource {
+    /// # Panics
+    ///
+    /// If `bits` is not 1, 2, 4 or 8, see `try_new()`.
+    pub fn new(palette: &'a [Color], data: &'a S, led_count: usize, bits: u8) -> Animation<'a, S> {
+        match Animation::try_new(palette, data, led_count, bits) {
+            Ok(animation) => animation,
+            Err(_) => panic!("palette indices must be 1, 2, 4 or 8 bits"),
+        }
+    }
+
+    pub fn try_new(palette: &'a [Color], data: &'a S, led_count: usize, bits: u8) -> Result<Animation<'a, S>, LedError> {
+        match bits {
+            1 | 2 | 4 | 8 => Ok(Animation {
+                palette,
+                data,
+                led_count,
+                bits,
+            }),
+            _ => Err(LedError::InvalidBits(bits)),
+        }
+    }
+
+    pub fn frame_size(&self) -> usize {
+        2 + (self.led_count * self.bits as usize).div_ceil(8)
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.data.len() / self.frame_size()
+    }
+
+    pub fn led_count(&self) -> usize {
+        self.led_count
+    }
+
+    /// Delay to wait after the given frame, in hundredths of second, `None`
+    /// past the last frame.
+    pub fn delay(&self, frame: usize) -> Option<u16> {
+        match frame < self.frame_count() {
+            true => Some(self.data.u16_le(frame * self.frame_size())),
+            false => None,
+        }
+    }
+
+    /// Color of an LED in a frame, black past the last LED or frame.
+    pub fn color(&self, frame: usize, led_index: usize) -> Color {
+        if led_index >= self.led_count || frame >= self.frame_count() {
+            return Color::Black;
+        }
+
+        let bit = led_index * self.bits as usize;
+        let byte = self.data.byte(frame * self.frame_size() + 2 + bit / 8);
+        let mask = ((1u16 << self.bits) - 1) as u8;
+        let index = (byte >> (bit % 8)) & mask;
+
+        self.palette.get(index as usize).copied().unwrap_or(Color::Black)
+    }
+}
+
+/// Frame of an `AnimationData`, one palette index per LED.
+#[cfg(feature = "std")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frame {
+    /// Delay to wait after the frame, in hundredths of second.
+    pub delay: u16,
+    pub indices: Vec<u8>,
+}
+
+/// Owned animation for host-side tools, encoded with `to_bytes()` into
+/// the data an `Animation` reads back from flash.
+#[cfg(feature = "std")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnimationData {
+    pub palette: Vec<Color>,
+    pub led_count: usize,
+    pub bits: u8,
+    pub frames: Vec<Frame>,
+}
+
+/// Palette the colors are mapped to by `AnimationData::quantize()`.
+#[cfg(feature = "std")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Quantization {
+    /// Nearest color of the given palette (up to 256 colors).
+    Fixed(Vec<Color>),
+    /// Palette of up to the given number of colors (at most 256) fitted to
+    /// the frames by median cut.
+    MedianCut(usize),
+}
+
+#[cfg(feature = "std")]
+impl AnimationData {
+    /// Palette-indexed animation of 24-bit frames, each being its delay in
+    /// hundredths of second and the color of each LED, e.g. converted from a
+    /// GIF, to write with `to_bytes()` to a file embedded with `include_bytes!`.
+    ///
+    /// Fails with `LedError::PaletteTooLarge` for a fixed palette of more than 256 colors.
+    pub fn quantize(
+        frames: &[(u16, Vec<[u8; 3]>)],
+        led_count: usize,
+        quantization: Quantization,
+    ) -> Result<AnimationData, LedError> {
+        let palette = match quantization {
+            Quantization::Fixed(palette) if palette.len() > 256 => {
+                return Err(LedError::PaletteTooLarge { len: palette.len(), max: 256 });
+            },
+            Quantization::Fixed(palette) => palette,
+            Quantization::MedianCut(count) => {
+                let pixels = frames.iter().flat_map(|(_, pixels)| pixels.iter().take(led_count).copied()).collect();
+
+                median_cut(pixels, count.clamp(1, 256))
+            },
+        };
+        let rgb: Vec<[u8; 3]> = palette.iter().map(|&color| color.into()).collect();
+        let bits = match palette.len() {
+            0..=2 => 1,
+            3..=4 => 2,
+            5..=16 => 4,
+            _ => 8,
+        };
+        let frames = frames.iter()
+            .map(|(delay, pixels)| Frame {
+                delay: *delay,
+                indices: pixels.iter().take(led_count).map(|&pixel| nearest(&rgb, pixel)).collect(),
+            })
+            .collect();
+
+        Ok(AnimationData {
+            palette,
+            led_count,
+            bits,
+            frames,
+        })
+    }
+
+    /// Packed frames, missing indices are 0 and extra ones are ignored.
+    ///
+    /// Fails if `bits` is not 1, 2, 4 or 8, or if it can't index the whole palette.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, LedError> {
+        if !matches!(self.bits, 1 | 2 | 4 | 8) {
+            return Err(LedError::InvalidBits(self.bits));
+        }
+
+        if self.palette.len() > 1 << self.bits {
+            return Err(LedError::PaletteTooLarge { len: self.palette.len(), max: 1 << self.bits });
+        }
+
+        let size = (self.led_count * self.bits as usize).div_ceil(8);
+        let mask = ((1u16 << self.bits) - 1) as u8;
+        let mut bytes = Vec::with_capacity(self.frames.len() * (2 + size));
+
+        for frame in &self.frames {
+            bytes.extend_from_slice(&frame.delay.to_le_bytes());
+
+            let start = bytes.len();
+            bytes.resize(start + size, 0);
+
+            for (led_index, index) in frame.indices.iter().take(self.led_count).enumerate() {
+                let bit = led_index * self.bits as usize;
+                bytes[start + bit / 8] |= (index & mask) << (bit % 8);
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Animation reading `data`, as returned by `to_bytes()`.
+    pub fn animation<'a, S: ?Sized + ByteSource>(&'a self, data: &'a S) -> Result<Animation<'a, S>, LedError> {
+        Animation::try_new(&self.palette, data, self.led_count, self.bits)
+    }
+}
+
+/// Split the box of colors with the widest channel range at its median
+/// until there are `count` boxes, the palette being their average colors.
+#[cfg(feature = "std")]
+fn median_cut(pixels: Vec<[u8; 3]>, count: usize) -> Vec<Color> {
+    let mut boxes = vec![pixels];
+
+    while boxes.len() < count {
+        let widest = boxes.iter()
+            .enumerate()
+            .map(|(index, pixels)| (index, widest_channel(pixels)))
+            .max_by_key(|&(_, (_, range))| range);
+
+        let Some((index, (channel, range))) = widest else { break };
+
+        if range == 0 {
+            break;
+        }
+
+        let mut pixels = boxes.swap_remove(index);
+        pixels.sort_unstable_by_key(|pixel| pixel[channel]);
+        let upper = pixels.split_off(pixels.len() / 2);
+        boxes.push(pixels);
+        boxes.push(upper);
+    }
+
+    boxes.iter()
+        .filter(|pixels| !pixels.is_empty())
+        .map(|pixels| {
+            let mut sums = [0u32; 3];
+
+            for pixel in pixels {
+                for channel in 0..3 {
+                    sums[channel] += u32::from(pixel[channel]);
+                }
+            }
+
+            let [red, green, blue] = sums.map(|sum| (sum / pixels.len() as u32) as u8);
+
+            Color::RGB(red, green, blue)
+        })
+        .collect()
+}
+
+/// Channel with the largest difference between its lowest and highest values, and that difference.
+#[cfg(feature = "std")]
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let values = pixels.iter().map(|pixel| pixel[channel]);
+            let range = values.clone().max().unwrap_or(0) - values.min().unwrap_or(0);
+
+            (channel, range)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+/// Index of the closest color of the palette.
+#[cfg(feature = "std")]
+fn nearest(palette: &[[u8; 3]], pixel: [u8; 3]) -> u8 {
+    let distance = |color: &[u8; 3]| -> u32 {
+        (0..3).map(|channel| u32::from(color[channel].abs_diff(pixel[channel])).pow(2)).sum()
+    };
+
+    palette.iter()
+        .enumerate()
+        .min_by_key(|(_, color)| distance(color))
+        .map_or(0, |(index, _)| index as u8)
+}
+
+pub struct FramePlayer<'a, S: ?Sized + ByteSource> {
+    animation: Animation<'a, S>,
+    frame: usize,
+    looping: bool,
+    finished: bool,
+}
+
+impl<'a, S> FramePlayer<'a, S> where S: ?Sized + ByteSource {
+    pub fn new(animation: Animation<'a, S>) -> FramePlayer<'a, S> {
+        FramePlayer {
+            animation,
+            frame: 0,
+            looping: true,
+            finished: false,
+        }
+    }
+
+    pub fn looping(mut self, looping: bool) -> FramePlayer<'a, S> {
+        self.looping = looping;
+
+        self
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn rewind(&mut self) {
+        self.frame = 0;
+        self.finished = false;
+    }
+
+    pub fn color(&self, led_index: usize) -> Color {
+        self.animation.color(self.frame, led_index)
+    }
+
+    pub fn delay(&self) -> u16 {
+        self.animation.delay(self.frame).unwrap_or(0)
+    }
+
+    /// Move to the next frame, return `false` once a non-looping animation is over.
+    pub fn advance(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+
+        if self.frame + 1 < self.animation.frame_count() {
+            self.frame += 1;
+        } else if self.looping {
+            self.frame = 0;
+        } else {
+            self.finished = true;
+        }
+
+        !self.finished
+    }
+
+    /// Send the current frame, wait for its delay, then move to the next one.
+    pub fn play<T: Transmitter>(&mut self, led_strip: &mut LedStrip<T>) -> bool {
+        if self.finished || self.animation.frame_count() == 0 {
+            return false;
+        }
+
+        led_strip.each(|led_index| self.color(led_index));
+
+        let mut remaining = u32::from(self.delay()) * 10_000;
+
+        while remaining > 0 {
+            let step = remaining.min(1_000_000);
+            led_strip.rest(step);
+            remaining -= step;
+        }
+
+        self.advance()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::LedError;
+    use crate::frames::{Animation, FramePlayer};
+    use crate::led::Color;
+
+    const PALETTE: [Color; 4] = [Color::Black, Color::Red, Color::Green, Color::Blue];
+    const DATA: [u8; 6] = [
+        10, 0, 0b1110_0100,
+        0, 1, 0b0001_1011,
+    ];
+
+    #[test]
+    fn decode() {
+        let animation = Animation::new(&PALETTE, &DATA, 4, 2);
+
+        assert_eq!(animation.frame_count(), 2);
+        assert_eq!(animation.delay(0), Some(10));
+        assert_eq!(animation.delay(1), Some(256));
+        assert_eq!(animation.delay(2), None);
+        assert_eq!(animation.color(2, 0), Color::Black);
+        assert!(Animation::try_new(&PALETTE, &DATA, 4, 3).is_err_and(|error| error == LedError::InvalidBits(3)));
+        assert_eq!(animation.color(0, 0).to_rgb(), (0, 0, 0));
+        assert_eq!(animation.color(0, 1).to_rgb(), (255, 0, 0));
+        assert_eq!(animation.color(0, 3).to_rgb(), (0, 0, 255));
+        assert_eq!(animation.color(1, 0).to_rgb(), (0, 0, 255));
+        assert_eq!(animation.color(1, 4).to_rgb(), (0, 0, 0));
+    }
+
+    #[test]
+    fn advance() {
+        let mut player = FramePlayer::new(Animation::new(&PALETTE, &DATA, 4, 2));
+
+        assert!(player.advance());
+        assert_eq!(player.frame(), 1);
+        assert!(player.advance());
+        assert_eq!(player.frame(), 0);
+
+        let mut player = FramePlayer::new(Animation::new(&PALETTE, &DATA, 4, 2)).looping(false);
+
+        assert!(player.advance());
+        assert!(!player.advance());
+        assert!(player.is_finished());
+        player.rewind();
+        assert_eq!(player.frame(), 0);
+        assert!(!player.is_finished());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn encode() {
+        use crate::frames::{AnimationData, Frame};
+        use std::vec;
+
+        let data = AnimationData {
+            palette: PALETTE.to_vec(),
+            led_count: 4,
+            bits: 2,
+            frames: vec![
+                Frame { delay: 10, indices: vec![0, 1, 2, 3] },
+                Frame { delay: 256, indices: vec![3, 2, 1, 0, 3] },
+            ],
+        };
+        let bytes = data.to_bytes().unwrap();
+
+        assert_eq!(bytes, DATA);
+        assert_eq!(AnimationData { bits: 3, ..data.clone() }.to_bytes(), Err(LedError::InvalidBits(3)));
+        assert_eq!(AnimationData { bits: 1, ..data.clone() }.to_bytes(), Err(LedError::PaletteTooLarge { len: 4, max: 2 }));
+        assert_eq!(data.animation(&bytes[..]).unwrap().color(1, 1).to_rgb(), (0, 255, 0));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn quantize() {
+        use crate::frames::{AnimationData, Quantization};
+        use std::vec;
+
+        let frames = [
+            (5, vec![[250, 5, 0], [0, 0, 200], [255, 0, 0]]),
+            (5, vec![[0, 10, 255], [240, 0, 10], [0, 0, 0]]),
+        ];
+
+        let fixed = AnimationData::quantize(&frames, 3, Quantization::Fixed(PALETTE.to_vec())).unwrap();
+        assert_eq!(fixed.bits, 2);
+        assert_eq!(fixed.frames[0].indices, [1, 3, 1]);
+        assert_eq!(fixed.frames[1].indices, [3, 1, 0]);
+
+        let too_large = Quantization::Fixed(vec![Color::Black; 257]);
+        assert_eq!(AnimationData::quantize(&frames, 3, too_large), Err(LedError::PaletteTooLarge { len: 257, max: 256 }));
+
+        let fitted = AnimationData::quantize(&frames, 2, Quantization::MedianCut(2)).unwrap();
+        assert_eq!(fitted.bits, 1);
+        assert_eq!(fitted.palette.len(), 2);
+        assert_eq!(fitted.frames[0].indices, [fitted.frames[1].indices[1], fitted.frames[1].indices[0]]);
+        assert_ne!(fitted.frames[0].indices[0], fitted.frames[0].indices[1]);
+
+        let bytes = fitted.to_bytes().unwrap();
+        assert_eq!(fitted.animation(&bytes[..]).unwrap().color(1, 0), fitted.palette[usize::from(fitted.frames[1].indices[0])]);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::flash::ByteSource;
+#[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+use crate::flash::ProgMem;
+
+/// Natural logarithm of `value` > 0, from the series of atanh once reduced
+/// to [0.5, 1).
+const fn ln(mut value: f64) -> f64 {
+    let mut exponent = 0.0;
+
+    while value < 0.5 {
+        value *= 2.0;
+        exponent += 1.0;
+    }
+
+    let z = (value - 1.0) / (value + 1.0);
+    let mut term = z;
+    let mut sum = 0.0;
+    let mut n = 1.0;
+
+    while n < 40.0 {
+        sum += term / n;
+        term *= z * z;
+        n += 2.0;
+    }
+
+    2.0 * sum - exponent * core::f64::consts::LN_2
+}
+
+/// Exponential of `value` <= 0, from its series at `value / 16`, squared 4 times.
+const fn exp(value: f64) -> f64 {
+    let x = value / 16.0;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    let mut n = 1.0;
+
+    while n < 16.0 {
+        term *= x / n;
+        sum += term;
+        n += 1.0;
+    }
+
+    let mut i = 0;
+
+    while i < 4 {
+        sum *= sum;
+        i += 1;
+    }
+
+    sum
+}
+
+/// Gamma correction table for `exponent`, computed at compile time when
+/// used in a `const` or `static`.
+pub const fn gamma_table(exponent: f64) -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut index = 1;
+
+    while index < 256 {
+        let value = exp(exponent * ln(index as f64 / 255.0));
+
+        table[index] = (value * 255.0 + 0.5) as u8;
+        index += 1;
+    }
+
+    table
+}
+
+/// Gamma 2.2, close to the sRGB curve.
+#[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+#[cfg_attr(target_arch = "avr", unsafe(link_section = ".progmem.data"))]
+pub static GAMMA_2_2: ProgMem<256> = unsafe { ProgMem::new(gamma_table(2.2)) };
+
+#[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+#[cfg_attr(target_arch = "avr", unsafe(link_section = ".progmem.data"))]
+pub static GAMMA_2_5: ProgMem<256> = unsafe { ProgMem::new(gamma_table(2.5)) };
+
+/// Gamma 2.8, the usual match for WS2812B.
+#[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+#[cfg_attr(target_arch = "avr", unsafe(link_section = ".progmem.data"))]
+pub static GAMMA_2_8: ProgMem<256> = unsafe { ProgMem::new(gamma_table(2.8)) };
+
+/// Correct a 16-bit channel with a 256 entries `table`, interpolating between
+/// entries so 16-bit colors keep their precision.
+pub fn gamma16(table: &dyn ByteSource, value: u16) -> u16 {
+    let last = table.len().saturating_sub(1);
+
+    if last == 0 {
+        return value;
+    }
+
+    let position = u32::from(value) * 256 / 257;
+    let index = (position >> 8) as usize;
+    let from = u32::from(table.byte(index.min(last)));
+    let to = u32::from(table.byte((index + 1).min(last)));
+    let fraction = position & 0xFF;
+
+    ((from * 256 + to * fraction - from * fraction) * 257 / 256) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gamma::{gamma16, gamma_table, GAMMA_2_2, GAMMA_2_8};
+
+    #[test]
+    fn tables() {
+        let select = |table: [u8; 256]| [1, 64, 128, 200, 254, 255].map(|index| table[index]);
+
+        assert_eq!(select(gamma_table(2.2)), [0, 12, 56, 149, 253, 255]);
+        assert_eq!(select(gamma_table(2.5)), [0, 8, 46, 139, 253, 255]);
+        assert_eq!(select(gamma_table(2.8)), [0, 5, 37, 129, 252, 255]);
+        assert_eq!(gamma_table(2.0)[16], 1);
+    }
+
+    #[test]
+    fn interpolate() {
+        assert_eq!(gamma16(&GAMMA_2_2, 128 * 257), 56 * 257);
+        assert_eq!(gamma16(&GAMMA_2_8, 65_535), 65_535);
+        assert_eq!(gamma16(&GAMMA_2_8, 0), 0);
+
+        let between = gamma16(&GAMMA_2_8, 200 * 257 + 128);
+        assert!(between > 129 * 257 && between < 131 * 257);
+    }
+}
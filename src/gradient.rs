@@ -0,0 +1,310 @@
+use crate::flash::ByteSource;
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Color at `position` of colors evenly spread across 0..=255.
+pub fn palette_at(palette: &[Color], position: u8) -> Color {
+    spread(palette.len(), |index| palette[index], position)
+}
+
+/// Color at `position` of `len` colors evenly spread across 0..=255.
+fn spread(len: usize, color: impl Fn(usize) -> Color, position: u8) -> Color {
+    let last = len.saturating_sub(1);
+
+    if len == 0 {
+        return Color::Black;
+    }
+
+    if last == 0 {
+        return color(0);
+    }
+
+    let scaled = usize::from(position) * last;
+    let (index, fraction) = (scaled / 255, (scaled % 255) as u8);
+
+    match index < last {
+        true => color(index).lerp(color(index + 1), fraction),
+        false => color(index),
+    }
+}
+
+/// RGB bytes of a palette, for a `FlashPalette` built at compile time, `M`
+/// must be 3 bytes per color.
+pub const fn rgb_bytes<const N: usize, const M: usize>(colors: [Color; N]) -> [u8; M] {
+    assert!(M == 3 * N, "expected 3 bytes per color");
+
+    let mut bytes = [0; M];
+    let mut index = 0;
+
+    while index < N {
+        let (red, green, blue) = colors[index].to_rgb();
+        bytes[3 * index] = red;
+        bytes[3 * index + 1] = green;
+        bytes[3 * index + 2] = blue;
+        index += 1;
+    }
+
+    bytes
+}
+
+/// Palette of RGB bytes, e.g. in flash so it takes no RAM:
+///
+/// ```ignore
+/// #[unsafe(link_section = ".progmem.data")]
+/// static LAVA: ProgMem<9> = unsafe { ProgMem::new(rgb_bytes([Color::Black, Color::Red, Color::Yellow])) };
+///
+/// led_strip.each(|led_index| FlashPalette(&LAVA).at(led_index as u8 * 8));
+/// ```
+#[derive(Copy, Clone)]
+pub struct FlashPalette<'a>(pub &'a dyn ByteSource);
+
+impl FlashPalette<'_> {
+    pub fn len(&self) -> usize {
+        self.0.len() / 3
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn color(&self, index: usize) -> Color {
+        Color::RGB(self.0.byte(3 * index), self.0.byte(3 * index + 1), self.0.byte(3 * index + 2))
+    }
+
+    /// Color at `position`, as `palette_at()`.
+    pub fn at(&self, position: u8) -> Color {
+        spread(self.len(), |index| self.color(index), position)
+    }
+}
+
+/// Color at `position` of `len` stops sorted by position.
+fn blend(len: usize, stop: impl Fn(usize) -> Stop, position: u8) -> Color {
+    if len == 0 {
+        return Color::Black;
+    }
+
+    let first = stop(0);
+
+    if position <= first.position {
+        return first.color;
+    }
+
+    let mut from = first;
+
+    for index in 1..len {
+        let to = stop(index);
+
+        if position <= to.position {
+            let span = u16::from(to.position - from.position).max(1);
+            let fraction = u16::from(position - from.position) * 255 / span;
+
+            return from.color.lerp(to.color, fraction as u8);
+        }
+
+        from = to;
+    }
+
+    from.color
+}
+
+/// Color of a `Gradient` at a `position` from 0 to 255.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stop {
+    pub position: u8,
+    pub color: Color,
+}
+
+/// Colors blended between `N` stops, sorted by position, e.g. a sunset:
+///
+/// ```ignore
+/// const SUNSET: Gradient<3> = Gradient::new([
+///     (0, Color::RGB(120, 0, 0)),
+///     (96, Color::Orange),
+///     (255, Color::RGB(255, 230, 160)),
+/// ]);
+/// ```
+///
+/// Before the first stop and after the last, their color is used.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Gradient<const N: usize> {
+    stops: [Stop; N],
+}
+
+impl<const N: usize> Gradient<N> {
+    pub const fn new(stops: [(u8, Color); N]) -> Gradient<N> {
+        let mut converted = [Stop { position: 0, color: Color::Black }; N];
+        let mut index = 0;
+
+        while index < N {
+            converted[index] = Stop { position: stops[index].0, color: stops[index].1 };
+            index += 1;
+        }
+
+        Gradient {
+            stops: converted,
+        }
+    }
+
+    /// Stops evenly spread from 0 to 255.
+    pub const fn even(colors: [Color; N]) -> Gradient<N> {
+        let mut stops = [Stop { position: 0, color: Color::Black }; N];
+        let mut index = 0;
+
+        while index < N {
+            let position = match N {
+                1 => 0,
+                _ => index * 255 / (N - 1),
+            };
+            stops[index] = Stop { position: position as u8, color: colors[index] };
+            index += 1;
+        }
+
+        Gradient {
+            stops,
+        }
+    }
+
+    pub const fn stops(&self) -> &[Stop; N] {
+        &self.stops
+    }
+
+    /// Color at `position`, linearly blended between the stops around it.
+    pub fn sample(&self, position: u8) -> Color {
+        blend(N, |index| self.stops[index], position)
+    }
+
+    /// Color of the LED at `led_index`, the gradient spanning `led_count` LEDs.
+    pub fn color(&self, led_index: usize, led_count: usize) -> Color {
+        self.sample(led_position(led_index, led_count))
+    }
+
+    /// `M` colors sampled evenly, e.g. as the palette of an effect.
+    pub fn palette<const M: usize>(&self) -> [Color; M] {
+        core::array::from_fn(|index| self.color(index, M))
+    }
+
+    /// Fill the strip with the gradient, from the first LED to the last.
+    pub fn show<T: Transmitter>(&self, led_strip: &mut LedStrip<T>) {
+        let led_count = led_strip.led_count();
+
+        led_strip.each(|led_index| self.color(led_index, led_count));
+    }
+
+    /// Stops as position and RGB bytes, for a `FlashGradient` built at
+    /// compile time, `M` must be 4 bytes per stop.
+    pub const fn to_bytes<const M: usize>(&self) -> [u8; M] {
+        assert!(M == 4 * N, "expected 4 bytes per stop");
+
+        let mut bytes = [0; M];
+        let mut index = 0;
+
+        while index < N {
+            let Stop { position, color } = self.stops[index];
+            let (red, green, blue) = color.to_rgb();
+            bytes[4 * index] = position;
+            bytes[4 * index + 1] = red;
+            bytes[4 * index + 2] = green;
+            bytes[4 * index + 3] = blue;
+            index += 1;
+        }
+
+        bytes
+    }
+}
+
+/// Position of the LED at `led_index` on a gradient spanning `led_count` LEDs.
+fn led_position(led_index: usize, led_count: usize) -> u8 {
+    let position = match led_count {
+        0 | 1 => 0,
+        _ => led_index.min(led_count - 1) * 255 / (led_count - 1),
+    };
+
+    position as u8
+}
+
+/// Gradient stored as `Gradient::to_bytes()`, e.g. in flash so it takes no RAM:
+///
+/// ```ignore
+/// const SUNSET: Gradient<3> = Gradient::new([(0, Color::RGB(120, 0, 0)), (96, Color::Orange), (255, Color::White)]);
+///
+/// #[unsafe(link_section = ".progmem.data")]
+/// static SUNSET_STOPS: ProgMem<12> = unsafe { ProgMem::new(SUNSET.to_bytes()) };
+///
+/// FlashGradient(&SUNSET_STOPS).show(&mut led_strip);
+/// ```
+#[derive(Copy, Clone)]
+pub struct FlashGradient<'a>(pub &'a dyn ByteSource);
+
+impl FlashGradient<'_> {
+    pub fn len(&self) -> usize {
+        self.0.len() / 4
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stop(&self, index: usize) -> Stop {
+        let byte = |offset| self.0.byte(4 * index + offset);
+
+        Stop {
+            position: byte(0),
+            color: Color::RGB(byte(1), byte(2), byte(3)),
+        }
+    }
+
+    /// Color at `position`, as `Gradient::sample()`.
+    pub fn sample(&self, position: u8) -> Color {
+        blend(self.len(), |index| self.stop(index), position)
+    }
+
+    pub fn color(&self, led_index: usize, led_count: usize) -> Color {
+        self.sample(led_position(led_index, led_count))
+    }
+
+    pub fn show<T: Transmitter>(&self, led_strip: &mut LedStrip<T>) {
+        let led_count = led_strip.led_count();
+
+        led_strip.each(|led_index| self.color(led_index, led_count));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gradient::{palette_at, rgb_bytes, FlashGradient, FlashPalette, Gradient};
+    use crate::led::Color;
+
+    #[test]
+    fn sample() {
+        let gradient = Gradient::new([(64, Color::Red), (128, Color::Blue), (255, Color::White)]);
+
+        assert_eq!(gradient.sample(0), Color::Red);
+        assert_eq!(gradient.sample(96).to_rgb(), (128, 0, 127));
+        assert_eq!(gradient.sample(128).to_rgb(), (0, 0, 255));
+        assert_eq!(gradient.sample(255).to_rgb(), (255, 255, 255));
+        assert_eq!(Gradient::new([]).sample(10), Color::Black);
+    }
+
+    #[test]
+    fn palette() {
+        const FIRE: Gradient<3> = Gradient::even([Color::Black, Color::Red, Color::Yellow]);
+
+        assert_eq!(FIRE.stops()[1].position, 127);
+        assert_eq!(FIRE.palette::<2>().map(|color| color.to_rgb()), [(0, 0, 0), (255, 255, 0)]);
+        assert_eq!(FIRE.color(2, 5).to_rgb(), (255, 0, 0));
+    }
+
+    #[test]
+    fn flash() {
+        const FIRE: Gradient<3> = Gradient::even([Color::Black, Color::HSV(0, 255, 255), Color::Yellow]);
+        static STOPS: [u8; 12] = FIRE.to_bytes();
+        static LAVA: [u8; 6] = rgb_bytes([Color::RGBA(255, 0, 0, 51), Color::NUM(0x00FF00)]);
+
+        assert_eq!(STOPS[4..8], [127, 255, 0, 0]);
+        assert!((0..=255).all(|position| FlashGradient(&STOPS).sample(position).to_rgb() == FIRE.sample(position).to_rgb()));
+        assert_eq!(FlashPalette(&LAVA).len(), 2);
+        assert_eq!(FlashPalette(&LAVA).color(0).to_rgb(), (51, 0, 0));
+        assert_eq!(FlashPalette(&LAVA).at(128), palette_at(&[Color::RGB(51, 0, 0), Color::Green], 128));
+        assert_eq!(FlashPalette(&[]).at(128), Color::Black);
+    }
+}
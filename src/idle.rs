@@ -0,0 +1,139 @@
+use crate::led::{Color, LedStrip, Transmitter};
+use crate::math::{scale8, sin8};
+
+/// What to show once no frame has been received for a while.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Idle {
+    Off,
+    /// Slowly pulse the whole strip, a breath taking `period_ms`.
+    Breathing { color: Color, period_ms: u16 },
+}
+
+/// Fallback for the streaming receivers (Adalight, TPM2, DMX): when the host
+/// stops sending frames, switch to an idle animation instead of freezing on
+/// the last frame.
+///
+/// ```ignore
+/// if adalight.push(byte, &mut led_strip) {
+///     idle.frame_received(millis());
+/// }
+///
+/// if pacer.ready(millis()) {
+///     idle.show(&mut led_strip, millis());
+/// }
+/// ```
+///
+/// The receivers don't keep the frames, so the animation fades in from black.
+pub struct IdleFallback {
+    timeout_ms: u32,
+    fade_ms: u16,
+    idle: Idle,
+    last_frame_ms: Option<u32>,
+}
+
+impl IdleFallback {
+    pub fn new(timeout_ms: u32, idle: Idle) -> IdleFallback {
+        IdleFallback {
+            timeout_ms,
+            fade_ms: 1_000,
+            idle,
+            last_frame_ms: None,
+        }
+    }
+
+    /// Duration of the fade in of the idle animation.
+    pub fn fade(mut self, fade_ms: u16) -> IdleFallback {
+        self.fade_ms = fade_ms;
+
+        self
+    }
+
+    pub fn frame_received(&mut self, now_ms: u32) {
+        self.last_frame_ms = Some(now_ms);
+    }
+
+    /// Time in the idle animation, `None` while frames are coming in; it's
+    /// idle from the start until the first frame.
+    pub fn idle_time(&self, now_ms: u32) -> Option<u32> {
+        let Some(last_frame_ms) = self.last_frame_ms else {
+            return Some(now_ms);
+        };
+
+        now_ms.wrapping_sub(last_frame_ms).checked_sub(self.timeout_ms)
+    }
+
+    pub fn is_idle(&self, now_ms: u32) -> bool {
+        self.idle_time(now_ms).is_some()
+    }
+
+    pub fn color(&self, now_ms: u32) -> Color {
+        let Some(time) = self.idle_time(now_ms) else {
+            return Color::Black;
+        };
+        let fade = match self.fade_ms {
+            0 => 255,
+            fade_ms => (time.min(u32::from(fade_ms)) * 255 / u32::from(fade_ms)) as u8,
+        };
+
+        match self.idle {
+            Idle::Off => Color::Black,
+            Idle::Breathing { color, period_ms } => {
+                let phase = (time % u32::from(period_ms.max(1)) * 256 / u32::from(period_ms.max(1))) as u8;
+                // Start from the dark point of the breath.
+                let level = scale8(sin8(phase.wrapping_sub(64)), fade);
+
+                color * level
+            },
+        }
+    }
+
+    /// Send the idle animation if idle and no frame is being received
+    /// (`LedStrip::is_latched()`), return whether it was sent.
+    pub fn show<T: Transmitter>(&self, led_strip: &mut LedStrip<T>, now_ms: u32) -> bool {
+        if !self.is_idle(now_ms) || !led_strip.is_latched() {
+            return false;
+        }
+
+        let color = self.color(now_ms);
+        led_strip.each(|_| color);
+        led_strip.latch();
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::idle::{Idle, IdleFallback};
+    use crate::led::{Color, LedStrip};
+    use crate::mock::MockPin;
+
+    #[test]
+    fn fallback() {
+        let breathing = Idle::Breathing { color: Color::RGB(0, 0, 200), period_ms: 2_000 };
+        let mut idle = IdleFallback::new(5_000, breathing).fade(0);
+
+        assert!(idle.is_idle(10));
+        idle.frame_received(100);
+        assert!(!idle.is_idle(5_099));
+        assert_eq!(idle.color(5_099), Color::Black);
+        assert_eq!(idle.idle_time(5_600), Some(500));
+        assert_eq!(idle.color(5_100), Color::RGB(0, 0, 1));
+        assert_eq!(idle.color(6_100), Color::RGB(0, 0, 200));
+
+        let mut led_strip = LedStrip::with_transmitter(1, MockPin::<256>::new());
+        assert!(!idle.show(&mut led_strip, 200));
+
+        led_strip.color(Color::Red);
+        assert!(!idle.show(&mut led_strip, 6_100));
+
+        led_strip.latch();
+        assert!(idle.show(&mut led_strip, 6_100));
+        assert!(led_strip.transmitter().bytes().skip(3).eq([0, 0, 200]));
+
+        let idle = IdleFallback::new(0, breathing);
+        assert_eq!(idle.color(0), Color::RGB(0, 0, 0));
+        assert_eq!(idle.color(1_500), Color::RGB(0, 0, 100));
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(target_arch = "avr")]
+use core::arch::asm;
+
+/// When `LedStrip` masks the interrupts while sending pixels.
+///
+/// Bit-banged pins need exact timings, an interrupt in the middle of a bit
+/// can stretch it into the wrong one, but masking them for a whole frame
+/// delays `millis()` and serial reception by a long time: with `PerPixel`,
+/// interrupts are only masked while the 24 bits of a pixel are sent
+/// (30 µs), the pending ones running between pixels, which the LEDs
+/// tolerate as long as the handlers take less than the latch time.
+///
+/// Masking goes through `without_interrupts()`, see there for each target.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InterruptMasking {
+    /// Interrupts are left as they are, e.g. already masked by the application.
+    None,
+    PerPixel,
+}
+
+/// Run `f` with interrupts disabled, restoring the previous state after.
+#[cfg(target_arch = "avr")]
+pub fn without_interrupts<R>(f: impl FnOnce() -> R) -> R {
+    let sreg: u8;
+
+    unsafe {
+        asm!("in {}, 0x3F", "cli", out(reg) sreg, options(nostack));
+    }
+
+    let result = f();
+
+    unsafe {
+        asm!("out 0x3F, {}", in(reg) sreg, options(nostack));
+    }
+
+    result
+}
+
+/// Run `f` in a critical section, nested ones included: interrupts are
+/// masked by the implementation of the target HAL (`rp2040-hal` with the
+/// `rp2040` feature, `esp-hal`), with `std` (the simulator) it's a global
+/// lock shared by the threads.
+#[cfg(not(target_arch = "avr"))]
+pub fn without_interrupts<R>(f: impl FnOnce() -> R) -> R {
+    critical_section::with(|_| f())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interrupt::without_interrupts;
+    use crate::queue::Queue;
+
+    #[test]
+    fn nested() {
+        static QUEUE: Queue<u8, 2> = Queue::new();
+
+        // A pixel sent with `PerPixel` while an interrupt handler posts to a queue.
+        let posted = without_interrupts(|| without_interrupts(|| QUEUE.post(7)));
+
+        assert!(posted.is_ok());
+        assert_eq!(QUEUE.take(), Some(7));
+    }
+}
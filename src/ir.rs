@@ -0,0 +1,244 @@
+use crate::effect::Scheduler;
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Brightness change of `Action::BrightnessUp` and `Action::BrightnessDown`.
+pub const BRIGHTNESS_STEP: u8 = 32;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Event {
+    /// Key pressed, `address` is 16 bits for remotes using the extended NEC addresses.
+    Code { address: u16, command: u8 },
+    /// Key still held, sent about every 110 ms after a `Code`.
+    Repeat,
+}
+
+/// NEC infrared protocol decoder, timing the falling edges of an IR
+/// receiver output (active low, e.g. TSOP38238) on a pin.
+///
+/// Call `falling_edge()` with a microsecond timestamp from the pin change
+/// interrupt. Each burst starts with a falling edge, so the duration between
+/// two of them is enough to tell a leader (13.5 ms), a repeat (11.25 ms),
+/// a 0 (1.125 ms) and a 1 (2.25 ms) apart; bits are sent least significant
+/// first: address, inverted address, command, inverted command.
+pub struct Nec {
+    last_edge: Option<u32>,
+    /// Bits received since the leader, `None` when waiting for one.
+    count: Option<u8>,
+    bits: u32,
+    repeatable: bool,
+}
+
+impl Nec {
+    pub fn new() -> Nec {
+        Nec {
+            last_edge: None,
+            count: None,
+            bits: 0,
+            repeatable: false,
+        }
+    }
+
+    pub fn falling_edge(&mut self, now_us: u32) -> Option<Event> {
+        let interval = self.last_edge.map(|last_edge| now_us.wrapping_sub(last_edge));
+        self.last_edge = Some(now_us);
+
+        match (interval?, self.count) {
+            (12_000..=15_000, _) => {
+                self.count = Some(0);
+                self.bits = 0;
+                self.repeatable = false;
+            },
+            (10_000..12_000, _) => {
+                self.count = None;
+
+                if self.repeatable {
+                    return Some(Event::Repeat);
+                }
+            },
+            (interval @ (800..=2_800), Some(count)) => {
+                let bit = u32::from(interval >= 1_700);
+                self.bits |= bit << count;
+                self.count = Some(count + 1);
+
+                if count == 31 {
+                    return self.code();
+                }
+            },
+            // Gaps between codes and repeats, or noise.
+            _ => {
+                self.count = None;
+
+                if interval? > 120_000 {
+                    self.repeatable = false;
+                }
+            },
+        }
+
+        None
+    }
+
+    fn code(&mut self) -> Option<Event> {
+        let [address, inverted_address, command, inverted_command] = self.bits.to_le_bytes();
+        self.count = None;
+
+        if command ^ inverted_command != 0xFF {
+            return None;
+        }
+
+        self.repeatable = true;
+
+        Some(Event::Code {
+            address: match address ^ inverted_address {
+                0xFF => u16::from(address),
+                _ => u16::from_le_bytes([address, inverted_address]),
+            },
+            command,
+        })
+    }
+}
+
+impl Default for Nec {
+    fn default() -> Self {
+        Nec::new()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Action {
+    BrightnessUp,
+    BrightnessDown,
+    Off,
+    On,
+    NextEffect,
+    PreviousEffect,
+    Color(Color),
+}
+
+/// Action of a key of the common 24-key RGB remote, from its command code.
+pub fn remote_action(command: u8) -> Option<Action> {
+    Some(match command {
+        0x00 => Action::BrightnessUp,
+        0x01 => Action::BrightnessDown,
+        0x02 => Action::Off,
+        0x03 => Action::On,
+        0x04 => Action::Color(Color::Red),
+        0x05 => Action::Color(Color::Green),
+        0x06 => Action::Color(Color::Blue),
+        0x07 => Action::Color(Color::White),
+        0x08 => Action::Color(Color::Orange),
+        0x09 => Action::Color(Color::RGB(0, 255, 127)),
+        0x0A => Action::Color(Color::RGB(0, 127, 255)),
+        0x0B | 0x13 => Action::NextEffect,
+        0x0C => Action::Color(Color::RGB(255, 191, 0)),
+        0x0D => Action::Color(Color::Cyan),
+        0x0E => Action::Color(Color::Purple),
+        0x0F | 0x17 => Action::PreviousEffect,
+        0x10 => Action::Color(Color::Yellow),
+        0x11 => Action::Color(Color::Turquoise),
+        0x12 => Action::Color(Color::Magenta),
+        0x14 => Action::Color(Color::RGB(255, 255, 127)),
+        0x15 => Action::Color(Color::RGB(0, 127, 127)),
+        0x16 => Action::Color(Color::Pink),
+        _ => return None,
+    })
+}
+
+/// Apply a remote action to the scheduler and the strip, return `false`
+/// for `Action::Color`, which is left to the application.
+pub fn apply<T: Transmitter, const N: usize>(
+    action: Action,
+    scheduler: &mut Scheduler<'_, N>,
+    led_strip: &mut LedStrip<T>,
+) -> bool {
+    match action {
+        Action::BrightnessUp => led_strip.set_brightness(led_strip.brightness_setting().saturating_add(BRIGHTNESS_STEP)),
+        Action::BrightnessDown => led_strip.set_brightness(led_strip.brightness_setting().saturating_sub(BRIGHTNESS_STEP)),
+        Action::Off => scheduler.set_enabled(false),
+        Action::On => scheduler.set_enabled(true),
+        Action::NextEffect => scheduler.next(),
+        Action::PreviousEffect => scheduler.previous(),
+        Action::Color(_) => return false,
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::effect::{Effect, Scheduler};
+    use crate::ir::{apply, remote_action, Action, Event, Nec};
+    use crate::led::{Color, LedStrip};
+    use crate::mock::MockPin;
+
+    fn send(nec: &mut Nec, time: &mut u32, intervals: impl Iterator<Item = u32>) -> Option<Event> {
+        let mut event = None;
+
+        for interval in intervals {
+            *time += interval;
+            event = nec.falling_edge(*time).or(event);
+        }
+
+        event
+    }
+
+    #[test]
+    fn decode() {
+        let mut nec = Nec::new();
+        let mut time = 0;
+        let code = u32::from_le_bytes([0x00, 0xFF, 0x05, 0xFA]);
+        let bits = (0..32).map(|bit| match (code >> bit) & 1 {
+            1 => 2_250,
+            _ => 1_125,
+        });
+
+        nec.falling_edge(time);
+
+        assert_eq!(
+            send(&mut nec, &mut time, [13_500].into_iter().chain(bits.clone())),
+            Some(Event::Code { address: 0x00, command: 0x05 }),
+        );
+        assert_eq!(send(&mut nec, &mut time, [40_000, 11_250].into_iter()), Some(Event::Repeat));
+        assert_eq!(send(&mut nec, &mut time, [200_000, 11_250].into_iter()), None);
+
+        let corrupted = bits.enumerate().map(|(bit, interval)| if bit == 20 { 2_250 } else { interval });
+
+        assert_eq!(send(&mut nec, &mut time, [13_500].into_iter().chain(corrupted)), None);
+    }
+
+    #[test]
+    fn remote() {
+        let solid = |_, _| Color::Red;
+        let mut scheduler: Scheduler<2> = Scheduler::new();
+        let mut led_strip = LedStrip::with_transmitter(1, MockPin::<1>::new());
+        scheduler.add(Effect::new("solid", &solid));
+        scheduler.add(Effect::new("other", &solid));
+
+        assert_eq!(remote_action(0x06), Some(Action::Color(Color::Blue)));
+        assert_eq!(remote_action(0x42), None);
+
+        assert!(apply(Action::BrightnessDown, &mut scheduler, &mut led_strip));
+        assert_eq!(led_strip.brightness(), 223);
+        assert!(apply(Action::NextEffect, &mut scheduler, &mut led_strip));
+        assert_eq!(scheduler.index(), 1);
+        assert!(apply(Action::Off, &mut scheduler, &mut led_strip));
+        assert!(!scheduler.is_enabled());
+        assert!(!apply(Action::Color(Color::Red), &mut scheduler, &mut led_strip));
+    }
+
+    #[test]
+    fn curved_brightness() {
+        use crate::correction::DimmingCurve;
+
+        let mut scheduler: Scheduler<1> = Scheduler::new();
+        let mut led_strip = LedStrip::with_transmitter(1, MockPin::<1>::new());
+        led_strip.set_dimming_curve(DimmingCurve::Square);
+        led_strip.set_soft_start(10);
+        led_strip.set_brightness(128);
+
+        // Stepped from the setting, not from the dimmer applied brightness.
+        assert!(apply(Action::BrightnessUp, &mut scheduler, &mut led_strip));
+        assert_eq!(led_strip.brightness_setting(), 160);
+        assert!(apply(Action::BrightnessDown, &mut scheduler, &mut led_strip));
+        assert_eq!(led_strip.brightness_setting(), 128);
+    }
+}
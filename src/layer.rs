@@ -0,0 +1,230 @@
+use heapless::Vec;
+use crate::led::{Color, LedStrip, Transmitter};
+use crate::mask::Mask;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BlendMode {
+    /// Replace what's below, alpha-composited for `Color::RGBA`.
+    Normal,
+    /// Draw over what's below, black pixels let it show through.
+    Over,
+    /// Average with what's below.
+    Mix,
+    /// Saturating sum with what's below.
+    Add,
+    /// Product with what's below, darkening it.
+    Multiply,
+    /// Inverse of the product of inverses, brightening what's below.
+    Screen,
+    /// Brightest of the two for each channel.
+    Lighten,
+}
+
+impl BlendMode {
+    pub fn blend(self, top: Color, bottom: Color) -> Color {
+        match self {
+            BlendMode::Normal => top.over(bottom),
+            BlendMode::Over => match top.to_rgb() {
+                (0, 0, 0) => bottom,
+                _ => top,
+            },
+            BlendMode::Mix => top.mix(bottom),
+            BlendMode::Add => top + bottom,
+            BlendMode::Multiply => channels(top, bottom, |top, bottom| {
+                (u16::from(top) * u16::from(bottom) / 255) as u8
+            }),
+            BlendMode::Screen => channels(top, bottom, |top, bottom| {
+                255 - ((255 - u16::from(top)) * (255 - u16::from(bottom)) / 255) as u8
+            }),
+            BlendMode::Lighten => channels(top, bottom, u8::max),
+        }
+    }
+}
+
+fn channels(top: Color, bottom: Color, blend: impl Fn(u8, u8) -> u8) -> Color {
+    let (red, green, blue) = top.to_rgb();
+    let (bottom_red, bottom_green, bottom_blue) = bottom.to_rgb();
+
+    Color::RGB(blend(red, bottom_red), blend(green, bottom_green), blend(blue, bottom_blue))
+}
+
+#[derive(Copy, Clone)]
+pub struct Layer<'a> {
+    render: &'a dyn Fn(usize) -> Color,
+    mode: BlendMode,
+    mask: Option<&'a dyn Mask>,
+    visible: bool,
+}
+
+impl<'a> Layer<'a> {
+    pub fn new(render: &'a dyn Fn(usize) -> Color, mode: BlendMode) -> Layer<'a> {
+        Layer {
+            render,
+            mode,
+            mask: None,
+            visible: true,
+        }
+    }
+
+    /// Only let the layer touch the LEDs allowed by the mask.
+    pub fn with_mask(mut self, mask: &'a dyn Mask) -> Layer<'a> {
+        self.mask = Some(mask);
+
+        self
+    }
+
+    pub fn mode(&self) -> BlendMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: BlendMode) {
+        self.mode = mode;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn blend(&self, led_index: usize, below: Color) -> Color {
+        if !self.visible {
+            return below;
+        }
+
+        let color = self.mode.blend((self.render)(led_index), below);
+
+        match self.mask {
+            Some(mask) => mask.apply(led_index, color, below),
+            None => color,
+        }
+    }
+}
+
+/// Stack of up to `N` layers, composited from the first added (bottom)
+/// to the last added (top) for each LED.
+pub struct Layers<'a, const N: usize> {
+    layers: Vec<Layer<'a>, N>,
+}
+
+impl<'a, const N: usize> Layers<'a, N> {
+    pub fn new() -> Layers<'a, N> {
+        Layers {
+            layers: Vec::new(),
+        }
+    }
+
+    /// Add a layer on top of the others, return its index or `None` when full.
+    pub fn add(&mut self, layer: Layer<'a>) -> Option<usize> {
+        self.layers.push(layer).ok()?;
+
+        Some(self.layers.len() - 1)
+    }
+
+    /// Take the layer at `index` out of the stack, the ones above move down.
+    pub fn remove(&mut self, index: usize) -> Option<Layer<'a>> {
+        (index < self.layers.len()).then(|| self.layers.remove(index))
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Layer<'a>> {
+        self.layers.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub fn color(&self, led_index: usize) -> Color {
+        self.layers.iter()
+            .fold(Color::Black, |below, layer| layer.blend(led_index, below))
+    }
+
+    pub fn show<T: Transmitter>(&self, led_strip: &mut LedStrip<T>) {
+        led_strip.each(|led_index| self.color(led_index));
+    }
+}
+
+impl<const N: usize> Default for Layers<'_, N> {
+    fn default() -> Self {
+        Layers::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::layer::{BlendMode, Layer, Layers};
+    use crate::led::Color;
+    use crate::mask::Stride;
+
+    #[test]
+    fn composite() {
+        let background = |_| Color::Blue;
+        let indicator = |led_index| match led_index {
+            0 => Color::Red,
+            _ => Color::Black,
+        };
+        let flash = |_| Color::White;
+        let mut layers: Layers<3> = Layers::new();
+
+        assert_eq!(layers.add(Layer::new(&background, BlendMode::Normal)), Some(0));
+        assert_eq!(layers.add(Layer::new(&indicator, BlendMode::Over)), Some(1));
+        assert_eq!(layers.color(0).to_rgb(), (255, 0, 0));
+        assert_eq!(layers.color(1).to_rgb(), (0, 0, 255));
+
+        assert_eq!(layers.add(Layer::new(&flash, BlendMode::Mix)), Some(2));
+        assert_eq!(layers.color(1).to_rgb(), (127, 127, 255));
+        assert_eq!(layers.add(Layer::new(&flash, BlendMode::Mix)), None);
+
+        let dimmed = |_| Color::RGBA(255, 0, 0, 51);
+        let mut layers: Layers<2> = Layers::new();
+        layers.add(Layer::new(&background, BlendMode::Normal));
+        layers.add(Layer::new(&dimmed, BlendMode::Normal));
+        assert_eq!(layers.color(0).to_rgb(), (51, 0, 204));
+    }
+
+    #[test]
+    fn blend_modes() {
+        let top = Color::RGB(200, 100, 0);
+        let bottom = Color::RGB(100, 200, 50);
+
+        assert_eq!(BlendMode::Add.blend(top, bottom), Color::RGB(255, 255, 50));
+        assert_eq!(BlendMode::Multiply.blend(top, bottom), Color::RGB(78, 78, 0));
+        assert_eq!(BlendMode::Screen.blend(top, bottom), Color::RGB(222, 222, 50));
+        assert_eq!(BlendMode::Lighten.blend(top, bottom), Color::RGB(200, 200, 50));
+        assert_eq!(BlendMode::Multiply.blend(Color::White, bottom), bottom);
+        assert_eq!(BlendMode::Screen.blend(Color::Black, bottom), bottom);
+    }
+
+    #[test]
+    fn visibility() {
+        let background = |_| Color::Blue;
+        let flash = |_| Color::White;
+        let mut layers: Layers<2> = Layers::new();
+        layers.add(Layer::new(&background, BlendMode::Normal));
+        layers.add(Layer::new(&flash, BlendMode::Mix));
+
+        layers.get_mut(1).unwrap().set_visible(false);
+        layers.get_mut(0).unwrap().set_mode(BlendMode::Over);
+        assert_eq!(layers.color(0).to_rgb(), (0, 0, 255));
+    }
+
+    #[test]
+    fn mask() {
+        let background = |_| Color::Blue;
+        let rainbow = |_| Color::Green;
+        let even = Stride { step: 2, offset: 0 };
+        let mut layers: Layers<2> = Layers::new();
+        layers.add(Layer::new(&background, BlendMode::Normal));
+        layers.add(Layer::new(&rainbow, BlendMode::Normal).with_mask(&even));
+
+        assert_eq!(layers.color(0).to_rgb(), (0, 255, 0));
+        assert_eq!(layers.color(1).to_rgb(), (0, 0, 255));
+    }
+}
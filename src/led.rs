@@ -1,12 +1,37 @@
-use core::ops::Fn;
-use core::convert::From;
+use core::ops::{Add, Fn, Mul, Sub};
+use core::convert::{From, TryFrom};
+use core::fmt;
+#[cfg(feature = "arduino-hal")]
 use arduino_hal::port::{Pin, PinOps};
-use arduino_hal::port::mode::{Floating, Input, Output};
+#[cfg(feature = "arduino-hal")]
+use arduino_hal::port::mode::{Io, OpenDrain, Output};
+use crate::color16::Color16;
+use crate::correction::{combine, Correction, DimmingCurve, Temperature};
+use crate::error::LedError;
+use crate::flash::ByteSource;
+#[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+use crate::interrupt::{without_interrupts, InterruptMasking};
+#[cfg(feature = "dithering")]
+use crate::math::dither;
+use crate::math::{lerp8, scale8};
+use crate::pacer::Pacer;
+use crate::power::{channel_draw, frame_milliamps, PowerLimit, ThermalLimit, VoltageDrop};
+use crate::transform::{Brightness, ChannelScale, Gamma, PixelTransform};
 
-#[derive(Copy, Clone)]
+/// Equality compares the displayed color and its alpha, whatever the
+/// variant: `Color::Red == Color::RGB(255, 0, 0)`.
+#[derive(Copy, Clone, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     RGB(u8, u8, u8),
+    RGBA(u8, u8, u8, u8),
+    /// Hue, saturation and value, the hue goes around the wheel in 256 steps.
+    HSV(u8, u8, u8),
+    /// `0xRRGGBB` number, see `Color::from_u32_rgb()`.
     NUM(u32),
+    /// Only serialized, data files should store parsed colors.
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
     HEX(&'static str),
 
     Black,
@@ -25,19 +50,143 @@ pub enum Color {
     Turquoise,
 }
 
-impl Color {
-    pub fn to_rgb(self) -> (u8, u8, u8) {
+impl PartialEq for Color {
+    fn eq(&self, other: &Color) -> bool {
+        self.to_rgba() == other.to_rgba()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ColorParseError {
+    InvalidLength,
+    InvalidDigit,
+}
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorParseError::InvalidLength => f.write_str("expected RRGGBB, #RRGGBB or RGB"),
+            ColorParseError::InvalidDigit => f.write_str("invalid hexadecimal digit"),
+        }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for ColorParseError {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error> where W: ufmt::uWrite + ?Sized {
         match self {
+            ColorParseError::InvalidLength => f.write_str("expected RRGGBB, #RRGGBB or RGB"),
+            ColorParseError::InvalidDigit => f.write_str("invalid hexadecimal digit"),
+        }
+    }
+}
+
+/// Displayed color as `#RRGGBB`.
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for Color {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error> where W: ufmt::uWrite + ?Sized {
+        const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+        let (red, green, blue) = self.to_rgb();
+
+        f.write_char('#')?;
+
+        for channel in [red, green, blue] {
+            f.write_char(DIGITS[usize::from(channel >> 4)] as char)?;
+            f.write_char(DIGITS[usize::from(channel & 0xF)] as char)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Colors of the base palette with their names.
+#[cfg(feature = "colors-basic")]
+pub const NAMED_COLORS: [(&str, Color); 13] = [
+    ("black", Color::Black),
+    ("gray", Color::Gray),
+    ("white", Color::White),
+    ("red", Color::Red),
+    ("green", Color::Green),
+    ("blue", Color::Blue),
+    ("cyan", Color::Cyan),
+    ("magenta", Color::Magenta),
+    ("yellow", Color::Yellow),
+    ("orange", Color::Orange),
+    ("purple", Color::Purple),
+    ("pink", Color::Pink),
+    ("turquoise", Color::Turquoise),
+];
+
+impl Color {
+    /// Parse `"RRGGBB"`, `"#RRGGBB"`, `"RGB"` or `"#RGB"`.
+    pub fn try_from_hex(color: &str) -> Result<Color, ColorParseError> {
+        parse(color).map(|bytes| Color::RGB(bytes[0], bytes[1], bytes[2]))
+    }
+
+    /// Color of a `0xRRGGBB` number: red in bits 16 to 23, green in bits 8
+    /// to 15 and blue in bits 0 to 7, the 8 highest bits are ignored.
+    pub const fn from_u32_rgb(color: u32) -> Color {
+        Color::RGB((color >> 16) as u8, (color >> 8) as u8, color as u8)
+    }
+
+    /// `0xRRGGBB` number of the displayed color, see `from_u32_rgb()`.
+    pub fn to_u32(&self) -> u32 {
+        let (red, green, blue) = self.to_rgb();
+
+        u32::from(red) << 16 | u32::from(green) << 8 | u32::from(blue)
+    }
+
+    /// Parse a hex color in a `const`, an invalid color fails the build there
+    /// (and panics if called at runtime).
+    pub const fn from_hex(color: &str) -> Color {
+        match parse(color) {
+            Ok([red, green, blue]) => Color::RGB(red, green, blue),
+            Err(ColorParseError::InvalidLength) => panic!("hex color must be RRGGBB, #RRGGBB or RGB"),
+            Err(ColorParseError::InvalidDigit) => panic!("invalid hexadecimal digit in color"),
+        }
+    }
+
+    /// Approximate color of visible light (380 nm to 780 nm), black outside.
+    pub fn from_wavelength(nm: u16) -> Color {
+        let nm = u32::from(nm);
+        let ramp = |from: u32, to: u32| match from < to {
+            true => (255 * (nm - from) / (to - from)) as u8,
+            false => (255 * (from - nm) / (from - to)) as u8,
+        };
+        let (red, green, blue) = match nm {
+            380..=439 => (ramp(440, 380), 0, 255),
+            440..=489 => (0, ramp(440, 490), 255),
+            490..=509 => (0, 255, ramp(510, 490)),
+            510..=579 => (ramp(510, 580), 255, 0),
+            580..=644 => (255, ramp(645, 580), 0),
+            645..=780 => (255, 0, 0),
+            _ => return Color::Black,
+        };
+        let intensity = match nm {
+            380..=419 => 77 + 178 * (nm - 380) / 40,
+            701..=780 => 77 + 178 * (780 - nm) / 80,
+            _ => 255,
+        } as u8;
+
+        Color::RGB(scale8(red, intensity), scale8(green, intensity), scale8(blue, intensity))
+    }
+
+    /// Displayed color, `const` so palettes can be converted at compile time.
+    pub const fn to_rgb(&self) -> (u8, u8, u8) {
+        match *self {
             Color::RGB(red, green, blue) => (red, green, blue),
-            Color::NUM(color) => (
-                ((color << 8) & 0xFF) as u8,
-                (color & 0xFF) as u8,
-                ((color << 16) & 0xFF) as u8,
+            // Over black, as `over(Color::Black)`.
+            Color::RGBA(red, green, blue, alpha) => (
+                premultiply(red, alpha),
+                premultiply(green, alpha),
+                premultiply(blue, alpha),
             ),
-            Color::HEX(color) => {
-                let bytes = parse(color);
-
-                (bytes[0], bytes[1], bytes[2])
+            Color::HSV(hue, saturation, value) => hsv_to_rgb(hue, saturation, value),
+            Color::NUM(color) => Color::from_u32_rgb(color).to_rgb(),
+            Color::HEX(color) => match parse(color) {
+                Ok(bytes) => (bytes[0], bytes[1], bytes[2]),
+                Err(_) => (0, 0, 0),
             },
 
             Color::Black => (0, 0, 0),
@@ -57,7 +206,169 @@ impl Color {
         }
     }
 
-    pub fn opacity(self, opacity: f64) -> Color {
+    pub fn to_hsv(&self) -> (u8, u8, u8) {
+        if let Color::HSV(hue, saturation, value) = *self {
+            return (hue, saturation, value);
+        }
+
+        let (red, green, blue) = self.to_rgb();
+        let max = red.max(green).max(blue);
+        let delta = i32::from(max - red.min(green).min(blue));
+
+        if delta == 0 {
+            return (0, 0, max);
+        }
+
+        let saturation = (255 * delta / i32::from(max)) as u8;
+        let (red, green, blue) = (i32::from(red), i32::from(green), i32::from(blue));
+        let hue = match max {
+            max if i32::from(max) == red => 43 * (green - blue) / delta,
+            max if i32::from(max) == green => 85 + 43 * (blue - red) / delta,
+            _ => 171 + 43 * (red - green) / delta,
+        };
+
+        (hue.rem_euclid(256) as u8, saturation, max)
+    }
+
+    /// Turn the hue around the color wheel, 256 being a full turn.
+    pub fn rotate_hue(&self, amount: u8) -> Color {
+        let (hue, saturation, value) = self.to_hsv();
+
+        Color::HSV(hue.wrapping_add(amount), saturation, value)
+    }
+
+    /// Name of the closest base palette (or CSS with `css-colors`) color
+    /// when it's within `max_distance` (see `distance()`).
+    #[cfg(feature = "colors-basic")]
+    pub fn nearest_named(&self, max_distance: u32) -> Option<(&'static str, Color)> {
+        let named = NAMED_COLORS.iter();
+        #[cfg(feature = "css-colors")]
+        let named = named.chain(crate::css::CSS_COLORS.iter());
+
+        named
+            .map(|&(name, color)| (self.distance(color), name, color))
+            .filter(|&(distance, _, _)| distance <= max_distance)
+            .min_by_key(|&(distance, _, _)| distance)
+            .map(|(_, name, color)| (name, color))
+    }
+
+    /// Perceptually weighted ("redmean") RGB distance, from 0 for identical
+    /// colors to 764 between black and white.
+    pub fn distance(&self, other: Color) -> u32 {
+        let (red, green, blue) = self.to_rgb();
+        let (other_red, other_green, other_blue) = other.to_rgb();
+        let red_mean = (u32::from(red) + u32::from(other_red)) / 2;
+        let square = |first: u8, second: u8| u32::from(first.abs_diff(second)).pow(2);
+
+        (((512 + red_mean) * square(red, other_red)) >> 8)
+            .saturating_add(4 * square(green, other_green))
+            .saturating_add(((767 - red_mean) * square(blue, other_blue)) >> 8)
+            .isqrt()
+    }
+
+    /// Opposite hue on the color wheel.
+    pub fn complement(&self) -> Color {
+        self.rotate_hue(128)
+    }
+
+    /// This color and the two hues a third of the wheel away.
+    pub fn triad(&self) -> [Color; 3] {
+        [*self, self.rotate_hue(85), self.rotate_hue(171)]
+    }
+
+    /// This color surrounded by the hues `spread` steps before and after it.
+    pub fn analogous(&self, spread: u8) -> [Color; 3] {
+        [self.rotate_hue(spread.wrapping_neg()), *self, self.rotate_hue(spread)]
+    }
+
+    /// Interpolate from `self` (0) to `other` (255).
+    pub fn lerp(&self, other: Color, fraction: u8) -> Color {
+        let (red, green, blue) = self.to_rgb();
+        let (other_red, other_green, other_blue) = other.to_rgb();
+
+        Color::RGB(
+            lerp8(red, other_red, fraction),
+            lerp8(green, other_green, fraction),
+            lerp8(blue, other_blue, fraction),
+        )
+    }
+
+    /// Interpolate in linear light (gamma approximated as 2) so intermediate
+    /// colors don't look too dark, e.g. red to green passes through yellow.
+    pub fn lerp_perceptual(&self, other: Color, fraction: u8) -> Color {
+        let (red, green, blue) = self.to_rgb();
+        let (other_red, other_green, other_blue) = other.to_rgb();
+        let channel = |from: u8, to: u8| {
+            let (from, to) = (u32::from(from).pow(2), u32::from(to).pow(2));
+            let linear = (from * (255 - u32::from(fraction)) + to * u32::from(fraction)) / 255;
+
+            linear.isqrt() as u8
+        };
+
+        Color::RGB(channel(red, other_red), channel(green, other_green), channel(blue, other_blue))
+    }
+
+    pub fn mix_perceptual(&self, color: Color) -> Color {
+        self.lerp_perceptual(color, 128)
+    }
+
+    /// Interpolate in HSV space, turning the hue the shortest way around the wheel.
+    pub fn lerp_hsv(&self, other: Color, fraction: u8) -> Color {
+        let (hue, saturation, value) = self.to_hsv();
+        let (other_hue, other_saturation, other_value) = other.to_hsv();
+        let turn = i32::from(other_hue.wrapping_sub(hue) as i8) * i32::from(fraction) / 255;
+
+        Color::HSV(
+            hue.wrapping_add(turn as u8),
+            lerp8(saturation, other_saturation, fraction),
+            lerp8(value, other_value, fraction),
+        )
+    }
+
+    pub fn to_rgba(&self) -> (u8, u8, u8, u8) {
+        match *self {
+            Color::RGBA(red, green, blue, alpha) => (red, green, blue, alpha),
+            _ => {
+                let (red, green, blue) = self.to_rgb();
+
+                (red, green, blue, 255)
+            },
+        }
+    }
+
+    pub fn alpha(&self) -> u8 {
+        self.to_rgba().3
+    }
+
+    /// Alpha-composite this color over the background.
+    pub fn over(&self, background: Color) -> Color {
+        let (red, green, blue, alpha) = self.to_rgba();
+
+        if alpha == 255 {
+            return *self;
+        }
+
+        let (back_red, back_green, back_blue, back_alpha) = background.to_rgba();
+        let alpha = u32::from(alpha);
+        let back_alpha = u32::from(back_alpha) * (255 - alpha) / 255;
+        let out_alpha = alpha + back_alpha;
+
+        if out_alpha == 0 {
+            return Color::RGBA(0, 0, 0, 0);
+        }
+
+        let channel = |front: u8, back: u8| {
+            ((u32::from(front) * alpha + u32::from(back) * back_alpha) / out_alpha) as u8
+        };
+        let (red, green, blue) = (channel(red, back_red), channel(green, back_green), channel(blue, back_blue));
+
+        match out_alpha {
+            255 => Color::RGB(red, green, blue),
+            _ => Color::RGBA(red, green, blue, out_alpha as u8),
+        }
+    }
+
+    pub fn opacity(&self, opacity: f64) -> Color {
         let (red, green, blue) = self.to_rgb();
 
         Color::RGB(
@@ -67,11 +378,11 @@ impl Color {
         )
     }
 
-    pub fn mix(self, color: Color) -> Color {
+    pub fn mix(&self, color: Color) -> Color {
         self.mix_ratio(color, 0.5, 0.5)
     }
 
-    pub fn mix_ratio(self, color: Color, self_ratio: f64, other_ratio: f64) -> Color {
+    pub fn mix_ratio(&self, color: Color, self_ratio: f64, other_ratio: f64) -> Color {
         let (red, green, blue) = self.to_rgb();
         let (other_red, other_green, other_blue) = color.to_rgb();
 
@@ -83,150 +394,2177 @@ impl Color {
     }
 }
 
-pub struct LedStrip<PIN: PinOps> {
-    led_count: usize,
-    pin: Pin<Output, PIN>,
+impl TryFrom<&str> for Color {
+    type Error = ColorParseError;
+
+    fn try_from(color: &str) -> Result<Color, ColorParseError> {
+        Color::try_from_hex(color)
+    }
 }
 
-impl<PIN> LedStrip<PIN> where PIN: PinOps {
-    #[allow(dead_code)]
-    pub fn new(led_count: usize, pin: Pin<Input<Floating>, PIN>) -> LedStrip<PIN> {
-        LedStrip {
-            led_count,
-            pin: pin.into_output(),
-        }
+/// From `0xRRGGBB`, higher bits are ignored.
+impl From<u32> for Color {
+    fn from(color: u32) -> Color {
+        Color::from_u32_rgb(color)
     }
+}
 
-    pub fn each<F>(&mut self, callback: F) where F: (Fn(usize) -> Color) {
-        for led_index in 0..self.led_count {
-            self.color(callback(led_index));
-        }
+impl From<(u8, u8, u8)> for Color {
+    fn from((red, green, blue): (u8, u8, u8)) -> Color {
+        Color::RGB(red, green, blue)
     }
+}
 
-    pub fn hex(&mut self, color: &str) {
-        let bytes = parse(color);
-        self.rgb(bytes[0], bytes[1], bytes[2]);
+impl From<[u8; 3]> for Color {
+    fn from([red, green, blue]: [u8; 3]) -> Color {
+        Color::RGB(red, green, blue)
     }
+}
 
-    pub fn rgb(&mut self, red: u8, green: u8, blue: u8) {
-        send_byte(&mut self.pin, green);
-        send_byte(&mut self.pin, red);
-        send_byte(&mut self.pin, blue);
+impl From<Color> for (u8, u8, u8) {
+    fn from(color: Color) -> (u8, u8, u8) {
+        color.to_rgb()
     }
+}
 
-    pub fn color(&mut self, color: Color) {
+impl From<Color> for [u8; 3] {
+    fn from(color: Color) -> [u8; 3] {
         let (red, green, blue) = color.to_rgb();
 
-        self.rgb(red, green, blue);
+        [red, green, blue]
     }
+}
 
-    pub fn color_number(&mut self, color: u32) {
-        self.rgb(
-            ((color << 8) & 0xFF) as u8,
-            (color & 0xFF) as u8,
-            ((color << 16) & 0xFF) as u8,
-        );
+/// Saturating per-channel addition, e.g. `base + sparkle`.
+impl Add for Color {
+    type Output = Color;
+
+    fn add(self, other: Color) -> Color {
+        let (red, green, blue) = self.to_rgb();
+        let (other_red, other_green, other_blue) = other.to_rgb();
+
+        Color::RGB(
+            red.saturating_add(other_red),
+            green.saturating_add(other_green),
+            blue.saturating_add(other_blue),
+        )
     }
+}
 
-    pub fn rest(&mut self, us: u32) {
-        reset(&mut self.pin, us);
+/// Saturating per-channel subtraction.
+impl Sub for Color {
+    type Output = Color;
+
+    fn sub(self, other: Color) -> Color {
+        let (red, green, blue) = self.to_rgb();
+        let (other_red, other_green, other_blue) = other.to_rgb();
+
+        Color::RGB(
+            red.saturating_sub(other_red),
+            green.saturating_sub(other_green),
+            blue.saturating_sub(other_blue),
+        )
     }
 }
 
-pub fn send_color<PIN: PinOps>(led: &mut Pin<Output, PIN>, color: &[u8]) -> () {
-    let mut bytes = [0; 3];
+/// Scale by `scale / 256` without floats, 255 keeping the color unchanged.
+impl Mul<u8> for Color {
+    type Output = Color;
 
-    bytes.copy_from_slice(color);
+    fn mul(self, scale: u8) -> Color {
+        let (red, green, blue) = self.to_rgb();
 
-    for byte in bytes {
-        send_byte(led, byte);
+        Color::RGB(scale8(red, scale), scale8(green, scale), scale8(blue, scale))
     }
 }
 
-pub fn send_bit<PIN: PinOps>(led: &mut Pin<Output, PIN>, bit: u8) -> () {
-    match bit {
-        0 => zero(led),
-        _ => one(led),
-    };
+/// Digital output the WS2812B signal is bit-banged on.
+pub trait DataPin {
+    fn set_high(&mut self);
+
+    fn set_low(&mut self);
+
+    /// Wait with the line kept at its current level.
+    #[cfg(feature = "arduino-hal")]
+    fn delay_ns(&mut self, ns: u32) {
+        arduino_hal::delay_ns(ns);
+    }
+
+    /// Wait with the line kept at its current level, there is no default
+    /// delay without a board.
+    #[cfg(not(feature = "arduino-hal"))]
+    fn delay_ns(&mut self, ns: u32);
 }
 
-pub fn send_byte<PIN: PinOps>(led: &mut Pin<Output, PIN>, byte: u8) -> () {
-    for i in 0..8 {
-        send_bit(led, byte & (1 << (7 - i)));
+#[cfg(feature = "arduino-hal")]
+impl<PIN: PinOps> DataPin for Pin<Output, PIN> {
+    fn set_high(&mut self) {
+        Pin::<Output, PIN>::set_high(self);
+    }
+
+    fn set_low(&mut self) {
+        Pin::<Output, PIN>::set_low(self);
     }
 }
 
-pub fn reset<PIN: PinOps>(led: &mut Pin<Output, PIN>, us: u32) -> () {
-    set_low_for(led, us * 1_000);
+/// Pin driven with swapped levels, for level shifters inverting the signal
+/// (e.g. a single transistor), the line then idles high.
+pub struct Inverted<P: DataPin>(pub P);
+
+impl<P: DataPin> DataPin for Inverted<P> {
+    fn set_high(&mut self) {
+        self.0.set_low();
+    }
+
+    fn set_low(&mut self) {
+        self.0.set_high();
+    }
+
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.delay_ns(ns);
+    }
 }
 
-pub fn zero<PIN: PinOps>(led: &mut Pin<Output, PIN>) -> () {
-    led.set_high();
-    led.set_low();
-    led.set_low();
-    led.set_low();
-    led.set_low();
-    led.set_low();
+#[cfg(feature = "arduino-hal")]
+impl<PIN: PinOps> DataPin for Pin<OpenDrain, PIN> {
+    fn set_high(&mut self) {
+        Pin::<OpenDrain, PIN>::set_high(self);
+    }
+
+    fn set_low(&mut self) {
+        Pin::<OpenDrain, PIN>::set_low(self);
+    }
 }
 
-pub fn one<PIN: PinOps>(led: &mut Pin<Output, PIN>) -> () {
-    led.set_high();
-    led.set_high();
-    led.set_high();
-    led.set_low();
-    led.set_low();
-    led.set_low();
+/// Output the bytes of a frame go to, in the order the strip expects them,
+/// so the strip, buffers, effects and transforms work with any backend:
+/// bit-banged pins (`DataPin`), SPI or UART (`bits::Bits`), parallel
+/// outputs (`parallel::Parallel`), clocked chipsets, the RP2040 PIO or the
+/// ESP32 RMT, or one implemented by the application.
+pub trait Transmitter {
+    fn send_byte(&mut self, byte: u8);
+
+    /// Send the bytes of a pixel, backends sending several bytes at once
+    /// (e.g. with DMA) can override it.
+    fn send_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.send_byte(byte);
+        }
+    }
+
+    /// Hold the line low for `us` microseconds so the strip displays the frame.
+    fn reset(&mut self, us: u32);
+
+    /// Channel order of the pixels sent, for backends decoding them back to
+    /// colors (e.g. the simulators), see `LedStrip::set_order()`.
+    fn set_order(&mut self, _order: ColorOrder) {}
 }
 
-pub fn set_high_for<PIN: PinOps>(led: &mut Pin<Output, PIN>, ns: u32) -> () {
-    led.set_high();
-    arduino_hal::delay_ns(ns);
+/// Bit-banged WS2812B signal on a digital pin.
+#[cfg(feature = "arduino-hal")]
+impl<PIN: PinOps> Transmitter for Pin<Output, PIN> {
+    fn send_byte(&mut self, byte: u8) {
+        send_byte(self, byte);
+    }
+
+    fn reset(&mut self, us: u32) {
+        reset(self, us);
+    }
 }
 
-pub fn set_low_for<PIN: PinOps>(led: &mut Pin<Output, PIN>, ns: u32) -> () {
-    led.set_low();
-    arduino_hal::delay_ns(ns);
+/// Bit-banged WS2812B signal on an open-drain pin.
+#[cfg(feature = "arduino-hal")]
+impl<PIN: PinOps> Transmitter for Pin<OpenDrain, PIN> {
+    fn send_byte(&mut self, byte: u8) {
+        send_byte(self, byte);
+    }
+
+    fn reset(&mut self, us: u32) {
+        reset(self, us);
+    }
 }
 
-pub fn parse(color: &str) -> [u8; 3] {
-    u8::from_str_radix(&color[..2], 16)
-        .and_then(|r| {
-            u8::from_str_radix(&color[2..4], 16).and_then(
-                |g| u8::from_str_radix(&color[4..6], 16).map(|b| [r, g, b]),
-            )
-        })
-        .unwrap()
+/// Bit-banged WS2812B signal with swapped levels.
+impl<P: DataPin> Transmitter for Inverted<P> {
+    fn send_byte(&mut self, byte: u8) {
+        send_byte(self, byte);
+    }
+
+    fn reset(&mut self, us: u32) {
+        reset(self, us);
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::led::Color;
+/// Time the line must stay low for the strip to display the data, in
+/// microseconds (above 280 µs for recent WS2812B, 50 µs for older ones).
+pub const LATCH_TIME: u32 = 300;
 
-    #[test]
-    fn opacity() {
-        assert_eq!(
-            Color::Orange.opacity(0.5).to_rgb(),
-            (127, 63, 0),
-        );
-        assert_eq!(
-            Color::Orange.opacity(0.25).to_rgb(),
-            (63, 31, 0),
-        );
+/// Time to send a pixel (24 bits of 1.25 µs), in microseconds.
+pub const PIXEL_TIME: u32 = 30;
+
+/// Order the strip expects the channels in, WS2812B being GRB.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ColorOrder {
+    Rgb,
+    Rbg,
+    Grb,
+    Gbr,
+    Brg,
+    Bgr,
+}
+
+impl ColorOrder {
+    /// Bytes to send for the given red, green and blue channels.
+    pub fn arrange(&self, [red, green, blue]: [u8; 3]) -> [u8; 3] {
+        match self {
+            ColorOrder::Rgb => [red, green, blue],
+            ColorOrder::Rbg => [red, blue, green],
+            ColorOrder::Grb => [green, red, blue],
+            ColorOrder::Gbr => [green, blue, red],
+            ColorOrder::Brg => [blue, red, green],
+            ColorOrder::Bgr => [blue, green, red],
+        }
     }
 
-    #[test]
-    fn mix() {
-        assert_eq!(
-            Color::Red.mix(Color::Green).to_rgb(),
-            Color::Yellow.opacity(0.5).to_rgb(),
-        );
-        assert_eq!(
-            Color::Red.mix_ratio(Color::Green, 1.0, 1.0).to_rgb(),
-            Color::Yellow.to_rgb(),
-        );
-        assert_eq!(
-            Color::Red.mix_ratio(Color::Green, 0.5, 1.0).to_rgb(),
-            (127, 255, 0),
-        );
+    /// Red, green and blue channels of the bytes sent, undoing `arrange()`.
+    pub fn unarrange(&self, [first, second, third]: [u8; 3]) -> [u8; 3] {
+        match self {
+            ColorOrder::Rgb => [first, second, third],
+            ColorOrder::Rbg => [first, third, second],
+            ColorOrder::Grb => [second, first, third],
+            ColorOrder::Gbr => [third, first, second],
+            ColorOrder::Brg => [second, third, first],
+            ColorOrder::Bgr => [third, second, first],
+        }
+    }
+}
+
+/// Snapshot of the strip state, see `LedStrip::status()`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Status {
+    pub led_count: usize,
+    /// Frames latched since the strip was created.
+    pub frame: u32,
+    /// Brightness applied, lower than the target during a soft start.
+    pub brightness: u8,
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for Status {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error> where W: ufmt::uWrite + ?Sized {
+        f.write_str("leds=")?;
+        ufmt::uDisplay::fmt(&self.led_count, f)?;
+        f.write_str(" frame=")?;
+        ufmt::uDisplay::fmt(&self.frame, f)?;
+        f.write_str(" brightness=")?;
+        ufmt::uDisplay::fmt(&self.brightness, f)
+    }
+}
+
+/// Runtime statistics of the strip, see `LedStrip::stats()`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stats {
+    /// Frames latched since the strip was created.
+    pub frames: u32,
+    /// Time the last frame took to send and latch, estimated from its
+    /// pixel count (`PIXEL_TIME` each) and the latch duration.
+    pub last_frame_us: u32,
+    /// Estimated current drawn by the LEDs for the last frame.
+    pub milliamps: u32,
+    /// Frames the pacer started late, see `Stats::with_pacer()`.
+    pub late_frames: u32,
+    /// Frames the pacer skipped because the loop was too slow.
+    pub dropped_frames: u32,
+}
+
+impl Stats {
+    /// Statistics including the late and dropped frames of the pacer timing the frames.
+    pub fn with_pacer(mut self, pacer: &Pacer) -> Stats {
+        self.late_frames = pacer.late_frames();
+        self.dropped_frames = pacer.dropped_frames();
+
+        self
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for Stats {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error> where W: ufmt::uWrite + ?Sized {
+        f.write_str("frames=")?;
+        ufmt::uDisplay::fmt(&self.frames, f)?;
+        f.write_str(" frame_us=")?;
+        ufmt::uDisplay::fmt(&self.last_frame_us, f)?;
+        f.write_str(" ma=")?;
+        ufmt::uDisplay::fmt(&self.milliamps, f)?;
+        f.write_str(" late=")?;
+        ufmt::uDisplay::fmt(&self.late_frames, f)?;
+        f.write_str(" dropped=")?;
+        ufmt::uDisplay::fmt(&self.dropped_frames, f)
+    }
+}
+
+/// Timing of the frames, measured with the clock given to
+/// `LedStrip::set_profiler()`, in microseconds.
+#[cfg(feature = "profiling")]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Profile {
+    /// Frames measured.
+    pub frames: u32,
+    /// Time sending the last frame, from its first pixel to the latch, the
+    /// latch wait and the rendering excluded.
+    pub transmit_us: u32,
+    pub max_transmit_us: u32,
+    /// Time spent in the `each()` callback during the last frame.
+    pub render_us: u32,
+    pub max_render_us: u32,
+}
+
+/// Brightness ramp over `frames` frames from `start_frame`.
+#[derive(Copy, Clone)]
+struct Fade {
+    start_frame: u32,
+    frames: u32,
+    out: bool,
+}
+
+impl Fade {
+    fn level(&self, frame: u32) -> u8 {
+        let elapsed = frame.wrapping_sub(self.start_frame).min(self.frames);
+        let level = (u64::from(elapsed) * 255 / u64::from(self.frames)) as u8;
+
+        match self.out {
+            true => 255 - level,
+            false => level,
+        }
+    }
+
+    fn is_running(&self, frame: u32) -> bool {
+        frame.wrapping_sub(self.start_frame) < self.frames
+    }
+}
+
+pub struct LedStrip<T: Transmitter> {
+    led_count: usize,
+    active_count: usize,
+    transmitter: T,
+    order: ColorOrder,
+    cursor: usize,
+    frame: u32,
+    elapsed_ms: u32,
+    elapsed_us: u32,
+    brightness: u8,
+    dimming_curve: DimmingCurve,
+    soft_start: u16,
+    fade: Option<Fade>,
+    temperature: Temperature,
+    correction: Correction,
+    calibration: (u8, u8, u8),
+    channel_scale: (u8, u8, u8),
+    brightness_map: Option<&'static [u8]>,
+    gamma: Option<&'static dyn ByteSource>,
+    transform: Option<&'static dyn PixelTransform>,
+    voltage_drop: Option<VoltageDrop>,
+    power_limit: Option<PowerLimit>,
+    power_scale: u8,
+    last_draw: Option<u32>,
+    thermal_limit: Option<ThermalLimit>,
+    thermal_scale: u8,
+    watchdog: Option<(fn(), usize)>,
+    #[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+    interrupt_masking: InterruptMasking,
+    draw: u32,
+    last_frame_us: u32,
+    milliamps: u32,
+    #[cfg(feature = "dithering")]
+    residuals: Option<&'static mut [[u8; 3]]>,
+    #[cfg(feature = "profiling")]
+    profiler: Option<fn() -> u32>,
+    #[cfg(feature = "profiling")]
+    profile: Profile,
+    #[cfg(feature = "profiling")]
+    frame_start_us: Option<u32>,
+    #[cfg(feature = "profiling")]
+    render_us: u32,
+}
+
+#[cfg(feature = "arduino-hal")]
+impl<PIN> LedStrip<Pin<Output, PIN>> where PIN: PinOps {
+    /// Strip on a pin in any mode, e.g. as returned by `pins!()` or already
+    /// configured as an output by the board support code.
+    #[allow(dead_code)]
+    pub fn new<MODE: Io>(led_count: usize, pin: Pin<MODE, PIN>) -> LedStrip<Pin<Output, PIN>> {
+        LedStrip::with_transmitter(led_count, pin.into_output())
+    }
+
+    pub fn builder<MODE: Io>(pin: Pin<MODE, PIN>) -> LedStripBuilder<Pin<Output, PIN>> {
+        LedStripBuilder::new(pin.into_output())
+    }
+}
+
+#[cfg(feature = "arduino-hal")]
+impl<PIN> LedStrip<Pin<OpenDrain, PIN>> where PIN: PinOps {
+    /// Strip on an open-drain pin, the line being only pulled low and
+    /// brought high by a pull-up resistor, e.g. to a 5 V level for a 3.3 V
+    /// board; the pull-up must be strong enough (around 1 kΩ) for the edges
+    /// to rise in time.
+    pub fn open_drain<MODE: Io>(led_count: usize, pin: Pin<MODE, PIN>) -> LedStrip<Pin<OpenDrain, PIN>> {
+        LedStrip::with_transmitter(led_count, pin.into_opendrain())
+    }
+}
+
+#[cfg(feature = "arduino-hal")]
+impl<PIN> LedStrip<Inverted<Pin<Output, PIN>>> where PIN: PinOps {
+    /// Strip behind an inverting level shifter, pulses being sent low and
+    /// the line resting high.
+    pub fn inverted<MODE: Io>(led_count: usize, pin: Pin<MODE, PIN>) -> LedStrip<Inverted<Pin<Output, PIN>>> {
+        let mut pin = pin.into_output();
+        pin.set_high();
+
+        LedStrip::with_transmitter(led_count, Inverted(pin))
+    }
+}
+
+impl<T> LedStrip<T> where T: Transmitter {
+    pub fn with_transmitter(led_count: usize, transmitter: T) -> LedStrip<T> {
+        LedStrip {
+            led_count,
+            active_count: led_count,
+            transmitter,
+            order: ColorOrder::Grb,
+            cursor: 0,
+            frame: 0,
+            elapsed_ms: 0,
+            elapsed_us: 0,
+            brightness: 255,
+            dimming_curve: DimmingCurve::Linear,
+            soft_start: 0,
+            fade: None,
+            temperature: Temperature::Uncorrected,
+            correction: Correction::Uncorrected,
+            calibration: (255, 255, 255),
+            channel_scale: (255, 255, 255),
+            brightness_map: None,
+            gamma: None,
+            transform: None,
+            voltage_drop: None,
+            power_limit: None,
+            power_scale: 255,
+            last_draw: None,
+            thermal_limit: None,
+            thermal_scale: 255,
+            watchdog: None,
+            #[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+            interrupt_masking: InterruptMasking::None,
+            draw: 0,
+            last_frame_us: 0,
+            milliamps: 0,
+            #[cfg(feature = "dithering")]
+            residuals: None,
+            #[cfg(feature = "profiling")]
+            profiler: None,
+            #[cfg(feature = "profiling")]
+            profile: Profile::default(),
+            #[cfg(feature = "profiling")]
+            frame_start_us: None,
+            #[cfg(feature = "profiling")]
+            render_us: 0,
+        }
+    }
+
+    pub fn led_count(&self) -> usize {
+        self.led_count
+    }
+
+    /// LEDs `each()` renders, the others up to `led_count()` are sent black.
+    pub fn active_count(&self) -> usize {
+        self.active_count
+    }
+
+    /// Use only the first `active_count` LEDs (at most `led_count()`), e.g.
+    /// when the strip length is only known at boot, from a jumper or the EEPROM.
+    pub fn set_active_count(&mut self, active_count: usize) {
+        self.active_count = active_count.min(self.led_count);
+    }
+
+    pub fn transmitter(&self) -> &T {
+        &self.transmitter
+    }
+
+    pub fn transmitter_mut(&mut self) -> &mut T {
+        &mut self.transmitter
+    }
+
+    pub fn status(&self) -> Status {
+        Status {
+            led_count: self.led_count,
+            frame: self.frame,
+            brightness: self.brightness(),
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            frames: self.frame,
+            last_frame_us: self.last_frame_us,
+            milliamps: self.milliamps,
+            late_frames: 0,
+            dropped_frames: 0,
+        }
+    }
+
+    pub fn order(&self) -> ColorOrder {
+        self.order
+    }
+
+    pub fn set_order(&mut self, order: ColorOrder) {
+        self.order = order;
+        self.transmitter.set_order(order);
+    }
+
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness;
+    }
+
+    /// Brightness as set by `set_brightness()`, before the curve and the
+    /// ramps `brightness()` applies, e.g. to step it up or down.
+    pub fn brightness_setting(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Brightness actually applied: the setting through the dimming curve,
+    /// lower during a soft start or a fade, or when derated by the thermal limit.
+    pub fn brightness(&self) -> u8 {
+        let brightness = self.dimming_curve.apply(self.brightness);
+        let brightness = match self.frame < u32::from(self.soft_start) {
+            true => (u32::from(brightness) * self.frame / u32::from(self.soft_start)) as u8,
+            false => brightness,
+        };
+        let brightness = match self.fade {
+            Some(fade) => scale8(brightness, fade.level(self.frame)),
+            None => brightness,
+        };
+
+        match self.thermal_scale {
+            255 => brightness,
+            scale => scale8(brightness, scale),
+        }
+    }
+
+    pub fn dimming_curve(&self) -> DimmingCurve {
+        self.dimming_curve
+    }
+
+    /// Curve applied to the `set_brightness()` setting, linear by default.
+    pub fn set_dimming_curve(&mut self, dimming_curve: DimmingCurve) {
+        self.dimming_curve = dimming_curve;
+    }
+
+    /// Ramp brightness from 0 to its target over the first `frames` frames
+    /// after construction, to limit inrush current at power-on.
+    pub fn set_soft_start(&mut self, frames: u16) {
+        self.soft_start = frames;
+    }
+
+    /// Ramp brightness up from 0 (or from the level of a fade it interrupts)
+    /// over `duration_ms`, counted in frames latched at the rate of `pacer`,
+    /// e.g. at boot or on a scene change after `fade_out()`.
+    pub fn fade_in(&mut self, duration_ms: u32, pacer: &Pacer) {
+        self.fade(duration_ms, pacer, false);
+    }
+
+    /// Ramp brightness down to 0 over `duration_ms`, counted in frames
+    /// latched at the rate of `pacer`, the strip then staying dark until
+    /// `fade_in()`, e.g. before cutting the power once `is_fading()` is false.
+    pub fn fade_out(&mut self, duration_ms: u32, pacer: &Pacer) {
+        self.fade(duration_ms, pacer, true);
+    }
+
+    /// Whether a `fade_in()` or `fade_out()` is still running.
+    pub fn is_fading(&self) -> bool {
+        self.fade.is_some_and(|fade| fade.is_running(self.frame))
+    }
+
+    fn fade(&mut self, duration_ms: u32, pacer: &Pacer, out: bool) {
+        let frames = (duration_ms / pacer.interval()).max(1);
+        // Start from the current level when interrupting a fade, so it doesn't
+        // jump, or from the end opposite to the fade.
+        let level = match self.fade {
+            Some(fade) => u64::from(fade.level(self.frame)),
+            None if out => 255,
+            None => 0,
+        };
+        let elapsed = match out {
+            true => (255 - level) * u64::from(frames) / 255,
+            false => level * u64::from(frames) / 255,
+        };
+
+        self.fade = Some(Fade {
+            start_frame: self.frame.wrapping_sub(elapsed as u32),
+            frames,
+            out,
+        });
+    }
+
+    pub fn temperature(&self) -> Temperature {
+        self.temperature
+    }
+
+    pub fn set_temperature(&mut self, temperature: Temperature) {
+        self.temperature = temperature;
+        self.update_channel_scale();
+    }
+
+    pub fn correction(&self) -> Correction {
+        self.correction
+    }
+
+    pub fn set_correction(&mut self, correction: Correction) {
+        self.correction = correction;
+        self.update_channel_scale();
+    }
+
+    pub fn calibration(&self) -> (u8, u8, u8) {
+        self.calibration
+    }
+
+    /// Per-channel multipliers (255 = unchanged) measured for this strip,
+    /// e.g. `(255, 176, 240)`, to match colors across strips.
+    pub fn set_calibration(&mut self, red: u8, green: u8, blue: u8) {
+        self.calibration = (red, green, blue);
+        self.update_channel_scale();
+    }
+
+    fn update_channel_scale(&mut self) {
+        self.channel_scale = combine(
+            combine(self.temperature.to_rgb(), self.correction.to_rgb()),
+            self.calibration,
+        );
+    }
+
+    /// Attenuate each LED by its value in the map (255 = unchanged) when sent,
+    /// LEDs after the end of the map are left unchanged.
+    pub fn set_brightness_map(&mut self, brightness_map: Option<&'static [u8]>) {
+        self.brightness_map = brightness_map;
+    }
+
+    /// Gamma correct colors with a 256 entries table before brightness is
+    /// applied, e.g. `Some(&GAMMA_2_8)`, or a table from `gamma_table()`
+    /// matching the LEDs used.
+    pub fn set_gamma(&mut self, gamma: Option<&'static dyn ByteSource>) {
+        self.gamma = gamma;
+    }
+
+    /// Transform applied to each pixel before the gamma and brightness, see
+    /// `PixelTransform` (chain several with a tuple or an array).
+    pub fn set_transform(&mut self, transform: Option<&'static dyn PixelTransform>) {
+        self.transform = transform;
+    }
+
+    /// Dim the LEDs near the power injection so they match the far ones,
+    /// applied after the gamma correction.
+    pub fn set_voltage_drop(&mut self, voltage_drop: Option<VoltageDrop>) {
+        self.voltage_drop = voltage_drop;
+    }
+
+    /// Scale frames sent with `each()` down when their estimated current
+    /// exceeds the limit, the callback is then called twice per LED.
+    pub fn set_power_limit(&mut self, power_limit: Option<PowerLimit>) {
+        self.power_limit = power_limit;
+    }
+
+    /// Derate the brightness from the temperatures given to `feed_temperature()`.
+    pub fn set_thermal_limit(&mut self, thermal_limit: Option<ThermalLimit>) {
+        self.thermal_limit = thermal_limit;
+        self.thermal_scale = 255;
+    }
+
+    /// Give the last temperature measured, in tenths of a degree Celsius,
+    /// applied to the brightness from the next pixel sent.
+    pub fn feed_temperature(&mut self, temperature: i16) {
+        self.thermal_scale = self.thermal_limit.map_or(255, |limit| limit.scale(temperature));
+    }
+
+    /// Scale (255 = unchanged) applied to the brightness for the last temperature fed.
+    pub fn thermal_scale(&self) -> u8 {
+        self.thermal_scale
+    }
+
+    #[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+    pub fn interrupt_masking(&self) -> InterruptMasking {
+        self.interrupt_masking
+    }
+
+    /// Mask the interrupts while each pixel is sent, so the timer behind
+    /// `millis()` and the serial reception keep running during long frames.
+    #[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+    pub fn set_interrupt_masking(&mut self, interrupt_masking: InterruptMasking) {
+        self.interrupt_masking = interrupt_masking;
+    }
+
+    /// Spread the fractional part of each channel over the next frames, using
+    /// one residual per LED, so low brightness fades don't visibly step.
+    #[cfg(feature = "dithering")]
+    pub fn set_dithering(&mut self, residuals: Option<&'static mut [[u8; 3]]>) {
+        self.residuals = residuals;
+    }
+
+    /// Call `feed` every `pixels` pixels and after each latch, so long frames
+    /// don't trip the watchdog, e.g. `Some((|| avr_device::asm::wdr(), 64))`.
+    pub fn set_watchdog(&mut self, watchdog: Option<(fn(), usize)>) {
+        self.watchdog = watchdog.map(|(feed, pixels)| (feed, pixels.max(1)));
+    }
+
+    /// Measure frames with `clock`, a microsecond counter (e.g. from a timer
+    /// interrupt), the measures being read with `profile()`.
+    #[cfg(feature = "profiling")]
+    pub fn set_profiler(&mut self, clock: Option<fn() -> u32>) {
+        self.profiler = clock;
+        self.profile = Profile::default();
+        self.frame_start_us = None;
+        self.render_us = 0;
+    }
+
+    #[cfg(feature = "profiling")]
+    pub fn profile(&self) -> Profile {
+        self.profile
+    }
+
+    #[cfg(feature = "profiling")]
+    fn render<F>(&mut self, callback: &F, led_index: usize) -> Color where F: (Fn(usize) -> Color) {
+        let Some(clock) = self.profiler else {
+            return callback(led_index);
+        };
+
+        self.frame_start_us.get_or_insert_with(clock);
+
+        let start = clock();
+        let color = callback(led_index);
+        self.render_us = self.render_us.wrapping_add(clock().wrapping_sub(start));
+
+        color
+    }
+
+    #[cfg(not(feature = "profiling"))]
+    fn render<F>(&mut self, callback: &F, led_index: usize) -> Color where F: (Fn(usize) -> Color) {
+        callback(led_index)
+    }
+
+    /// Send a frame, latching the previous one first if it was fully sent
+    /// without a latch, so it's not overwritten before being displayed.
+    ///
+    /// `callback` is called once per LED, so with a power limit the frame is
+    /// scaled from the previous one, as with `write()`.
+    pub fn each<F>(&mut self, callback: F) where F: (Fn(usize) -> Color) {
+        if self.cursor >= self.led_count {
+            self.latch();
+        }
+
+        let budget = self.start_power_limit();
+        let mut draw = 0;
+
+        for led_index in 0..self.led_count {
+            let color = match led_index < self.active_count {
+                true => self.render(&callback, led_index),
+                false => Color::Black,
+            };
+
+            self.send_limited(color, budget, &mut draw);
+        }
+
+        self.last_draw = Some(draw);
+        self.power_scale = 255;
+    }
+
+    /// Send a frame of exactly `led_count()` colors, the following ones
+    /// being dropped and the missing ones sent black, e.g.
+    /// `led_strip.write(ColorCycle::new(&colors))` or `led_strip.write(pixels)`.
+    ///
+    /// The colors are read only once, so with a power limit the frame is
+    /// scaled from the estimate of the previous one sent (all white for
+    /// the first one), the following LEDs being dimmed further while it's
+    /// sent when it's brighter, so the budget is never exceeded.
+    pub fn write<I, C>(&mut self, colors: I) where I: IntoIterator<Item = C>, C: Into<Color> {
+        if self.cursor >= self.led_count {
+            self.latch();
+        }
+
+        let mut colors = colors.into_iter();
+        let budget = self.start_power_limit();
+        let mut draw = 0;
+
+        for led_index in 0..self.led_count {
+            let color = match (colors.next(), led_index < self.active_count) {
+                (Some(color), true) => color.into(),
+                _ => Color::Black,
+            };
+
+            self.send_limited(color, budget, &mut draw);
+        }
+
+        self.last_draw = Some(draw);
+        self.power_scale = 255;
+    }
+
+    /// Set the power scale of a streamed frame from the previous one (all
+    /// white before the first one) and return the budget of the channels,
+    /// in 1/255 mA, if a power limit is set.
+    fn start_power_limit(&mut self) -> u32 {
+        let Some(power_limit) = self.power_limit else {
+            return 0;
+        };
+
+        let estimate = self.last_draw.unwrap_or(self.led_count as u32 * channel_draw(255, 255, 255));
+        self.power_scale = power_limit.scale(self.led_count, estimate);
+
+        (u32::from(power_limit.milliamps()) * 255).saturating_sub(frame_milliamps(self.led_count, 0) * 255)
+    }
+
+    /// Send a pixel of a streamed frame, lowering the power scale first if
+    /// it would exceed what's left of the `budget`, adding its estimate to `draw`.
+    fn send_limited(&mut self, color: Color, budget: u32, draw: &mut u32) {
+        if self.power_limit.is_some() {
+            let pixel_draw = self.pixel_draw(self.cursor, Color16::from(color));
+            let left = budget.saturating_sub(self.draw);
+            *draw += pixel_draw;
+
+            if (pixel_draw * u32::from(self.power_scale)).div_ceil(255) > left {
+                self.power_scale = (left * 255 / pixel_draw) as u8;
+            }
+        }
+
+        self.color(color);
+    }
+
+    /// Set the power scale of the next frame from its `colors`, if a power limit is set.
+    fn limit_power(&mut self, colors: impl Iterator<Item = Color16>) {
+        let Some(power_limit) = self.power_limit else {
+            return;
+        };
+
+        let draw = colors.enumerate().map(|(led_index, color)| self.pixel_draw(self.cursor + led_index, color)).sum();
+        self.power_scale = power_limit.scale(self.led_count, draw);
+    }
+
+    /// `channel_draw()` of a pixel before the power limit.
+    fn pixel_draw(&self, led_index: usize, Color16 { red, green, blue }: Color16) -> u32 {
+        // Rounded up, so the frame sent (truncated) never exceeds the estimate.
+        let [red, green, blue] = self.output(led_index, red, green, blue)
+            .map(|channel| ((u32::from(channel) + 255) >> 8).min(255) as u8);
+
+        channel_draw(red, green, blue)
+    }
+
+    /// Send a frame, or fail with `LedError::NotLatched` instead of waiting
+    /// when the previous one was fully sent but not latched yet.
+    pub fn try_each<F>(&mut self, callback: F) -> Result<(), LedError> where F: (Fn(usize) -> Color) {
+        if self.cursor >= self.led_count {
+            return Err(LedError::NotLatched);
+        }
+
+        self.each(callback);
+
+        Ok(())
+    }
+
+    /// Send a frame, `callback` receiving the LED index, the number of
+    /// frames latched so far and `millis()`.
+    pub fn each_frame<F>(&mut self, callback: F) where F: (Fn(usize, u32, u32) -> Color) {
+        let frame = self.frame;
+        let millis = self.elapsed_ms;
+
+        self.each(|led_index| callback(led_index, frame, millis));
+    }
+
+    /// Milliseconds since the strip was created, estimated from the time
+    /// spent sending pixels (30 µs each) and waiting in `rest()`, so time
+    /// spent elsewhere in the loop is not counted.
+    pub fn millis(&self) -> u32 {
+        self.elapsed_ms
+    }
+
+    pub fn hex(&mut self, color: &str) -> Result<(), LedError> {
+        let bytes = parse(color)?;
+        self.rgb(bytes[0], bytes[1], bytes[2]);
+
+        Ok(())
+    }
+
+    pub fn rgb(&mut self, red: u8, green: u8, blue: u8) {
+        self.rgb16(u16::from(red) * 257, u16::from(green) * 257, u16::from(blue) * 257);
+    }
+
+    /// Send a 16-bit per channel color, quantized (and dithered if enabled) to 8 bits.
+    pub fn rgb16(&mut self, red: u16, green: u16, blue: u16) {
+        #[cfg(feature = "profiling")]
+        if let Some(clock) = self.profiler {
+            self.frame_start_us.get_or_insert_with(clock);
+        }
+
+        let power_scale = u32::from(self.power_scale);
+        let channels = self.output(self.cursor, red, green, blue)
+            .map(|channel| (u32::from(channel) * power_scale / 255) as u16);
+        let channels = self.quantize(channels);
+        self.draw += channel_draw(channels[0], channels[1], channels[2]);
+
+        self.send_pixel(self.order.arrange(channels));
+
+        self.cursor += 1;
+
+        // Between pixels, the line is low for much less than the latch time.
+        if let Some((feed, pixels)) = self.watchdog
+            && self.cursor.is_multiple_of(pixels)
+        {
+            feed();
+        }
+    }
+
+    #[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+    fn send_pixel(&mut self, bytes: [u8; 3]) {
+        match self.interrupt_masking {
+            InterruptMasking::None => self.transmitter.send_bytes(&bytes),
+            InterruptMasking::PerPixel => without_interrupts(|| self.transmitter.send_bytes(&bytes)),
+        }
+    }
+
+    #[cfg(all(target_arch = "avr", not(feature = "asm_experimental_arch")))]
+    fn send_pixel(&mut self, bytes: [u8; 3]) {
+        self.transmitter.send_bytes(&bytes);
+    }
+
+    /// Channels after brightness and correction, as 8.8 fixed-point values.
+    fn output(&self, led_index: usize, red: u16, green: u16, blue: u16) -> [u16; 3] {
+        let brightness = self.brightness_map
+            .and_then(|map| map.get(led_index).copied())
+            .map_or(self.brightness(), |weight| scale8(self.brightness(), weight));
+        let (red_scale, green_scale, blue_scale) = self.channel_scale;
+        let mut channels = [red, green, blue];
+
+        if let Some(transform) = self.transform {
+            channels = transform.transform(led_index, channels);
+        }
+
+        if let Some(gamma) = self.gamma {
+            channels = Gamma(gamma).transform(led_index, channels);
+        }
+
+        if let Some(voltage_drop) = self.voltage_drop {
+            channels = voltage_drop.transform(led_index, channels);
+        }
+
+        (Brightness(brightness), ChannelScale(red_scale, green_scale, blue_scale)).transform(led_index, channels)
+    }
+
+    #[cfg(not(feature = "dithering"))]
+    fn quantize(&mut self, channels: [u16; 3]) -> [u8; 3] {
+        channels.map(|channel| (channel >> 8) as u8)
+    }
+
+    #[cfg(feature = "dithering")]
+    fn quantize(&mut self, channels: [u16; 3]) -> [u8; 3] {
+        let cursor = self.cursor;
+
+        match self.residuals.as_deref_mut().and_then(|residuals| residuals.get_mut(cursor)) {
+            Some(residual) => [
+                dither(channels[0], &mut residual[0]),
+                dither(channels[1], &mut residual[1]),
+                dither(channels[2], &mut residual[2]),
+            ],
+            None => channels.map(|channel| (channel >> 8) as u8),
+        }
+    }
+
+    pub fn color(&mut self, color: Color) {
+        let (red, green, blue) = color.to_rgb();
+
+        self.rgb(red, green, blue);
+    }
+
+    pub fn color16(&mut self, color: Color16) {
+        self.rgb16(color.red, color.green, color.blue);
+    }
+
+    /// Send a frame of a single 16-bit color, scaled to fit the power limit
+    /// from the estimate of the whole frame.
+    pub fn fill16(&mut self, color: Color16) {
+        if self.cursor >= self.led_count {
+            self.latch();
+        }
+
+        let active_count = self.active_count;
+        let colors = (0..self.led_count).map(|led_index| match led_index < active_count {
+            true => color,
+            false => Color16::new(0, 0, 0),
+        });
+
+        self.limit_power(colors.clone());
+
+        for color in colors {
+            self.color16(color);
+        }
+
+        self.power_scale = 255;
+    }
+
+    /// Send a `0xRRGGBB` color, see `Color::from_u32_rgb()`.
+    pub fn color_number(&mut self, color: u32) {
+        self.color(Color::from_u32_rgb(color));
+    }
+
+    pub fn rest(&mut self, us: u32) {
+        #[cfg(feature = "profiling")]
+        if let (Some(clock), Some(start)) = (self.profiler, self.frame_start_us.take()) {
+            let profile = &mut self.profile;
+            profile.frames = profile.frames.wrapping_add(1);
+            profile.render_us = self.render_us;
+            profile.transmit_us = clock().wrapping_sub(start).saturating_sub(self.render_us);
+            profile.max_render_us = profile.max_render_us.max(profile.render_us);
+            profile.max_transmit_us = profile.max_transmit_us.max(profile.transmit_us);
+            self.render_us = 0;
+        }
+
+        self.transmitter.reset(us);
+
+        let frame_us = u64::from(us) + self.cursor as u64 * PIXEL_TIME as u64;
+        self.last_frame_us = frame_us.min(u64::from(u32::MAX)) as u32;
+        self.milliamps = frame_milliamps(self.cursor, self.draw);
+        self.draw = 0;
+
+        let elapsed_us = self.elapsed_us as u64 + frame_us;
+        self.elapsed_ms = self.elapsed_ms.wrapping_add((elapsed_us / 1000) as u32);
+        self.elapsed_us = (elapsed_us % 1000) as u32;
+        self.cursor = 0;
+        self.frame = self.frame.saturating_add(1);
+
+        if let Some((feed, _)) = self.watchdog {
+            feed();
+        }
+    }
+
+    /// Whether all the pixels sent were displayed, i.e. none was sent since the last latch.
+    pub fn is_latched(&self) -> bool {
+        self.cursor == 0
+    }
+
+    /// Display the pixels sent since the last latch, if any.
+    pub fn latch(&mut self) {
+        if !self.is_latched() {
+            self.rest(LATCH_TIME);
+        }
+    }
+
+    /// Send a frame and return a guard latching it when dropped, the strip
+    /// being borrowed meanwhile so no other frame can start before it's displayed.
+    pub fn show<F>(&mut self, callback: F) -> Shown<'_, T> where F: (Fn(usize) -> Color) {
+        self.each(callback);
+
+        Shown {
+            led_strip: self,
+            rest_us: LATCH_TIME,
+        }
+    }
+
+    /// Light all the LEDs red, green, blue then white for half a second each,
+    /// then move a single white LED along the strip, to check the wiring,
+    /// the channel order and for dead pixels.
+    pub fn self_test(&mut self) {
+        for color in [Color::Red, Color::Green, Color::Blue, Color::White] {
+            self.each(|_| color);
+            self.rest(500_000);
+        }
+
+        for lit in 0..self.led_count {
+            self.each(|led_index| match led_index == lit {
+                true => Color::White,
+                false => Color::Black,
+            });
+            self.rest(50_000);
+        }
+
+        self.each(|_| Color::Black);
+        self.rest(50_000);
+    }
+}
+
+/// Latch the last pixels sent when the strip goes out of scope.
+impl<T> Drop for LedStrip<T> where T: Transmitter {
+    fn drop(&mut self) {
+        if !self.is_latched() {
+            self.transmitter.reset(LATCH_TIME);
+        }
+    }
+}
+
+/// Frame sent by `LedStrip::show()`, latched when dropped.
+#[must_use = "the frame is latched as soon as the guard is dropped"]
+pub struct Shown<'a, T: Transmitter> {
+    led_strip: &'a mut LedStrip<T>,
+    rest_us: u32,
+}
+
+impl<T> Shown<'_, T> where T: Transmitter {
+    pub fn led_strip(&self) -> &LedStrip<T> {
+        self.led_strip
+    }
+
+    /// Latch now and wait `us` microseconds (at least `LATCH_TIME`) before the next frame.
+    pub fn rest(mut self, us: u32) {
+        self.rest_us = us.max(LATCH_TIME);
+    }
+}
+
+impl<T> Drop for Shown<'_, T> where T: Transmitter {
+    fn drop(&mut self) {
+        self.led_strip.rest(self.rest_us);
+    }
+}
+
+/// Options of a `LedStrip`, e.g.
+/// `LedStrip::builder(pin).leds(60).order(ColorOrder::Grb).brightness(96).build()`.
+pub struct LedStripBuilder<T: Transmitter> {
+    transmitter: T,
+    led_count: usize,
+    order: ColorOrder,
+    brightness: u8,
+    dimming_curve: DimmingCurve,
+    soft_start: u16,
+    temperature: Temperature,
+    correction: Correction,
+    calibration: (u8, u8, u8),
+    brightness_map: Option<&'static [u8]>,
+    gamma: Option<&'static dyn ByteSource>,
+    transform: Option<&'static dyn PixelTransform>,
+    voltage_drop: Option<VoltageDrop>,
+    power_limit: Option<PowerLimit>,
+    thermal_limit: Option<ThermalLimit>,
+    watchdog: Option<(fn(), usize)>,
+    #[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+    interrupt_masking: InterruptMasking,
+    #[cfg(feature = "dithering")]
+    residuals: Option<&'static mut [[u8; 3]]>,
+}
+
+impl<T> LedStripBuilder<T> where T: Transmitter {
+    pub fn new(transmitter: T) -> LedStripBuilder<T> {
+        LedStripBuilder {
+            transmitter,
+            led_count: 0,
+            order: ColorOrder::Grb,
+            brightness: 255,
+            dimming_curve: DimmingCurve::Linear,
+            soft_start: 0,
+            temperature: Temperature::Uncorrected,
+            correction: Correction::Uncorrected,
+            calibration: (255, 255, 255),
+            brightness_map: None,
+            gamma: None,
+            transform: None,
+            voltage_drop: None,
+            power_limit: None,
+            thermal_limit: None,
+            watchdog: None,
+            #[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+            interrupt_masking: InterruptMasking::None,
+            #[cfg(feature = "dithering")]
+            residuals: None,
+        }
+    }
+
+    pub fn leds(mut self, led_count: usize) -> LedStripBuilder<T> {
+        self.led_count = led_count;
+
+        self
+    }
+
+    pub fn order(mut self, order: ColorOrder) -> LedStripBuilder<T> {
+        self.order = order;
+
+        self
+    }
+
+    pub fn brightness(mut self, brightness: u8) -> LedStripBuilder<T> {
+        self.brightness = brightness;
+
+        self
+    }
+
+    pub fn dimming_curve(mut self, dimming_curve: DimmingCurve) -> LedStripBuilder<T> {
+        self.dimming_curve = dimming_curve;
+
+        self
+    }
+
+    pub fn soft_start(mut self, frames: u16) -> LedStripBuilder<T> {
+        self.soft_start = frames;
+
+        self
+    }
+
+    pub fn temperature(mut self, temperature: Temperature) -> LedStripBuilder<T> {
+        self.temperature = temperature;
+
+        self
+    }
+
+    pub fn correction(mut self, correction: Correction) -> LedStripBuilder<T> {
+        self.correction = correction;
+
+        self
+    }
+
+    pub fn calibration(mut self, red: u8, green: u8, blue: u8) -> LedStripBuilder<T> {
+        self.calibration = (red, green, blue);
+
+        self
+    }
+
+    pub fn brightness_map(mut self, brightness_map: &'static [u8]) -> LedStripBuilder<T> {
+        self.brightness_map = Some(brightness_map);
+
+        self
+    }
+
+    pub fn gamma(mut self, gamma: &'static dyn ByteSource) -> LedStripBuilder<T> {
+        self.gamma = Some(gamma);
+
+        self
+    }
+
+    pub fn transform(mut self, transform: &'static dyn PixelTransform) -> LedStripBuilder<T> {
+        self.transform = Some(transform);
+
+        self
+    }
+
+    pub fn voltage_drop(mut self, voltage_drop: VoltageDrop) -> LedStripBuilder<T> {
+        self.voltage_drop = Some(voltage_drop);
+
+        self
+    }
+
+    pub fn power_limit(mut self, power_limit: PowerLimit) -> LedStripBuilder<T> {
+        self.power_limit = Some(power_limit);
+
+        self
+    }
+
+    pub fn thermal_limit(mut self, thermal_limit: ThermalLimit) -> LedStripBuilder<T> {
+        self.thermal_limit = Some(thermal_limit);
+
+        self
+    }
+
+    pub fn watchdog(mut self, feed: fn(), pixels: usize) -> LedStripBuilder<T> {
+        self.watchdog = Some((feed, pixels));
+
+        self
+    }
+
+    #[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+    pub fn interrupt_masking(mut self, interrupt_masking: InterruptMasking) -> LedStripBuilder<T> {
+        self.interrupt_masking = interrupt_masking;
+
+        self
+    }
+
+    #[cfg(feature = "dithering")]
+    pub fn dithering(mut self, residuals: &'static mut [[u8; 3]]) -> LedStripBuilder<T> {
+        self.residuals = Some(residuals);
+
+        self
+    }
+
+    pub fn build(self) -> LedStrip<T> {
+        let mut led_strip = LedStrip::with_transmitter(self.led_count, self.transmitter);
+        let (red, green, blue) = self.calibration;
+        led_strip.set_order(self.order);
+        led_strip.set_brightness(self.brightness);
+        led_strip.set_dimming_curve(self.dimming_curve);
+        led_strip.set_soft_start(self.soft_start);
+        led_strip.set_temperature(self.temperature);
+        led_strip.set_correction(self.correction);
+        led_strip.set_calibration(red, green, blue);
+        led_strip.set_brightness_map(self.brightness_map);
+        led_strip.set_gamma(self.gamma);
+        led_strip.set_transform(self.transform);
+        led_strip.set_voltage_drop(self.voltage_drop);
+        led_strip.set_power_limit(self.power_limit);
+        led_strip.set_thermal_limit(self.thermal_limit);
+        led_strip.set_watchdog(self.watchdog);
+        #[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+        led_strip.set_interrupt_masking(self.interrupt_masking);
+        #[cfg(feature = "dithering")]
+        led_strip.set_dithering(self.residuals);
+
+        led_strip
+    }
+}
+
+/// Color wheel going from red to green, blue and back to red.
+pub fn wheel(position: u8) -> Color {
+    let position = 255 - position;
+
+    match position {
+        0..=84 => Color::RGB(255 - position * 3, 0, position * 3),
+        85..=169 => Color::RGB(0, (position - 85) * 3, 255 - (position - 85) * 3),
+        _ => Color::RGB((position - 170) * 3, 255 - (position - 170) * 3, 0),
+    }
+}
+
+const fn premultiply(channel: u8, alpha: u8) -> u8 {
+    (channel as u16 * alpha as u16 / 255) as u8
+}
+
+const fn hsv_to_rgb(hue: u8, saturation: u8, value: u8) -> (u8, u8, u8) {
+    if saturation == 0 {
+        return (value, value, value);
+    }
+
+    let region = hue / 43;
+    let remainder = (hue - region * 43) as u16 * 6;
+    let (saturation, value16) = (saturation as u16, value as u16);
+    let p = ((value16 * (255 - saturation)) >> 8) as u8;
+    let q = ((value16 * (255 - ((saturation * remainder) >> 8))) >> 8) as u8;
+    let t = ((value16 * (255 - ((saturation * (255 - remainder)) >> 8))) >> 8) as u8;
+
+    match region {
+        0 => (value, t, p),
+        1 => (q, value, p),
+        2 => (p, value, t),
+        3 => (p, q, value),
+        4 => (t, p, value),
+        _ => (value, p, q),
+    }
+}
+
+#[deprecated(note = "use `Transmitter::send_bytes()`, implemented by all the backends")]
+pub fn send_color<P: DataPin>(led: &mut P, color: &[u8]) -> () {
+    let mut bytes = [0; 3];
+
+    bytes.copy_from_slice(color);
+
+    for byte in bytes {
+        send_byte(led, byte);
+    }
+}
+
+pub fn send_bit<P: DataPin>(led: &mut P, bit: u8) -> () {
+    match bit {
+        0 => zero(led),
+        _ => one(led),
+    };
+}
+
+pub fn send_byte<P: DataPin>(led: &mut P, byte: u8) -> () {
+    for i in 0..8 {
+        send_bit(led, byte & (1 << (7 - i)));
+    }
+}
+
+pub fn reset<P: DataPin>(led: &mut P, us: u32) -> () {
+    set_low_for(led, us * 1_000);
+}
+
+#[cfg(not(feature = "trinket"))]
+pub fn zero<P: DataPin>(led: &mut P) -> () {
+    led.set_high();
+    led.set_low();
+    led.set_low();
+    led.set_low();
+    led.set_low();
+    led.set_low();
+}
+
+#[cfg(not(feature = "trinket"))]
+pub fn one<P: DataPin>(led: &mut P) -> () {
+    led.set_high();
+    led.set_high();
+    led.set_high();
+    led.set_low();
+    led.set_low();
+    led.set_low();
+}
+
+/// On an 8 MHz ATtiny85, each toggle lasts about 416 ns so a bit is 3 of them.
+#[cfg(feature = "trinket")]
+pub fn zero<P: DataPin>(led: &mut P) -> () {
+    led.set_high();
+    led.set_low();
+    led.set_low();
+}
+
+#[cfg(feature = "trinket")]
+pub fn one<P: DataPin>(led: &mut P) -> () {
+    led.set_high();
+    led.set_high();
+    led.set_low();
+}
+
+pub fn set_high_for<P: DataPin>(led: &mut P, ns: u32) -> () {
+    led.set_high();
+    led.delay_ns(ns);
+}
+
+pub fn set_low_for<P: DataPin>(led: &mut P, ns: u32) -> () {
+    led.set_low();
+    led.delay_ns(ns);
+}
+
+pub const fn parse(color: &str) -> Result<[u8; 3], ColorParseError> {
+    let bytes = color.as_bytes();
+    let start = match bytes {
+        [b'#', ..] => 1,
+        _ => 0,
+    };
+    let length = bytes.len() - start;
+
+    if length != 6 && length != 3 {
+        return Err(ColorParseError::InvalidLength);
+    }
+
+    let mut channels = [0; 3];
+    let mut index = 0;
+
+    while index < 3 {
+        channels[index] = match length {
+            6 => match (hex_digit(bytes[start + 2 * index]), hex_digit(bytes[start + 2 * index + 1])) {
+                (Some(high), Some(low)) => (high << 4) | low,
+                _ => return Err(ColorParseError::InvalidDigit),
+            },
+            _ => match hex_digit(bytes[start + index]) {
+                Some(digit) => digit * 17,
+                None => return Err(ColorParseError::InvalidDigit),
+            },
+        };
+        index += 1;
+    }
+
+    Ok(channels)
+}
+
+const fn hex_digit(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Color from a hex literal checked at compile time, e.g. `hex!("#FF8800")`.
+#[macro_export]
+macro_rules! hex {
+    ($color:literal) => {{
+        const COLOR: $crate::led::Color = $crate::led::Color::from_hex($color);
+
+        COLOR
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::led::{wheel, Color, ColorParseError};
+
+    #[test]
+    fn opacity() {
+        assert_eq!(
+            Color::Orange.opacity(0.5).to_rgb(),
+            (127, 63, 0),
+        );
+        assert_eq!(
+            Color::Orange.opacity(0.25).to_rgb(),
+            (63, 31, 0),
+        );
+    }
+
+    #[test]
+    fn mix() {
+        assert_eq!(
+            Color::Red.mix(Color::Green).to_rgb(),
+            Color::Yellow.opacity(0.5).to_rgb(),
+        );
+        assert_eq!(
+            Color::Red.mix_ratio(Color::Green, 1.0, 1.0).to_rgb(),
+            Color::Yellow.to_rgb(),
+        );
+        assert_eq!(
+            Color::Red.mix_ratio(Color::Green, 0.5, 1.0).to_rgb(),
+            (127, 255, 0),
+        );
+    }
+
+    #[test]
+    fn equality() {
+        let color = Color::Red;
+        let dimmed = color.opacity(0.5);
+
+        assert_eq!(color, Color::Red);
+        assert_eq!(dimmed, Color::RGB(127, 0, 0));
+        assert_eq!(color, Color::RGB(255, 0, 0));
+        assert_eq!(Color::NUM(0x0000FF), Color::RGBA(0, 0, 255, 255));
+        assert_ne!(Color::RGBA(0, 0, 255, 128), Color::RGB(0, 0, 128));
+        assert_ne!(Color::Black, Color::RGBA(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Color::from(0xFF8800), Color::RGB(255, 136, 0));
+        assert_eq!(Color::from(0x01FF8800), Color::RGB(255, 136, 0));
+        assert_eq!(Color::NUM(0xFF0000).to_rgb(), (255, 0, 0));
+        assert_eq!(Color::NUM(0x123456).to_u32(), 0x123456);
+        assert_eq!(Color::Orange.to_u32(), 0xFF7F00);
+        assert_eq!(Color::from((1, 2, 3)), Color::RGB(1, 2, 3));
+        assert_eq!(Color::from([1, 2, 3]), Color::RGB(1, 2, 3));
+
+        let bytes: [u8; 3] = Color::Orange.into();
+        let channels: (u8, u8, u8) = Color::Orange.into();
+
+        assert_eq!(bytes, [255, 127, 0]);
+        assert_eq!(channels, (255, 127, 0));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(Color::Red + Color::Blue, Color::RGB(255, 0, 255));
+        assert_eq!(Color::Orange + Color::Orange, Color::RGB(255, 254, 0));
+        assert_eq!(Color::Gray + Color::Gray + Color::Gray, Color::RGB(255, 255, 255));
+        assert_eq!(Color::White - Color::Red, Color::RGB(0, 255, 255));
+        assert_eq!(Color::Gray - Color::White, Color::RGB(0, 0, 0));
+        assert_eq!(Color::Orange * 255, Color::RGB(255, 127, 0));
+        assert_eq!(Color::Orange * 127, Color::RGB(127, 63, 0));
+        assert_eq!(Color::Orange * 64, Color::RGB(64, 32, 0));
+    }
+
+    #[test]
+    fn hue() {
+        assert_eq!(Color::HSV(0, 255, 255).to_rgb(), (255, 0, 0));
+        assert_eq!(Color::HSV(172, 255, 255).to_rgb(), (0, 0, 255));
+        assert_eq!(Color::HSV(42, 0, 200).to_rgb(), (200, 200, 200));
+        assert_eq!(Color::Red.to_hsv(), (0, 255, 255));
+        assert_eq!(Color::Green.to_hsv(), (85, 255, 255));
+        assert_eq!(Color::Magenta.to_hsv(), (213, 255, 255));
+        assert_eq!(Color::Gray.to_hsv(), (0, 0, 127));
+        assert_eq!(Color::Red.rotate_hue(85).to_rgb(), (3, 255, 0));
+        assert_eq!(Color::Blue.rotate_hue(85), Color::HSV(0, 255, 255));
+    }
+
+    #[test]
+    fn color_wheel() {
+        assert_eq!(wheel(0).to_rgb(), (255, 0, 0));
+        assert_eq!(wheel(85).to_rgb(), (0, 255, 0));
+        assert_eq!(wheel(170).to_rgb(), (0, 0, 255));
+        assert_eq!(wheel(255).to_rgb(), (255, 0, 0));
+    }
+
+    #[test]
+    fn wavelength() {
+        assert_eq!(Color::from_wavelength(300), Color::Black);
+        assert_eq!(Color::from_wavelength(380), Color::RGB(77, 0, 77));
+        assert_eq!(Color::from_wavelength(440), Color::RGB(0, 0, 255));
+        assert_eq!(Color::from_wavelength(500), Color::RGB(0, 255, 127));
+        assert_eq!(Color::from_wavelength(550), Color::RGB(145, 255, 0));
+        assert_eq!(Color::from_wavelength(700), Color::RGB(255, 0, 0));
+        assert_eq!(Color::from_wavelength(780), Color::RGB(77, 0, 0));
+        assert_eq!(Color::from_wavelength(781), Color::Black);
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(Color::Orange.distance(Color::RGB(255, 127, 0)), 0);
+        assert_eq!(Color::Black.distance(Color::White), 764);
+        assert_eq!(Color::RGB(250, 10, 5).distance(Color::Red), 22);
+        assert!(Color::Black.distance(Color::Green) > Color::Black.distance(Color::Blue));
+    }
+
+    #[cfg(feature = "colors-basic")]
+    #[test]
+    fn nearest_named() {
+        assert_eq!(Color::RGB(250, 10, 5).nearest_named(30), Some(("red", Color::Red)));
+        assert_eq!(Color::RGB(0, 130, 250).nearest_named(30), Some(("turquoise", Color::Turquoise)));
+        assert_eq!(Color::RGB(128, 64, 0).nearest_named(30), None);
+        assert!(Color::RGB(128, 64, 0).nearest_named(u32::MAX).is_some());
+    }
+
+    #[test]
+    fn perceptual() {
+        assert_eq!(Color::Red.mix_perceptual(Color::Green), Color::RGB(179, 180, 0));
+        assert_eq!(Color::Red.lerp_perceptual(Color::Green, 0), Color::RGB(255, 0, 0));
+        assert_eq!(Color::Red.lerp_perceptual(Color::Green, 255), Color::RGB(0, 255, 0));
+        assert_eq!(Color::Black.mix_perceptual(Color::Gray), Color::RGB(89, 89, 89));
+    }
+
+    #[test]
+    fn schemes() {
+        let seed = Color::HSV(10, 255, 200);
+
+        assert_eq!(seed.complement(), Color::HSV(138, 255, 200));
+        assert_eq!(seed.triad(), [seed, Color::HSV(95, 255, 200), Color::HSV(181, 255, 200)]);
+        assert_eq!(seed.analogous(20), [Color::HSV(246, 255, 200), seed, Color::HSV(30, 255, 200)]);
+        assert_eq!(Color::Red.complement().to_rgb(), (0, 255, 252));
+    }
+
+    #[test]
+    fn lerp() {
+        assert_eq!(Color::Red.lerp(Color::Blue, 0), Color::RGB(255, 0, 0));
+        assert_eq!(Color::Red.lerp(Color::Blue, 255), Color::RGB(0, 0, 255));
+        assert_eq!(Color::Red.lerp(Color::Blue, 51), Color::RGB(204, 0, 51));
+        assert_eq!(Color::HSV(10, 255, 255).lerp_hsv(Color::HSV(250, 255, 55), 128), Color::HSV(2, 255, 155));
+        assert_eq!(Color::HSV(250, 255, 255).lerp_hsv(Color::HSV(10, 255, 255), 255), Color::HSV(10, 255, 255));
+        assert_eq!(Color::HSV(0, 255, 255).lerp_hsv(Color::HSV(100, 255, 255), 128), Color::HSV(50, 255, 255));
+    }
+
+    #[test]
+    fn over() {
+        assert_eq!(
+            Color::RGBA(255, 0, 0, 255).over(Color::Blue).to_rgb(),
+            (255, 0, 0),
+        );
+        assert_eq!(
+            Color::RGBA(255, 0, 0, 0).over(Color::Blue).to_rgb(),
+            (0, 0, 255),
+        );
+        assert_eq!(
+            Color::RGBA(255, 0, 0, 51).over(Color::Blue).to_rgb(),
+            (51, 0, 204),
+        );
+        assert_eq!(
+            Color::RGBA(255, 255, 255, 102).to_rgb(),
+            (102, 102, 102),
+        );
+        assert_eq!(
+            Color::RGBA(255, 0, 0, 102).over(Color::RGBA(0, 0, 255, 85)).to_rgba(),
+            (170, 0, 85, 153),
+        );
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(Color::try_from_hex("FF8800").map(|color| color.to_rgb()), Ok((255, 136, 0)));
+        assert_eq!(Color::try_from_hex("#ff8800").map(|color| color.to_rgb()), Ok((255, 136, 0)));
+        assert_eq!(Color::try_from_hex("F80").map(|color| color.to_rgb()), Ok((255, 136, 0)));
+        assert_eq!(Color::try_from("#0af").map(|color| color.to_rgb()), Ok((0, 170, 255)));
+        assert_eq!(Color::try_from_hex("FF880").map(|color| color.to_rgb()), Err(ColorParseError::InvalidLength));
+        assert_eq!(Color::try_from_hex("FF88G0").map(|color| color.to_rgb()), Err(ColorParseError::InvalidDigit));
+        assert_eq!(Color::try_from_hex("été").map(|color| color.to_rgb()), Err(ColorParseError::InvalidLength));
+        assert_eq!(Color::HEX("oops").to_rgb(), (0, 0, 0));
+    }
+
+    #[test]
+    fn const_hex() {
+        const ORANGE: Color = Color::from_hex("#FF8800");
+
+        assert_eq!(ORANGE.to_rgb(), (255, 136, 0));
+        assert_eq!(crate::hex!("0af").to_rgb(), (0, 170, 255));
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn micro_format() {
+        use crate::led::LedStrip;
+        use crate::mock::MockPin;
+
+        struct Buffer([u8; 64], usize);
+
+        impl ufmt::uWrite for Buffer {
+            type Error = ();
+
+            fn write_str(&mut self, s: &str) -> Result<(), ()> {
+                self.0[self.1..self.1 + s.len()].copy_from_slice(s.as_bytes());
+                self.1 += s.len();
+
+                Ok(())
+            }
+        }
+
+        let mut led_strip = LedStrip::with_transmitter(3, MockPin::<256>::new());
+        led_strip.set_brightness(200);
+        led_strip.each(|_| Color::Black);
+        led_strip.rest(50);
+
+        let mut buffer = Buffer([0; 64], 0);
+        ufmt::uDisplay::fmt(&Color::Orange, &mut ufmt::Formatter::new(&mut buffer)).unwrap();
+        ufmt::uWrite::write_str(&mut buffer, " ").unwrap();
+        ufmt::uDisplay::fmt(&led_strip.status(), &mut ufmt::Formatter::new(&mut buffer)).unwrap();
+
+        assert_eq!(&buffer.0[..buffer.1], b"#FF7F00 leds=3 frame=1 brightness=200");
+    }
+
+    #[test]
+    fn self_test() {
+        use crate::led::LedStrip;
+        use crate::mock::MockPin;
+
+        let mut led_strip = LedStrip::with_transmitter(2, MockPin::<1024>::new());
+        led_strip.self_test();
+
+        let pin = led_strip.transmitter();
+        let mut frames = [[0; 6]; 7];
+
+        for (index, byte) in pin.bytes().enumerate() {
+            frames[index / 6][index % 6] = byte;
+        }
+
+        assert!(!pin.overflowed());
+        assert_eq!(pin.bytes().count(), 42);
+        assert_eq!(pin.latches().count(), 7);
+        assert_eq!(frames, [
+            [0, 255, 0, 0, 255, 0],
+            [255, 0, 0, 255, 0, 0],
+            [0, 0, 255, 0, 0, 255],
+            [255, 255, 255, 255, 255, 255],
+            [255, 255, 255, 0, 0, 0],
+            [0, 0, 0, 255, 255, 255],
+            [0, 0, 0, 0, 0, 0],
+        ]);
+    }
+
+    #[test]
+    fn builder() {
+        use crate::led::{ColorOrder, LedStripBuilder};
+        use crate::mock::MockPin;
+
+        let mut led_strip = LedStripBuilder::new(MockPin::<128>::new())
+            .leds(2)
+            .order(ColorOrder::Brg)
+            .brightness(127)
+            .build();
+
+        assert_eq!(led_strip.led_count(), 2);
+        assert_eq!(led_strip.order(), ColorOrder::Brg);
+        led_strip.rgb(255, 0, 3);
+        assert!(led_strip.transmitter().bytes().eq([1, 127, 0]));
+        assert_eq!(ColorOrder::Gbr.arrange([1, 2, 3]), [2, 3, 1]);
+
+        for order in [ColorOrder::Rgb, ColorOrder::Rbg, ColorOrder::Grb, ColorOrder::Gbr, ColorOrder::Brg, ColorOrder::Bgr] {
+            assert_eq!(order.unarrange(order.arrange([1, 2, 3])), [1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn active_count() {
+        use crate::led::LedStrip;
+        use crate::mock::MockPin;
+
+        let mut led_strip = LedStrip::with_transmitter(3, MockPin::<256>::new());
+        led_strip.set_active_count(5);
+        assert_eq!(led_strip.active_count(), 3);
+
+        led_strip.set_active_count(1);
+        led_strip.each(|_| Color::White);
+
+        assert_eq!(led_strip.active_count(), 1);
+        assert!(led_strip.transmitter().bytes().eq([255, 255, 255, 0, 0, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn write() {
+        use crate::led::LedStrip;
+        use crate::mock::MockPin;
+
+        let mut led_strip = LedStrip::with_transmitter(2, MockPin::<512>::new());
+        led_strip.write([(1, 2, 3)]);
+        led_strip.write([Color::Red, Color::Blue, Color::White]);
+
+        assert_eq!(led_strip.transmitter().latches().count(), 1);
+        assert!(led_strip.transmitter().bytes().eq([2, 1, 3, 0, 0, 0, 0, 255, 0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn write_power_limit() {
+        use crate::led::{LATCH_TIME, LedStripBuilder};
+        use crate::mock::MockPin;
+        use crate::power::PowerLimit;
+
+        let mut led_strip = LedStripBuilder::new(MockPin::<1024>::new()).leds(10)
+            .power_limit(PowerLimit::new(5_000, 100)).build();
+        let mut sent = 0;
+
+        for _ in 0..2 {
+            // Not `Clone`, read once.
+            led_strip.write(core::iter::from_fn(|| {
+                sent += 1;
+                Some(Color::RGB(200, 200, 100))
+            }));
+            led_strip.rest(LATCH_TIME);
+            led_strip.transmitter_mut().clear();
+
+            assert!(led_strip.stats().milliamps <= 100);
+        }
+
+        // Scaled from the estimate of the first frame.
+        assert!(led_strip.stats().milliamps >= 95);
+        assert_eq!(sent, 20);
+
+        // Brighter than estimated, dimmed while sent.
+        led_strip.write([Color::White; 10]);
+        led_strip.rest(LATCH_TIME);
+        assert!(led_strip.stats().milliamps <= 100);
+    }
+
+    #[test]
+    fn watchdog() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        use crate::led::LedStrip;
+        use crate::mock::MockPin;
+
+        static FEEDS: AtomicUsize = AtomicUsize::new(0);
+
+        let mut led_strip = LedStrip::with_transmitter(10, MockPin::<1>::new());
+        led_strip.set_watchdog(Some((|| { FEEDS.fetch_add(1, Ordering::Relaxed); }, 4)));
+        led_strip.each(|_| Color::Red);
+        led_strip.rest(300);
+
+        assert_eq!(FEEDS.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn latch() {
+        use core::cell::Cell;
+        use crate::led::{LedStrip, Transmitter, LATCH_TIME};
+        use crate::mock::MockPin;
+
+        struct Counter<'a>(&'a Cell<u32>);
+
+        impl Transmitter for Counter<'_> {
+            fn send_byte(&mut self, _byte: u8) {}
+
+            fn reset(&mut self, us: u32) {
+                self.0.set(self.0.get() + us);
+            }
+        }
+
+        let mut led_strip = LedStrip::with_transmitter(2, MockPin::<256>::new());
+        assert!(led_strip.is_latched());
+        led_strip.latch();
+        assert_eq!(led_strip.transmitter().latches().count(), 0);
+
+        led_strip.color(Color::Red);
+        assert!(!led_strip.is_latched());
+        led_strip.latch();
+        assert!(led_strip.is_latched());
+        assert!(led_strip.transmitter().latches().eq([LATCH_TIME * 1000]));
+
+        let reset = Cell::new(0);
+        LedStrip::with_transmitter(2, Counter(&reset)).each(|_| Color::Red);
+        assert_eq!(reset.get(), LATCH_TIME);
+        LedStrip::with_transmitter(2, Counter(&reset));
+        assert_eq!(reset.get(), LATCH_TIME);
+    }
+
+    #[test]
+    fn show() {
+        use crate::error::LedError;
+        use crate::led::{LedStrip, LATCH_TIME};
+        use crate::mock::MockPin;
+
+        let mut led_strip = LedStrip::with_transmitter(1, MockPin::<256>::new());
+        led_strip.each(|_| Color::Red);
+        led_strip.each(|_| Color::Blue);
+        assert!(led_strip.transmitter().latches().eq([LATCH_TIME * 1000]));
+
+        led_strip.show(|_| Color::Green).rest(10);
+        assert!(led_strip.is_latched());
+        assert_eq!(led_strip.status().frame, 3);
+
+        led_strip.color(Color::Red);
+        assert_eq!(led_strip.try_each(|_| Color::Red), Err(LedError::NotLatched));
+        led_strip.latch();
+
+        let frame = led_strip.show(|_| Color::Black);
+        assert!(!frame.led_strip().is_latched());
+        drop(frame);
+        assert!(led_strip.transmitter().latches().eq([LATCH_TIME * 1000; 5]));
+    }
+
+    #[test]
+    fn each_frame() {
+        use crate::led::LedStrip;
+        use crate::mock::MockPin;
+
+        let mut led_strip = LedStrip::with_transmitter(2, MockPin::<256>::new());
+        let frame = |led_index, frame, millis: u32| Color::RGB(led_index as u8, frame as u8, millis as u8);
+
+        led_strip.each_frame(frame);
+        led_strip.rest(19_940);
+        led_strip.each_frame(frame);
+
+        assert_eq!(led_strip.millis(), 20);
+        assert!(led_strip.transmitter().bytes().eq([0, 0, 0, 0, 1, 0, 1, 0, 20, 1, 1, 20]));
+    }
+
+    #[test]
+    fn stats() {
+        use crate::led::{LedStrip, Stats};
+        use crate::mock::MockPin;
+        use crate::pacer::Pacer;
+
+        let mut led_strip = LedStrip::with_transmitter(2, MockPin::<256>::new());
+        let mut pacer = Pacer::every_n_millis(20);
+        assert!(pacer.ready(0));
+        assert!(pacer.ready(50));
+
+        led_strip.each(|_| Color::White);
+        led_strip.rest(300);
+
+        assert_eq!(led_strip.stats().with_pacer(&pacer), Stats {
+            frames: 1,
+            last_frame_us: 360,
+            milliamps: 86,
+            late_frames: 1,
+            dropped_frames: 1,
+        });
+    }
+
+    #[test]
+    fn dimming_curve() {
+        use crate::correction::DimmingCurve;
+        use crate::led::LedStripBuilder;
+        use crate::mock::MockPin;
+
+        let mut led_strip = LedStripBuilder::new(MockPin::<64>::new()).leds(1).brightness(128)
+            .dimming_curve(DimmingCurve::Square).build();
+        assert_eq!(led_strip.brightness(), 64);
+        led_strip.rgb(0, 0, 255);
+
+        assert!(led_strip.transmitter().bytes().eq([0, 0, 64]));
+    }
+
+    #[test]
+    fn thermal_limit() {
+        use crate::led::LedStripBuilder;
+        use crate::mock::MockPin;
+        use crate::power::ThermalLimit;
+
+        let mut led_strip = LedStripBuilder::new(MockPin::<64>::new()).leds(1).brightness(200)
+            .thermal_limit(ThermalLimit::new(450, 650)).build();
+        led_strip.feed_temperature(400);
+        assert_eq!(led_strip.brightness(), 200);
+
+        led_strip.feed_temperature(550);
+        assert_eq!(led_strip.thermal_scale(), 128);
+        assert_eq!(led_strip.brightness(), 100);
+        led_strip.rgb(255, 0, 0);
+
+        assert!(led_strip.transmitter().bytes().eq([0, 100, 0]));
+    }
+
+    #[test]
+    fn fade() {
+        use crate::led::{Fade, LATCH_TIME, LedStrip};
+        use crate::mock::MockPin;
+        use crate::pacer::Pacer;
+
+        let pacer = Pacer::every_n_millis(20);
+        let mut led_strip = LedStrip::with_transmitter(1, MockPin::<1024>::new());
+        let mut levels = [0; 6];
+
+        led_strip.fade_out(100, &pacer);
+
+        for level in &mut levels {
+            *level = led_strip.brightness();
+            led_strip.color(Color::White);
+            led_strip.rest(LATCH_TIME);
+        }
+
+        assert_eq!(levels, [255, 204, 153, 102, 51, 0]);
+        assert!(!led_strip.is_fading());
+
+        led_strip.fade_in(40, &pacer);
+        assert!(led_strip.is_fading());
+        assert_eq!(led_strip.brightness(), 0);
+        led_strip.color(Color::White);
+        led_strip.rest(LATCH_TIME);
+        assert_eq!(led_strip.brightness(), 127);
+
+        // Fading out again from the middle of the fade in.
+        led_strip.fade_out(40, &pacer);
+        assert_eq!(led_strip.brightness(), 128);
+
+        // Without an earlier fade, e.g. at boot.
+        let mut led_strip = LedStrip::with_transmitter(1, MockPin::<1024>::new());
+        led_strip.fade_in(60, &pacer);
+
+        for level in &mut levels[..4] {
+            *level = led_strip.brightness();
+            led_strip.color(Color::White);
+            led_strip.rest(LATCH_TIME);
+        }
+
+        assert_eq!(levels[..4], [0, 85, 170, 255]);
+        assert!(!led_strip.is_fading());
+
+        // Long enough for `elapsed * 255` to overflow a `u32`.
+        let fade = Fade { start_frame: 0, frames: 4_000_000_000, out: false };
+
+        assert_eq!(fade.level(2_000_000_000), 127);
+        assert_eq!(fade.level(4_000_000_000), 255);
+    }
+
+    #[test]
+    fn power_limit() {
+        use core::cell::Cell;
+        use crate::color16::Color16;
+        use crate::led::{LedStrip, LedStripBuilder};
+        use crate::mock::MockPin;
+        use crate::power::{channel_draw, frame_milliamps, PowerLimit};
+
+        for (channel, milliamps) in [(128, 33), (201, 97), (255, 150)] {
+            let mut led_strip = LedStripBuilder::new(MockPin::<2048>::new()).leds(30)
+                .power_limit(PowerLimit::new(5_000, milliamps)).build();
+            let calls = Cell::new(0);
+
+            // The first frame is scaled from an all white estimate, the second from the first.
+            for frame in 0..2 {
+                led_strip.each(|_| {
+                    calls.set(calls.get() + 1);
+
+                    Color::RGB(channel, channel, channel)
+                });
+
+                let bytes: [u8; 90] = {
+                    let mut bytes = led_strip.transmitter().bytes();
+                    core::array::from_fn(|_| bytes.next().unwrap())
+                };
+                let draw = bytes.chunks(3).map(|grb| channel_draw(grb[1], grb[0], grb[2])).sum();
+
+                // Never above the budget, even by the rounding of the scaled channels.
+                assert!(frame_milliamps(30, draw) <= u32::from(milliamps));
+                assert!(frame == 0 || frame_milliamps(30, draw) >= u32::from(milliamps) * 9 / 10);
+                led_strip.latch();
+                led_strip.transmitter_mut().clear();
+            }
+
+            assert_eq!(calls.get(), 60);
+        }
+
+        // Black frames on a budget below the idle current of the strip.
+        let mut led_strip = LedStripBuilder::new(MockPin::<2048>::new()).leds(30)
+            .power_limit(PowerLimit::new(5_000, 20)).build();
+        let frames: [fn(&mut LedStrip<MockPin<2048>>); 4] = [
+            |led_strip| led_strip.each(|_| Color::Black),
+            |led_strip| led_strip.write([Color::Black; 30]),
+            |led_strip| led_strip.write([Color::Black; 30]),
+            |led_strip| led_strip.fill16(Color16::from(Color::Black)),
+        ];
+
+        for frame in frames {
+            frame(&mut led_strip);
+
+            assert_eq!(led_strip.transmitter().bytes().count(), 90);
+            assert!(led_strip.transmitter().bytes().all(|byte| byte == 0));
+            led_strip.latch();
+            led_strip.transmitter_mut().clear();
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "dithering", feature = "std"))]
+    fn dithered_off() {
+        use std::vec;
+        use crate::led::LedStripBuilder;
+        use crate::mock::MockPin;
+
+        let mut led_strip = LedStripBuilder::new(MockPin::<2048>::new()).leds(3)
+            .brightness(0).dithering(vec![[0; 3]; 3].leak()).build();
+
+        for _ in 0..8 {
+            led_strip.each(|_| Color::White);
+            led_strip.latch();
+        }
+
+        assert_eq!(led_strip.transmitter().bytes().count(), 72);
+        assert!(led_strip.transmitter().bytes().all(|byte| byte == 0));
+    }
+
+    #[test]
+    fn voltage_drop() {
+        use crate::led::LedStripBuilder;
+        use crate::mock::MockPin;
+        use crate::power::VoltageDrop;
+
+        let mut led_strip = LedStripBuilder::new(MockPin::<256>::new()).leds(2)
+            .voltage_drop(VoltageDrop::new(2, (0, 40, 80))).build();
+        led_strip.each(|_| Color::White);
+
+        assert!(led_strip.transmitter().bytes().eq([215, 255, 175, 255, 255, 255]));
+    }
+
+    #[test]
+    fn interrupt_masking() {
+        use crate::interrupt::InterruptMasking;
+        use crate::led::LedStripBuilder;
+        use crate::mock::MockPin;
+
+        let mut led_strip = LedStripBuilder::new(MockPin::<256>::new()).leds(2)
+            .interrupt_masking(InterruptMasking::PerPixel).build();
+        led_strip.each(|led_index| [Color::Red, Color::Blue][led_index]);
+
+        assert_eq!(led_strip.interrupt_masking(), InterruptMasking::PerPixel);
+        assert!(led_strip.transmitter().bytes().eq([0, 255, 0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn inverted() {
+        use crate::led::{Inverted, LedStrip};
+        use crate::mock::{MockPin, Run};
+
+        let mut led_strip = LedStrip::with_transmitter(1, Inverted(MockPin::<64>::new()));
+        led_strip.color(Color::Green);
+        led_strip.rest(300);
+
+        let runs = led_strip.transmitter().0.runs();
+
+        assert_eq!(runs[..2], [Run { high: false, ticks: 3, ns: 0 }, Run { high: true, ticks: 3, ns: 0 }]);
+        assert_eq!(runs.last(), Some(&Run { high: true, ticks: 6, ns: 300_000 }));
+    }
+
+    #[test]
+    fn gamma() {
+        use crate::gamma::GAMMA_2_2;
+        use crate::led::LedStripBuilder;
+        use crate::mock::MockPin;
+
+        let mut led_strip = LedStripBuilder::new(MockPin::<64>::new()).leds(1).gamma(&GAMMA_2_2).build();
+        led_strip.rgb(128, 255, 0);
+
+        assert!(led_strip.transmitter().bytes().eq([255, 56, 0]));
+    }
+
+    #[test]
+    fn transform() {
+        use crate::led::LedStripBuilder;
+        use crate::mock::MockPin;
+        use crate::transform::ChannelScale;
+
+        static REDSHIFT: ChannelScale = ChannelScale(255, 127, 0);
+
+        let mut led_strip = LedStripBuilder::new(MockPin::<64>::new()).leds(1).transform(&REDSHIFT).build();
+        led_strip.color(Color::White);
+
+        assert!(led_strip.transmitter().bytes().eq([127, 255, 0]));
+    }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn profile() {
+        use core::sync::atomic::{AtomicU32, Ordering};
+        use crate::led::LedStrip;
+        use crate::mock::MockPin;
+
+        static CLOCK: AtomicU32 = AtomicU32::new(0);
+
+        let mut led_strip = LedStrip::with_transmitter(2, MockPin::<256>::new());
+        led_strip.set_profiler(Some(|| CLOCK.fetch_add(5, Ordering::Relaxed) + 5));
+        led_strip.each(|_| Color::Red);
+        led_strip.rest(300);
+
+        let profile = led_strip.profile();
+        assert_eq!((profile.frames, profile.transmit_us, profile.render_us), (1, 15, 10));
+
+        led_strip.rest(300);
+        assert_eq!(led_strip.profile().frames, 1);
     }
 }
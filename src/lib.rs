@@ -1,2 +1,77 @@
 #![no_std]
-pub mod led;
\ No newline at end of file
+#![cfg_attr(all(target_arch = "avr", feature = "asm_experimental_arch"), feature(asm_experimental_arch))]
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod adalight;
+#[cfg(all(feature = "async", not(target_arch = "avr")))]
+pub mod asynch;
+pub mod audio;
+#[cfg(feature = "effects-balls")]
+pub mod balls;
+pub mod bits;
+pub mod blink;
+pub mod buffer;
+pub mod button;
+pub mod clock;
+pub mod chipset;
+pub mod clocked;
+pub mod color16;
+pub mod command;
+#[cfg(feature = "effects-confetti")]
+pub mod confetti;
+pub mod coordinate;
+pub mod correction;
+#[cfg(feature = "css-colors")]
+pub mod css;
+pub mod dmx;
+pub mod dump;
+pub mod effect;
+pub mod error;
+#[cfg(feature = "esp32")]
+pub mod esp32;
+pub mod flash;
+#[cfg(feature = "effects-flasher")]
+pub mod flasher;
+pub mod frames;
+pub mod gamma;
+pub mod gradient;
+pub mod idle;
+#[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+pub mod interrupt;
+pub mod ir;
+pub mod layer;
+pub mod led;
+#[cfg(feature = "effects-lightning")]
+pub mod lightning;
+pub mod marker;
+pub mod mask;
+pub mod math;
+#[cfg(any(test, feature = "testing"))]
+pub mod mock;
+pub mod multi;
+pub mod noise;
+#[cfg(feature = "effects-ocean")]
+pub mod ocean;
+pub mod pacer;
+pub mod parallel;
+pub mod polar;
+pub mod power;
+pub mod progress;
+#[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+pub mod queue;
+#[cfg(feature = "effects-rain")]
+pub mod rain;
+pub mod random;
+#[cfg(feature = "rp2040")]
+pub mod rp2040;
+pub mod sequence;
+pub mod settings;
+#[cfg(feature = "std")]
+pub mod simulator;
+pub mod stream;
+#[cfg(feature = "effects-sunrise")]
+pub mod sunrise;
+pub mod tpm2;
+pub mod transform;
+pub mod zone;
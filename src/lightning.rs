@@ -0,0 +1,153 @@
+use core::ops::Range;
+
+use crate::led::{Color, LedStrip, Transmitter};
+use crate::random::Random;
+
+/// Whether `now_ms` reached `at_ms`, counters wrapping around.
+fn reached(now_ms: u32, at_ms: u32) -> bool {
+    now_ms.wrapping_sub(at_ms) <= u32::MAX / 2
+}
+
+/// Storm of clustered white flashes of random intensity, at random intervals,
+/// on a segment of the strip.
+///
+/// Flashes never start more often than `max_flashes_per_second()`, 3 by
+/// default, the limit recommended for photosensitive viewers.
+pub struct Lightning {
+    random: Random,
+    segment: Range<usize>,
+    interval_ms: (u16, u16),
+    min_gap_ms: u32,
+    next_ms: Option<u32>,
+    last_flash_ms: Option<u32>,
+    flash_end_ms: Option<u32>,
+    remaining: u8,
+    intensity: u8,
+}
+
+impl Lightning {
+    pub fn new(random: Random, segment: Range<usize>) -> Lightning {
+        Lightning {
+            random,
+            segment,
+            interval_ms: (2_000, 10_000),
+            min_gap_ms: 334,
+            next_ms: None,
+            last_flash_ms: None,
+            flash_end_ms: None,
+            remaining: 0,
+            intensity: 0,
+        }
+    }
+
+    /// Shortest and longest time between two storms.
+    pub fn intervals(mut self, min_ms: u16, max_ms: u16) -> Lightning {
+        self.interval_ms = (min_ms, max_ms.max(min_ms));
+
+        self
+    }
+
+    pub fn max_flashes_per_second(mut self, flashes: u8) -> Lightning {
+        self.min_gap_ms = 1_000u32.div_ceil(u32::from(flashes.max(1)));
+
+        self
+    }
+
+    pub fn is_flashing(&self) -> bool {
+        self.flash_end_ms.is_some()
+    }
+
+    fn between(&mut self, min: u16, max: u16) -> u32 {
+        let offset = match u16::try_from(u32::from(max) - u32::from(min) + 1) {
+            Ok(span) => self.random.random16_below(span),
+            // The span of all the `u16` values.
+            Err(_) => self.random.random16(),
+        };
+
+        u32::from(min) + u32::from(offset)
+    }
+
+    pub fn update(&mut self, now_ms: u32) {
+        if let Some(end_ms) = self.flash_end_ms && reached(now_ms, end_ms) {
+            self.flash_end_ms = None;
+            let delay = match self.remaining {
+                0 => self.between(self.interval_ms.0, self.interval_ms.1),
+                _ => self.between(40, 150),
+            };
+            self.next_ms = Some(now_ms.wrapping_add(delay));
+        }
+
+        let next_ms = *self.next_ms.get_or_insert_with(|| now_ms.wrapping_add(u32::from(self.interval_ms.0)));
+        let rested = self.last_flash_ms.is_none_or(|last_ms| reached(now_ms, last_ms.wrapping_add(self.min_gap_ms)));
+
+        if self.is_flashing() || !reached(now_ms, next_ms) || !rested {
+            return;
+        }
+
+        if self.remaining == 0 {
+            self.remaining = self.random.random8_between(2, 6);
+        }
+
+        self.remaining -= 1;
+        self.intensity = self.random.random8_between(96, 255);
+        self.last_flash_ms = Some(now_ms);
+        self.flash_end_ms = Some(now_ms.wrapping_add(self.between(20, 80)));
+    }
+
+    pub fn color(&self, led_index: usize) -> Color {
+        match self.is_flashing() && self.segment.contains(&led_index) {
+            true => Color::RGB(self.intensity, self.intensity, self.intensity),
+            false => Color::Black,
+        }
+    }
+
+    pub fn show<T: Transmitter>(&mut self, led_strip: &mut LedStrip<T>, now_ms: u32) {
+        self.update(now_ms);
+        led_strip.each(|led_index| self.color(led_index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::led::Color;
+    use crate::lightning::Lightning;
+    use crate::random::Random;
+
+    #[test]
+    fn storm() {
+        let mut lightning = Lightning::new(Random::new(11), 2..5).intervals(500, 1_500);
+        let mut starts = [0u32; 64];
+        let mut count = 0;
+        let mut was_flashing = false;
+
+        for now in 0..20_000 {
+            lightning.update(now);
+
+            if lightning.is_flashing() && !was_flashing && count < starts.len() {
+                starts[count] = now;
+                count += 1;
+                assert_eq!(lightning.color(1), Color::Black);
+                assert_ne!(lightning.color(2), Color::Black);
+            }
+
+            was_flashing = lightning.is_flashing();
+        }
+
+        assert!(count > 10);
+        assert!(starts[..count].windows(2).all(|pair| pair[1] - pair[0] >= 334));
+        assert!(starts[..count].windows(4).all(|window| window[3] - window[0] >= 1_000));
+    }
+
+    #[test]
+    fn widest_intervals() {
+        let mut lightning = Lightning::new(Random::new(3), 1..2).intervals(0, u16::MAX);
+        let mut flashing = 0;
+
+        for now in 0..300_000 {
+            lightning.update(now);
+            flashing += u32::from(lightning.is_flashing());
+        }
+
+        assert!(flashing > 0);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::led::{ColorOrder, DataPin, Transmitter};
+
+/// Transmitter wrapper driving a spare `marker` pin, to correlate a logic
+/// analyzer capture of the data line with the frames: the marker goes high
+/// with the first byte of a frame, low when the frame ends and the latch
+/// starts, then pulses once when the latch is over.
+///
+/// ```ignore
+/// let marked = Marked::new(pins.d4.into_output(), pins.d5.into_output());
+/// let mut led_strip = LedStrip::with_transmitter(NUM_LEDS, marked);
+/// ```
+pub struct Marked<T: Transmitter, M: DataPin> {
+    transmitter: T,
+    marker: M,
+    in_frame: bool,
+}
+
+impl<T: Transmitter, M: DataPin> Marked<T, M> {
+    pub fn new(transmitter: T, mut marker: M) -> Marked<T, M> {
+        marker.set_low();
+
+        Marked {
+            transmitter,
+            marker,
+            in_frame: false,
+        }
+    }
+
+    pub fn transmitter(&self) -> &T {
+        &self.transmitter
+    }
+
+    pub fn transmitter_mut(&mut self) -> &mut T {
+        &mut self.transmitter
+    }
+
+    pub fn marker(&self) -> &M {
+        &self.marker
+    }
+}
+
+impl<T: Transmitter, M: DataPin> Transmitter for Marked<T, M> {
+    fn send_byte(&mut self, byte: u8) {
+        // Raised before the byte so the edge comes just before the first bit.
+        if !self.in_frame {
+            self.marker.set_high();
+            self.in_frame = true;
+        }
+
+        self.transmitter.send_byte(byte);
+    }
+
+    fn reset(&mut self, us: u32) {
+        self.marker.set_low();
+        self.in_frame = false;
+        self.transmitter.reset(us);
+        self.marker.set_high();
+        self.marker.set_low();
+    }
+
+    fn set_order(&mut self, order: ColorOrder) {
+        self.transmitter.set_order(order);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::led::{Color, LedStrip};
+    use crate::marker::Marked;
+    use crate::mock::{MockPin, Run};
+
+    #[test]
+    fn frames() {
+        let marked = Marked::new(MockPin::<256>::new(), MockPin::<16>::new());
+        let mut led_strip = LedStrip::with_transmitter(2, marked);
+
+        led_strip.each(|_| Color::Red);
+        led_strip.rest(50);
+        led_strip.each(|_| Color::Blue);
+
+        assert_eq!(led_strip.transmitter().transmitter().bytes().count(), 12);
+        assert_eq!(led_strip.transmitter().marker().runs(), [
+            Run { high: false, ticks: 1, ns: 0 },
+            Run { high: true, ticks: 1, ns: 0 },
+            Run { high: false, ticks: 1, ns: 0 },
+            Run { high: true, ticks: 1, ns: 0 },
+            Run { high: false, ticks: 1, ns: 0 },
+            Run { high: true, ticks: 1, ns: 0 },
+        ]);
+    }
+}
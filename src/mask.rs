@@ -0,0 +1,116 @@
+use core::ops::Range;
+use crate::led::Color;
+
+/// Gate deciding how much an effect may touch each LED.
+pub trait Mask {
+    /// From 0 (LED left untouched) to 255 (effect fully applied).
+    fn weight(&self, led_index: usize) -> u8;
+
+    fn apply(&self, led_index: usize, color: Color, below: Color) -> Color {
+        match self.weight(led_index) {
+            0 => below,
+            255 => color,
+            weight => {
+                let (red, green, blue) = color.to_rgb();
+
+                Color::RGBA(red, green, blue, weight).over(below)
+            },
+        }
+    }
+}
+
+/// One bit per LED, lowest bits first.
+pub struct BitMask<'a>(pub &'a [u8]);
+
+impl Mask for BitMask<'_> {
+    fn weight(&self, led_index: usize) -> u8 {
+        match self.0.get(led_index / 8) {
+            Some(byte) if byte & (1 << (led_index % 8)) != 0 => 255,
+            _ => 0,
+        }
+    }
+}
+
+/// One weight byte per LED.
+pub struct WeightMask<'a>(pub &'a [u8]);
+
+impl Mask for WeightMask<'_> {
+    fn weight(&self, led_index: usize) -> u8 {
+        self.0.get(led_index).copied().unwrap_or(0)
+    }
+}
+
+/// Every `step` LEDs starting from `offset`, e.g. even LEDs with `Stride { step: 2, offset: 0 }`.
+pub struct Stride {
+    pub step: usize,
+    pub offset: usize,
+}
+
+impl Mask for Stride {
+    fn weight(&self, led_index: usize) -> u8 {
+        match led_index >= self.offset && (led_index - self.offset).is_multiple_of(self.step) {
+            true => 255,
+            false => 0,
+        }
+    }
+}
+
+impl Mask for Range<usize> {
+    fn weight(&self, led_index: usize) -> u8 {
+        match self.contains(&led_index) {
+            true => 255,
+            false => 0,
+        }
+    }
+}
+
+/// Render `effect` where the mask allows it and `background` elsewhere.
+pub fn masked<'a>(
+    mask: &'a dyn Mask,
+    effect: &'a dyn Fn(usize) -> Color,
+    background: &'a dyn Fn(usize) -> Color,
+) -> impl Fn(usize) -> Color + 'a {
+    move |led_index| mask.apply(led_index, effect(led_index), background(led_index))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::led::Color;
+    use crate::mask::{masked, BitMask, Mask, Stride, WeightMask};
+
+    #[test]
+    fn weights() {
+        let bits = BitMask(&[0b0000_0101, 0b1000_0000]);
+        assert_eq!(bits.weight(0), 255);
+        assert_eq!(bits.weight(1), 0);
+        assert_eq!(bits.weight(2), 255);
+        assert_eq!(bits.weight(15), 255);
+        assert_eq!(bits.weight(16), 0);
+
+        let weights = WeightMask(&[0, 128, 255]);
+        assert_eq!(weights.weight(1), 128);
+        assert_eq!(weights.weight(3), 0);
+
+        let odd = Stride { step: 2, offset: 1 };
+        assert_eq!(odd.weight(0), 0);
+        assert_eq!(odd.weight(3), 255);
+
+        assert_eq!((2..4).weight(1), 0);
+        assert_eq!((2..4).weight(3), 255);
+        assert_eq!((2..4).weight(4), 0);
+    }
+
+    #[test]
+    fn apply() {
+        let even = Stride { step: 2, offset: 0 };
+        let effect = |_| Color::Red;
+        let background = |_| Color::Blue;
+        let render = masked(&even, &effect, &background);
+
+        assert_eq!(render(0).to_rgb(), (255, 0, 0));
+        assert_eq!(render(1).to_rgb(), (0, 0, 255));
+
+        let half = WeightMask(&[51]);
+        assert_eq!(half.apply(0, Color::Red, Color::Blue).to_rgb(), (51, 0, 204));
+    }
+}
@@ -0,0 +1,58 @@
+/// Scale `value` by `scale / 256`, with 255 keeping the value unchanged.
+pub fn scale8(value: u8, scale: u8) -> u8 {
+    ((u16::from(value) * (u16::from(scale) + 1)) >> 8) as u8
+}
+
+/// Interpolate from `from` (0) to `to` (255).
+pub fn lerp8(from: u8, to: u8, fraction: u8) -> u8 {
+    (i32::from(from) + (i32::from(to) - i32::from(from)) * i32::from(fraction) / 255) as u8
+}
+
+/// Round an 8.8 fixed-point value to 8 bits, carrying the fractional part
+/// lost over to the next call through `residual`.
+pub fn dither(value: u16, residual: &mut u8) -> u8 {
+    let sum = u16::from(value as u8) + u16::from(*residual);
+    *residual = sum as u8;
+
+    ((value >> 8) + (sum >> 8)).min(255) as u8
+}
+
+/// Sine of `angle` (a full turn in 256 steps) from 1 to 255 around 128,
+/// approximated with parabolas.
+pub fn sin8(angle: u8) -> u8 {
+    let t = i32::from(angle & 127);
+    let half = t * (128 - t) * 127 / 4096;
+
+    match angle < 128 {
+        true => (128 + half) as u8,
+        false => (128 - half) as u8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::{dither, scale8, sin8};
+
+    #[test]
+    fn scale() {
+        assert_eq!(scale8(200, 255), 200);
+        assert_eq!(scale8(200, 0), 0);
+        assert_eq!(scale8(200, 127), 100);
+        assert_eq!(scale8(255, 64), 64);
+    }
+
+    #[test]
+    fn temporal_dither() {
+        let mut residual = 0;
+        let frames: [u8; 4] = core::array::from_fn(|_| dither(0x0140, &mut residual));
+
+        assert_eq!(frames, [1, 1, 1, 2]);
+        assert_eq!(residual, 0);
+        assert_eq!(dither(0xFFC0, &mut 0x80), 255);
+    }
+
+    #[test]
+    fn sine() {
+        assert_eq!([0, 32, 64, 128, 192, 224].map(sin8), [128, 223, 255, 128, 1, 33]);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::led::{reset, send_byte, DataPin, Transmitter};
+
+/// Consecutive calls keeping the line at the same level.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Run {
+    pub high: bool,
+    /// Number of `set_high()`/`set_low()` calls, i.e. of pin toggle cycles.
+    pub ticks: u16,
+    /// Time explicitly waited with `delay_ns()`.
+    pub ns: u32,
+}
+
+/// Test double recording the signal sent on a pin as up to `N` runs,
+/// and decoding it back to bits, bytes and latches.
+pub struct MockPin<const N: usize> {
+    runs: [Run; N],
+    len: usize,
+    overflowed: bool,
+}
+
+impl<const N: usize> MockPin<N> {
+    pub fn new() -> MockPin<N> {
+        MockPin {
+            runs: [Run::default(); N],
+            len: 0,
+            overflowed: false,
+        }
+    }
+
+    pub fn runs(&self) -> &[Run] {
+        &self.runs[..self.len]
+    }
+
+    /// Whether more than `N` runs were sent, the extra ones being dropped.
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.overflowed = false;
+    }
+
+    /// Bits sent, a long high pulse being a 1 and a short one a 0.
+    pub fn bits(&self) -> impl Iterator<Item = bool> + '_ {
+        self.runs().iter().filter(|run| run.high).map(|run| run.ticks > 1)
+    }
+
+    /// Bytes sent, most significant bit first, in the order the strip receives them (GRB).
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        let mut bits = self.bits();
+
+        core::iter::from_fn(move || {
+            let mut byte = 0;
+
+            for _ in 0..8 {
+                byte = (byte << 1) | u8::from(bits.next()?);
+            }
+
+            Some(byte)
+        })
+    }
+
+    /// Duration of each reset the line was held low for, in nanoseconds.
+    pub fn latches(&self) -> impl Iterator<Item = u32> + '_ {
+        self.runs().iter().filter(|run| !run.high && run.ns > 0).map(|run| run.ns)
+    }
+
+    fn level(&mut self, high: bool) {
+        match self.runs[..self.len].last_mut() {
+            Some(run) if run.high == high && run.ns == 0 => run.ticks = run.ticks.saturating_add(1),
+            _ => self.push(Run { high, ticks: 1, ns: 0 }),
+        }
+    }
+
+    fn push(&mut self, run: Run) {
+        match self.runs.get_mut(self.len) {
+            Some(slot) => {
+                *slot = run;
+                self.len += 1;
+            },
+            None => self.overflowed = true,
+        }
+    }
+}
+
+impl<const N: usize> Default for MockPin<N> {
+    fn default() -> Self {
+        MockPin::new()
+    }
+}
+
+impl<const N: usize> DataPin for MockPin<N> {
+    fn set_high(&mut self) {
+        self.level(true);
+    }
+
+    fn set_low(&mut self) {
+        self.level(false);
+    }
+
+    fn delay_ns(&mut self, ns: u32) {
+        match self.runs[..self.len].last_mut() {
+            Some(run) => run.ns = run.ns.saturating_add(ns),
+            None => self.push(Run { high: false, ticks: 0, ns }),
+        }
+    }
+}
+
+impl<const N: usize> Transmitter for MockPin<N> {
+    fn send_byte(&mut self, byte: u8) {
+        send_byte(self, byte);
+    }
+
+    fn reset(&mut self, us: u32) {
+        reset(self, us);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::led::{send_bit, Color, LedStrip};
+    use crate::mock::{MockPin, Run};
+
+    #[test]
+    fn pulses() {
+        let mut pin: MockPin<4> = MockPin::new();
+
+        send_bit(&mut pin, 1);
+        send_bit(&mut pin, 0);
+
+        assert_eq!(pin.runs(), &[
+            Run { high: true, ticks: 3, ns: 0 },
+            Run { high: false, ticks: 3, ns: 0 },
+            Run { high: true, ticks: 1, ns: 0 },
+            Run { high: false, ticks: 5, ns: 0 },
+        ]);
+        assert!(!pin.overflowed());
+
+        send_bit(&mut pin, 1);
+        assert!(pin.overflowed());
+    }
+
+    #[test]
+    fn grb_frame() {
+        let mut led_strip = LedStrip::with_transmitter(2, MockPin::<128>::new());
+
+        led_strip.each(|led_index| match led_index {
+            0 => Color::Orange,
+            _ => Color::RGB(1, 2, 3),
+        });
+        led_strip.rest(50);
+
+        let pin = led_strip.transmitter();
+        let mut bytes = pin.bytes();
+
+        assert!(!pin.overflowed());
+        assert_eq!(bytes.next(), Some(127));
+        assert_eq!(bytes.next(), Some(255));
+        assert_eq!(bytes.next(), Some(0));
+        assert_eq!(bytes.next(), Some(2));
+        assert_eq!(bytes.next(), Some(1));
+        assert_eq!(bytes.next(), Some(3));
+        assert_eq!(bytes.next(), None);
+
+        let mut latches = pin.latches();
+
+        assert_eq!(latches.next(), Some(50_000));
+        assert_eq!(latches.next(), None);
+    }
+}
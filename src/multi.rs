@@ -0,0 +1,209 @@
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Several strips on their own pins driven together.
+///
+/// Strips on different pins have different types, `downgrade()` the pins
+/// first so the strips all become `LedStrip<Pin<Output, Dynamic>>`:
+/// `LedStrip::new(12, pins.d4.downgrade())`.
+pub struct MultiStrip<T: Transmitter, const N: usize> {
+    strips: [LedStrip<T>; N],
+}
+
+impl<T: Transmitter, const N: usize> MultiStrip<T, N> {
+    pub fn new(strips: [LedStrip<T>; N]) -> MultiStrip<T, N> {
+        MultiStrip {
+            strips,
+        }
+    }
+
+    pub fn strips(&self) -> &[LedStrip<T>; N] {
+        &self.strips
+    }
+
+    pub fn strips_mut(&mut self) -> &mut [LedStrip<T>; N] {
+        &mut self.strips
+    }
+
+    pub fn get_mut(&mut self, strip_index: usize) -> Option<&mut LedStrip<T>> {
+        self.strips.get_mut(strip_index)
+    }
+
+    pub fn set_brightness(&mut self, brightness: u8) {
+        for strip in &mut self.strips {
+            strip.set_brightness(brightness);
+        }
+    }
+
+    /// Send a frame to every strip, one after another, `callback` receiving
+    /// the strip index and the LED index in that strip.
+    pub fn each<F>(&mut self, callback: F) where F: Fn(usize, usize) -> Color {
+        for (strip_index, strip) in self.strips.iter_mut().enumerate() {
+            strip.each(|led_index| callback(strip_index, led_index));
+        }
+    }
+
+    /// Latch all strips: a strip latches as soon as its line stays low long
+    /// enough, which happens while the next ones are sent, so only the last
+    /// strip waits `us`.
+    pub fn rest(&mut self, us: u32) {
+        let last = N.saturating_sub(1);
+
+        for (strip_index, strip) in self.strips.iter_mut().enumerate() {
+            strip.rest(match strip_index == last {
+                true => us,
+                false => 0,
+            });
+        }
+    }
+}
+
+/// Several strips on their own pins presented as one continuous strip,
+/// LED 0 of each strip following the last LED of the previous one,
+/// e.g. a long run split to limit the voltage drop.
+pub struct ChainedStrip<T: Transmitter, const N: usize> {
+    strips: MultiStrip<T, N>,
+    next_strip: usize,
+}
+
+impl<T: Transmitter, const N: usize> ChainedStrip<T, N> {
+    pub fn new(strips: [LedStrip<T>; N]) -> ChainedStrip<T, N> {
+        ChainedStrip {
+            strips: MultiStrip::new(strips),
+            next_strip: 0,
+        }
+    }
+
+    pub fn strips(&self) -> &[LedStrip<T>; N] {
+        self.strips.strips()
+    }
+
+    pub fn strips_mut(&mut self) -> &mut [LedStrip<T>; N] {
+        self.strips.strips_mut()
+    }
+
+    /// Total length of the strips.
+    pub fn led_count(&self) -> usize {
+        self.strips().iter().map(LedStrip::led_count).sum()
+    }
+
+    /// Strip index and LED index in that strip of a logical LED index.
+    pub fn locate(&self, led_index: usize) -> Option<(usize, usize)> {
+        let mut start = 0;
+
+        for (strip_index, strip) in self.strips().iter().enumerate() {
+            if led_index < start + strip.led_count() {
+                return Some((strip_index, led_index - start));
+            }
+
+            start += strip.led_count();
+        }
+
+        None
+    }
+
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.strips.set_brightness(brightness);
+    }
+
+    /// Send a frame, `callback` receiving the logical LED index.
+    pub fn each<F>(&mut self, callback: F) where F: Fn(usize) -> Color {
+        let mut start = 0;
+
+        for strip in self.strips.strips_mut() {
+            strip.each(|led_index| callback(start + led_index));
+            start += strip.led_count();
+        }
+    }
+
+    /// Send and latch only one of the strips, the next one on the next call,
+    /// so interrupts are never blocked longer than it takes to send a single
+    /// strip, at the cost of each strip being refreshed every `N` calls.
+    ///
+    /// A single clockless line can't be refreshed that way since every frame
+    /// starts again from its first LED, split very long runs over several
+    /// pins instead.
+    pub fn each_interlaced<F>(&mut self, callback: F) where F: Fn(usize) -> Color {
+        let strip_index = self.next_strip;
+        let start: usize = self.strips()[..strip_index].iter().map(LedStrip::led_count).sum();
+
+        if let Some(strip) = self.strips.get_mut(strip_index) {
+            strip.each(|led_index| callback(start + led_index));
+            strip.latch();
+        }
+
+        self.next_strip = (strip_index + 1) % N.max(1);
+    }
+
+    pub fn rest(&mut self, us: u32) {
+        self.strips.rest(us);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::led::{Color, LedStrip};
+    use crate::mock::MockPin;
+    use crate::multi::{ChainedStrip, MultiStrip};
+
+    #[test]
+    fn jointly() {
+        let mut strips = MultiStrip::new([
+            LedStrip::with_transmitter(1, MockPin::<128>::new()),
+            LedStrip::with_transmitter(2, MockPin::<128>::new()),
+        ]);
+
+        strips.each(|strip_index, led_index| match (strip_index, led_index) {
+            (0, _) => Color::Red,
+            (_, 0) => Color::Green,
+            _ => Color::Blue,
+        });
+        strips.rest(300);
+
+        let [first, second] = strips.strips();
+
+        assert!(first.transmitter().bytes().eq([0, 255, 0]));
+        assert!(second.transmitter().bytes().eq([255, 0, 0, 0, 0, 255]));
+        assert_eq!(first.transmitter().latches().count(), 0);
+        assert!(second.transmitter().latches().eq([300_000]));
+        assert_eq!(first.status().frame, 1);
+    }
+
+    #[test]
+    fn chained() {
+        let mut chain = ChainedStrip::new([
+            LedStrip::with_transmitter(2, MockPin::<128>::new()),
+            LedStrip::with_transmitter(1, MockPin::<128>::new()),
+        ]);
+
+        assert_eq!(chain.led_count(), 3);
+        assert_eq!(chain.locate(1), Some((0, 1)));
+        assert_eq!(chain.locate(2), Some((1, 0)));
+        assert_eq!(chain.locate(3), None);
+
+        chain.each(|led_index| Color::RGB(led_index as u8, 0, 0));
+        chain.rest(300);
+
+        let [first, second] = chain.strips();
+
+        assert!(first.transmitter().bytes().eq([0, 0, 0, 0, 1, 0]));
+        assert!(second.transmitter().bytes().eq([0, 2, 0]));
+    }
+
+    #[test]
+    fn interlaced() {
+        let mut chain = ChainedStrip::new([
+            LedStrip::with_transmitter(1, MockPin::<128>::new()),
+            LedStrip::with_transmitter(1, MockPin::<128>::new()),
+        ]);
+
+        chain.each_interlaced(|led_index| Color::RGB(0, 0, led_index as u8 + 1));
+        chain.each_interlaced(|led_index| Color::RGB(0, 0, led_index as u8 + 1));
+        chain.each_interlaced(|led_index| Color::RGB(0, 0, led_index as u8 + 3));
+
+        let [first, second] = chain.strips();
+
+        assert!(first.transmitter().bytes().eq([0, 0, 1, 0, 0, 3]));
+        assert!(second.transmitter().bytes().eq([0, 0, 2]));
+        assert_eq!(first.transmitter().latches().count(), 2);
+    }
+}
@@ -0,0 +1,93 @@
+/// Pseudo-random byte for a lattice point, computed instead of being looked
+/// up in a permutation table to keep the RAM free.
+fn hash(x: u8, y: u8) -> u8 {
+    let mut hash = u16::from(x) << 8 | u16::from(y);
+    hash ^= hash >> 7;
+    hash = hash.wrapping_mul(0x9E3B);
+    hash ^= hash >> 9;
+    hash = hash.wrapping_mul(0x2C1B);
+    hash ^= hash >> 8;
+
+    (hash >> 8) as u8
+}
+
+/// Smoothstep of `t` from 0 to 256, so the noise has no visible creases.
+fn fade(t: i32) -> i32 {
+    (t * t * (768 - 2 * t)) >> 16
+}
+
+fn lerp(from: i32, to: i32, fraction: i32) -> i32 {
+    from + (((to - from) * fraction) >> 8)
+}
+
+fn gradient(hash: u8, dx: i32) -> i32 {
+    (i32::from(hash as i8) * dx) >> 7
+}
+
+fn gradient_2d(hash: u8, dx: i32, dy: i32) -> i32 {
+    match hash & 7 {
+        0 => dx + dy,
+        1 => dy - dx,
+        2 => dx - dy,
+        3 => -dx - dy,
+        4 => dx,
+        5 => -dx,
+        6 => dy,
+        _ => -dy,
+    }
+}
+
+/// Smoothly varying value around 128 (gradient noise, as Perlin's), for
+/// plasma, lava or cloud like effects.
+///
+/// `x` is 8.8 fixed-point: the value goes through a new random slope every
+/// 256 steps, where it's 128, so moving by a few units per frame gives slow
+/// changes and by hundreds fast ones.
+pub fn inoise8(x: u16) -> u8 {
+    let cell = (x >> 8) as u8;
+    let fraction = i32::from(x & 0xFF);
+    let from = gradient(hash(cell, 0), fraction);
+    let to = gradient(hash(cell.wrapping_add(1), 0), fraction - 256);
+
+    (128 + lerp(from, to, fade(fraction))).clamp(0, 255) as u8
+}
+
+/// Two dimensional noise, e.g. with `x` the LED position and `y` the time.
+pub fn inoise8_2d(x: u16, y: u16) -> u8 {
+    let (cell_x, cell_y) = ((x >> 8) as u8, (y >> 8) as u8);
+    let (next_x, next_y) = (cell_x.wrapping_add(1), cell_y.wrapping_add(1));
+    let (dx, dy) = (i32::from(x & 0xFF), i32::from(y & 0xFF));
+    let (u, v) = (fade(dx), fade(dy));
+
+    let top = lerp(
+        gradient_2d(hash(cell_x, cell_y), dx, dy),
+        gradient_2d(hash(next_x, cell_y), dx - 256, dy),
+        u,
+    );
+    let bottom = lerp(
+        gradient_2d(hash(cell_x, next_y), dx, dy - 256),
+        gradient_2d(hash(next_x, next_y), dx - 256, dy - 256),
+        u,
+    );
+
+    (128 + ((lerp(top, bottom, v) * 3) >> 2)).clamp(0, 255) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::noise::{inoise8, inoise8_2d};
+
+    #[test]
+    fn smooth() {
+        assert_eq!([0, 256, 100, 1000, 5000].map(inoise8), [128, 128, 147, 139, 112]);
+        assert_eq!(inoise8_2d(0, 0), 128);
+        assert_eq!(inoise8_2d(300, 700), 153);
+        assert_eq!(inoise8_2d(1234, 4321), 137);
+
+        let steps = (0..u16::MAX).map(|x| inoise8(x).abs_diff(inoise8(x + 1)));
+        assert_eq!(steps.max(), Some(2));
+
+        let values = (0..u16::MAX).step_by(7).map(inoise8);
+        assert_eq!((values.clone().min(), values.max()), (Some(9), Some(248)));
+    }
+}
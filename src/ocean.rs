@@ -0,0 +1,133 @@
+use crate::gradient::palette_at;
+use crate::layer::BlendMode;
+use crate::led::{Color, LedStrip, Transmitter};
+use crate::math::{lerp8, sin8};
+
+const DEEP: [Color; 4] = [
+    Color::RGB(0, 5, 7),
+    Color::RGB(0, 3, 13),
+    Color::RGB(0, 1, 23),
+    Color::RGB(0, 0, 70),
+];
+
+const SHALLOW: [Color; 4] = [
+    Color::RGB(0, 2, 16),
+    Color::RGB(0, 0, 49),
+    Color::RGB(20, 85, 75),
+    Color::RGB(40, 170, 80),
+];
+
+const FOAM: [Color; 4] = [
+    Color::RGB(0, 5, 7),
+    Color::RGB(0, 0, 28),
+    Color::RGB(16, 31, 70),
+    Color::RGB(25, 85, 100),
+];
+
+/// Layer of the ocean: a palette scrolled by a sine along the strip, its
+/// brightness swelling slowly.
+#[derive(Copy, Clone)]
+struct Wave {
+    palette: &'static [Color],
+    /// Phase added per LED and per frame, in 1/256 of turn.
+    wavelength: u16,
+    speed: u16,
+    swell: u16,
+}
+
+impl Wave {
+    fn color(&self, frame: u32, led_index: usize) -> Color {
+        let phase = (led_index as u32 * u32::from(self.wavelength)).wrapping_add(frame.wrapping_mul(u32::from(self.speed))) >> 8;
+        let swell = (frame.wrapping_mul(u32::from(self.swell)) >> 8) as u8;
+        let level = sin8(swell.wrapping_add((led_index as u8).wrapping_mul(3)));
+
+        palette_at(self.palette, sin8(phase as u8)) * lerp8(96, 255, level)
+    }
+}
+
+/// Gently moving blue-green waves, several scrolling palettes summed with
+/// white crests where they add up, inspired by FastLED's Pacifica.
+pub struct Ocean {
+    waves: [Wave; 3],
+    background: Color,
+    whitecaps: Option<u8>,
+}
+
+impl Ocean {
+    pub fn new() -> Ocean {
+        Ocean {
+            waves: [
+                Wave { palette: &DEEP, wavelength: 2_600, speed: 700, swell: 300 },
+                Wave { palette: &SHALLOW, wavelength: 3_400, speed: 500, swell: 220 },
+                Wave { palette: &FOAM, wavelength: 1_800, speed: 430, swell: 170 },
+            ],
+            background: Color::RGB(2, 6, 10),
+            whitecaps: Some(140),
+        }
+    }
+
+    /// Level of the blue/green channels above which waves turn white,
+    /// `None` for no whitecaps.
+    pub fn whitecaps(mut self, threshold: Option<u8>) -> Ocean {
+        self.whitecaps = threshold;
+
+        self
+    }
+
+    pub fn color(&self, frame: u32, led_index: usize) -> Color {
+        let water = self.waves.iter().fold(self.background, |below, wave| {
+            BlendMode::Add.blend(wave.color(frame, led_index), below)
+        });
+        let (_, green, blue) = water.to_rgb();
+
+        match self.whitecaps {
+            Some(threshold) if green.max(blue) > threshold => {
+                let crest = (green.max(blue) - threshold).saturating_mul(2);
+
+                water + Color::RGB(crest, crest, crest)
+            },
+            _ => water,
+        }
+    }
+
+    pub fn show<T: Transmitter>(&self, led_strip: &mut LedStrip<T>, frame: u32) {
+        led_strip.each(|led_index| self.color(frame, led_index));
+    }
+}
+
+impl Default for Ocean {
+    fn default() -> Ocean {
+        Ocean::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::led::Color;
+    use crate::gradient::palette_at;
+    use crate::ocean::Ocean;
+
+    #[test]
+    fn palette() {
+        let palette = [Color::RGB(0, 0, 0), Color::RGB(0, 0, 200), Color::RGB(200, 0, 200)];
+
+        assert_eq!(palette_at(&palette, 0), Color::RGB(0, 0, 0));
+        assert_eq!(palette_at(&palette, 255), Color::RGB(200, 0, 200));
+        assert_eq!(palette_at(&palette, 64), Color::RGB(0, 0, 100));
+        assert_eq!(palette_at(&[], 10), Color::Black);
+    }
+
+    #[test]
+    fn waves() {
+        let ocean = Ocean::new();
+        let colors = (0..200).flat_map(|frame| (0..30).map(move |led_index| (frame, led_index)))
+            .map(|(frame, led_index)| ocean.color(frame, led_index).to_rgb());
+
+        assert!(colors.clone().all(|(red, green, blue)| blue >= 10 && red <= green.max(blue)));
+        assert!(colors.clone().any(|(red, _, _)| red > 100));
+        assert_ne!(ocean.color(0, 3), ocean.color(50, 3));
+
+        let calm = Ocean::new().whitecaps(None);
+        assert!((0..200).all(|frame| calm.color(frame, 0).to_rgb().0 <= 85));
+    }
+}
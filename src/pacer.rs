@@ -0,0 +1,120 @@
+/// Frame pacing on a millisecond counter (e.g. incremented by a timer0
+/// compare interrupt), so animations run at a stable rate whatever the
+/// strip length and the time spent in the loop.
+///
+/// ```ignore
+/// let mut pacer = Pacer::fps(50);
+///
+/// loop {
+///     if pacer.ready(millis()) {
+///         scheduler.show(&mut led_strip);
+///         led_strip.rest(300);
+///     }
+/// }
+/// ```
+pub struct Pacer {
+    interval_ms: u32,
+    next_ms: Option<u32>,
+    frame: u32,
+    late_frames: u32,
+    dropped_frames: u32,
+}
+
+impl Pacer {
+    pub fn every_n_millis(interval_ms: u32) -> Pacer {
+        Pacer {
+            interval_ms: interval_ms.max(1),
+            next_ms: None,
+            frame: 0,
+            late_frames: 0,
+            dropped_frames: 0,
+        }
+    }
+
+    pub fn fps(fps: u16) -> Pacer {
+        Pacer::every_n_millis(1000 / u32::from(fps.max(1)))
+    }
+
+    pub fn interval(&self) -> u32 {
+        self.interval_ms
+    }
+
+    pub fn set_interval(&mut self, interval_ms: u32) {
+        self.interval_ms = interval_ms.max(1);
+    }
+
+    /// Frames started so far.
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Frames started after they were due.
+    pub fn late_frames(&self) -> u32 {
+        self.late_frames
+    }
+
+    /// Frames skipped because the previous ones were more than an interval late.
+    pub fn dropped_frames(&self) -> u32 {
+        self.dropped_frames
+    }
+
+    /// Whether a new frame is due at `now_ms`, the first call always is.
+    ///
+    /// Frames keep a regular rhythm when a frame is slightly late, but when
+    /// more than a whole interval late, the missed ones are skipped rather
+    /// than sent in a burst.
+    pub fn ready(&mut self, now_ms: u32) -> bool {
+        let next_ms = self.next_ms.unwrap_or(now_ms);
+        let late = now_ms.wrapping_sub(next_ms);
+
+        // Not reached yet (the difference wrapped around).
+        if late > u32::MAX / 2 {
+            return false;
+        }
+
+        if late > 0 {
+            self.late_frames = self.late_frames.wrapping_add(1);
+        }
+
+        if late >= self.interval_ms {
+            self.dropped_frames = self.dropped_frames.wrapping_add(late / self.interval_ms);
+        }
+
+        self.next_ms = Some(match late >= self.interval_ms {
+            true => now_ms.wrapping_add(self.interval_ms),
+            false => next_ms.wrapping_add(self.interval_ms),
+        });
+        self.frame = self.frame.wrapping_add(1);
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pacer::Pacer;
+
+    #[test]
+    fn pacing() {
+        let mut pacer = Pacer::fps(50);
+        assert_eq!(pacer.interval(), 20);
+
+        let frames = (100..200).filter(|&now| pacer.ready(now)).count();
+        assert_eq!(frames, 5);
+
+        assert!(!pacer.ready(199));
+        assert!(pacer.ready(205));
+        assert!(pacer.ready(240));
+        assert!(pacer.ready(300));
+        assert!(!pacer.ready(319));
+        assert!(pacer.ready(320));
+        assert_eq!(pacer.frame(), 9);
+        assert_eq!(pacer.late_frames(), 3);
+        assert_eq!(pacer.dropped_frames(), 3);
+
+        let mut pacer = Pacer::every_n_millis(10);
+        assert!(pacer.ready(u32::MAX - 5));
+        assert!(!pacer.ready(2));
+        assert!(pacer.ready(4));
+    }
+}
@@ -0,0 +1,130 @@
+use heapless::Vec;
+#[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+use crate::interrupt::without_interrupts;
+use crate::led::Transmitter;
+
+/// Up to 8 strips driven at once from the pins of one port, the strip
+/// (lane) `n` on the bit `n`, so a frame takes the time of a single strip:
+///
+/// ```ignore
+/// // Lanes on PD2..PD5, 4 strips of 60 LEDs.
+/// let port = |levels: u8| dp.PORTD.portd.write(|w| unsafe { w.bits(levels << 2) });
+/// let mut led_strip = LedStrip::with_transmitter(4 * 60, Parallel::<_, 720>::new(port, 4, delay_us));
+/// ```
+///
+/// The strip is seen as the lanes one after the other, the bytes of a
+/// frame (up to `BYTES`, the following ones being dropped) being kept until
+/// the latch, where they are sent interleaved by `write`, which should only
+/// set the levels of the port.
+///
+/// As the frame is bit-banged at the latch, out of the pixels `LedStrip`
+/// masks with `InterruptMasking::PerPixel`, the interrupts are masked here
+/// while each byte of the lanes is sent (10 µs), whatever the masking of the
+/// strip. On AVR without the `asm_experimental_arch` feature, the
+/// application must mask them around the latch.
+pub struct Parallel<W: FnMut(u8), const BYTES: usize> {
+    write: W,
+    lanes: u8,
+    delay_us: fn(u32),
+    bytes: Vec<u8, BYTES>,
+}
+
+impl<W: FnMut(u8), const BYTES: usize> Parallel<W, BYTES> {
+    pub fn new(write: W, lanes: u8, delay_us: fn(u32)) -> Parallel<W, BYTES> {
+        Parallel {
+            write,
+            lanes: lanes.clamp(1, 8),
+            delay_us,
+            bytes: Vec::new(),
+        }
+    }
+
+    pub fn lanes(&self) -> u8 {
+        self.lanes
+    }
+
+    #[cfg(not(feature = "trinket"))]
+    fn send_bits(&mut self, all: u8, ones: u8) {
+        (self.write)(all);
+        (self.write)(ones);
+        (self.write)(ones);
+        (self.write)(0);
+        (self.write)(0);
+        (self.write)(0);
+    }
+
+    #[cfg(feature = "trinket")]
+    fn send_bits(&mut self, all: u8, ones: u8) {
+        (self.write)(all);
+        (self.write)(ones);
+        (self.write)(0);
+    }
+
+    #[cfg(any(not(target_arch = "avr"), feature = "asm_experimental_arch"))]
+    fn send_position(&mut self, all: u8, ones: [u8; 8]) {
+        without_interrupts(|| {
+            for ones in ones {
+                self.send_bits(all, ones);
+            }
+        });
+    }
+
+    #[cfg(all(target_arch = "avr", not(feature = "asm_experimental_arch")))]
+    fn send_position(&mut self, all: u8, ones: [u8; 8]) {
+        for ones in ones {
+            self.send_bits(all, ones);
+        }
+    }
+}
+
+impl<W: FnMut(u8), const BYTES: usize> Transmitter for Parallel<W, BYTES> {
+    fn send_byte(&mut self, byte: u8) {
+        self.bytes.push(byte).ok();
+    }
+
+    fn reset(&mut self, us: u32) {
+        let lanes = usize::from(self.lanes);
+        let lane_len = self.bytes.len().div_ceil(lanes);
+        let all = ((1u16 << lanes) - 1) as u8;
+        let bytes = core::mem::take(&mut self.bytes);
+
+        for position in 0..lane_len {
+            // Bits of every lane computed first, so only the port writes are timed.
+            let ones: [u8; 8] = core::array::from_fn(|index| {
+                let bit = 7 - index;
+
+                (0..lanes)
+                    .filter(|&lane| bytes.get(lane * lane_len + position).is_some_and(|byte| byte >> bit & 1 == 1))
+                    .fold(0, |ones, lane| ones | 1 << lane)
+            });
+
+            self.send_position(all, ones);
+        }
+
+        (self.write)(0);
+        (self.delay_us)(us);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use heapless::Vec;
+    use crate::led::{Color, LedStrip};
+    use crate::parallel::Parallel;
+
+    #[test]
+    fn lanes() {
+        let mut levels: Vec<u8, 160> = Vec::new();
+        let port = Parallel::<_, 6>::new(|byte| { levels.push(byte).ok(); }, 2, |_| {});
+        let mut led_strip = LedStrip::with_transmitter(2, port);
+
+        led_strip.each(|led_index| [Color::Red, Color::Blue][led_index]);
+        led_strip.rest(50);
+        drop(led_strip);
+
+        assert_eq!(levels.len(), 3 * 8 * 6 + 1);
+        assert_eq!(levels[..6], [3, 0, 0, 0, 0, 0]);
+        assert_eq!(levels[48..54], [3, 1, 1, 0, 0, 0]);
+        assert_eq!(levels[96..102], [3, 2, 2, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::coordinate::CoordinateMap;
+use crate::gradient::Gradient;
+use crate::led::Color;
+use crate::mask::Mask;
+
+/// Render an `effect` of `(angle, radius)` turned by `angle` (a full turn in
+/// 256 steps), so it spins the same on rings of any size.
+pub fn rotate<'a>(
+    map: &'a dyn CoordinateMap,
+    angle: u8,
+    effect: &'a dyn Fn(u8, u8) -> Color,
+) -> impl Fn(usize) -> Color + 'a {
+    move |led_index| match map.polar(led_index) {
+        Some((led_angle, radius)) => effect(led_angle.wrapping_sub(angle), radius),
+        None => Color::Black,
+    }
+}
+
+/// Gradient from the center (position 0) to the edge (255).
+pub fn radial<'a, const N: usize>(map: &'a dyn CoordinateMap, gradient: &'a Gradient<N>) -> impl Fn(usize) -> Color + 'a {
+    move |led_index| map.polar(led_index).map_or(Color::Black, |(_, radius)| gradient.sample(radius))
+}
+
+/// Gradient around the circle, its position 0 at `angle`.
+pub fn conic<'a, const N: usize>(
+    map: &'a dyn CoordinateMap,
+    angle: u8,
+    gradient: &'a Gradient<N>,
+) -> impl Fn(usize) -> Color + 'a {
+    move |led_index| map.polar(led_index).map_or(Color::Black, |(led_angle, _)| gradient.sample(led_angle.wrapping_sub(angle)))
+}
+
+/// LEDs whose angle is from `from` to `to` included in the wiring direction,
+/// wrapping around, e.g. to fill a gauge with `masked()`.
+pub struct Arc<'a> {
+    pub map: &'a dyn CoordinateMap,
+    pub from: u8,
+    pub to: u8,
+}
+
+impl Arc<'_> {
+    pub fn contains(&self, angle: u8) -> bool {
+        angle.wrapping_sub(self.from) <= self.to.wrapping_sub(self.from)
+    }
+}
+
+impl Mask for Arc<'_> {
+    fn weight(&self, led_index: usize) -> u8 {
+        match self.map.polar(led_index) {
+            Some((angle, _)) if self.contains(angle) => 255,
+            _ => 0,
+        }
+    }
+}
+
+/// Render `color` on the arc from `from` to `to`, black elsewhere.
+pub fn arc(map: &dyn CoordinateMap, from: u8, to: u8, color: Color) -> impl Fn(usize) -> Color + '_ {
+    let arc = Arc { map, from, to };
+
+    move |led_index| match arc.weight(led_index) {
+        0 => Color::Black,
+        _ => color,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coordinate::Rings;
+    use crate::gradient::Gradient;
+    use crate::led::Color;
+    use crate::polar::{arc, conic, radial, rotate};
+
+    #[test]
+    fn rotation() {
+        let rings = Rings(&[8, 4]);
+        let needle = |angle, _| match angle {
+            0 => Color::White,
+            _ => Color::Black,
+        };
+        let rotated = rotate(&rings, 64, &needle);
+
+        assert_eq!([0, 2, 9].map(&rotated), [Color::Black, Color::White, Color::White]);
+        assert_eq!(rotated(12), Color::Black);
+    }
+
+    #[test]
+    fn gradients() {
+        let rings = Rings(&[4, 1]);
+        let gradient = Gradient::even([Color::Red, Color::Blue]);
+
+        assert_eq!(radial(&rings, &gradient)(4).to_rgb(), (255, 0, 0));
+        assert_eq!(radial(&rings, &gradient)(0).to_rgb(), (0, 0, 255));
+        assert_eq!(conic(&rings, 128, &gradient)(2).to_rgb(), (255, 0, 0));
+        assert_eq!(conic(&rings, 128, &gradient)(3).to_rgb(), (191, 0, 64));
+    }
+
+    #[test]
+    fn arcs() {
+        let rings = Rings(&[8]);
+        let gauge = arc(&rings, 224, 64, Color::Green);
+
+        assert_eq!((0..8).map(gauge).filter(|&color| color == Color::Green).count(), 4);
+        assert_eq!(arc(&rings, 32, 32, Color::Red)(1), Color::Red);
+        assert_eq!(arc(&rings, 32, 32, Color::Red)(2), Color::Black);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::math::scale8;
+use crate::transform::{ChannelScale, PixelTransform};
+
+/// Typical WS2812B current for a fully lit channel and for an unlit LED, in mA.
+const RED_MILLIAMPS: u32 = 16;
+const GREEN_MILLIAMPS: u32 = 11;
+const BLUE_MILLIAMPS: u32 = 15;
+const IDLE_MILLIAMPS: u32 = 1;
+
+/// Current drawn by the channels of one LED, in 1/255 mA (idle current excluded).
+pub fn channel_draw(red: u8, green: u8, blue: u8) -> u32 {
+    u32::from(red) * RED_MILLIAMPS + u32::from(green) * GREEN_MILLIAMPS + u32::from(blue) * BLUE_MILLIAMPS
+}
+
+/// Estimated current of a frame of `led_count` LEDs with the summed `channel_draw()`.
+pub fn frame_milliamps(led_count: usize, draw: u32) -> u32 {
+    led_count as u32 * IDLE_MILLIAMPS + draw / 255
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PowerLimit {
+    millivolts: u16,
+    milliamps: u16,
+}
+
+impl PowerLimit {
+    pub fn new(millivolts: u16, milliamps: u16) -> PowerLimit {
+        PowerLimit {
+            millivolts,
+            milliamps,
+        }
+    }
+
+    pub fn millivolts(&self) -> u16 {
+        self.millivolts
+    }
+
+    pub fn milliamps(&self) -> u16 {
+        self.milliamps
+    }
+
+    pub fn budget_milliwatts(&self) -> u32 {
+        u32::from(self.millivolts) * u32::from(self.milliamps) / 1_000
+    }
+
+    /// Scale (255 = unchanged) to apply to the channels of a frame so it fits the budget.
+    pub fn scale(&self, led_count: usize, draw: u32) -> u8 {
+        let idle = led_count as u32 * IDLE_MILLIAMPS * 255;
+        let budget = u32::from(self.milliamps) * 255;
+
+        if idle + draw <= budget {
+            return 255;
+        }
+
+        // The idle current alone is over the budget, nothing left to light.
+        if draw == 0 || idle >= budget {
+            return 0;
+        }
+
+        (u64::from(budget.saturating_sub(idle)) * 255 / u64::from(draw)) as u8
+    }
+}
+
+/// Brightness derating from a temperature measured near the LEDs (e.g. a
+/// thermistor read with the ADC), in tenths of a degree Celsius: unchanged
+/// up to `threshold`, then decreasing linearly down to off at `shutdown`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ThermalLimit {
+    threshold: i16,
+    shutdown: i16,
+}
+
+impl ThermalLimit {
+    pub fn new(threshold: i16, shutdown: i16) -> ThermalLimit {
+        ThermalLimit {
+            threshold,
+            shutdown: shutdown.max(threshold),
+        }
+    }
+
+    pub fn threshold(&self) -> i16 {
+        self.threshold
+    }
+
+    pub fn shutdown(&self) -> i16 {
+        self.shutdown
+    }
+
+    /// Scale (255 = unchanged) to apply to the brightness at `temperature`.
+    pub fn scale(&self, temperature: i16) -> u8 {
+        if temperature <= self.threshold {
+            return 255;
+        }
+
+        if temperature >= self.shutdown {
+            return 0;
+        }
+
+        let above = i32::from(temperature) - i32::from(self.threshold);
+        let range = i32::from(self.shutdown) - i32::from(self.threshold);
+
+        (255 - above * 255 / range) as u8
+    }
+}
+
+/// Where the power is injected into the strip, the LEDs the farthest from
+/// it getting the most voltage drop.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FeedPoint {
+    Start,
+    End,
+    /// Both ends, the middle being the farthest.
+    Both,
+}
+
+/// Compensation of the voltage drop along a long strip, which gets dimmer
+/// and redder far from the power injection: the nearest LEDs are dimmed
+/// by `gain` (per channel, 255 = off) decreasing to none for the farthest,
+/// so all the strip looks the same.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VoltageDrop {
+    led_count: usize,
+    feed: FeedPoint,
+    gain: (u8, u8, u8),
+}
+
+impl VoltageDrop {
+    /// Strip of `led_count` LEDs powered from its start, e.g. with a
+    /// `gain` of `(0, 24, 40)` as blue and green suffer the most.
+    pub fn new(led_count: usize, gain: (u8, u8, u8)) -> VoltageDrop {
+        VoltageDrop {
+            led_count,
+            feed: FeedPoint::Start,
+            gain,
+        }
+    }
+
+    pub fn with_feed(mut self, feed: FeedPoint) -> VoltageDrop {
+        self.feed = feed;
+
+        self
+    }
+
+    /// Distance of the LED from the feed, from 0 (nearest) to 255 (farthest).
+    pub fn distance(&self, led_index: usize) -> u8 {
+        let last = self.led_count.saturating_sub(1).max(1);
+        let led_index = led_index.min(last);
+        let distance = match self.feed {
+            FeedPoint::Start => led_index * 255 / last,
+            FeedPoint::End => (last - led_index) * 255 / last,
+            FeedPoint::Both => led_index.min(last - led_index) * 510 / last,
+        };
+
+        distance.min(255) as u8
+    }
+
+    /// Per-channel scale (255 = unchanged) of the LED at `led_index`.
+    pub fn scale(&self, led_index: usize) -> (u8, u8, u8) {
+        let closeness = 255 - self.distance(led_index);
+        let channel = |gain: u8| 255 - scale8(gain, closeness);
+
+        (channel(self.gain.0), channel(self.gain.1), channel(self.gain.2))
+    }
+}
+
+impl PixelTransform for VoltageDrop {
+    fn transform(&self, led_index: usize, channels: [u16; 3]) -> [u16; 3] {
+        let (red, green, blue) = self.scale(led_index);
+
+        ChannelScale(red, green, blue).transform(led_index, channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::power::{channel_draw, frame_milliamps, FeedPoint, PowerLimit, ThermalLimit, VoltageDrop};
+
+    #[test]
+    fn estimate() {
+        assert_eq!(frame_milliamps(10, 10 * channel_draw(255, 255, 255)), 430);
+        assert_eq!(frame_milliamps(10, 0), 10);
+    }
+
+    #[test]
+    fn scale() {
+        let limit = PowerLimit::new(5_000, 500);
+        let white = 10 * channel_draw(255, 255, 255);
+
+        assert_eq!(limit.budget_milliwatts(), 2_500);
+        assert_eq!(limit.scale(10, white), 255);
+        assert_eq!(limit.scale(20, 2 * white), 145);
+        assert_eq!(PowerLimit::new(5_000, 5).scale(10, white), 0);
+        assert_eq!(PowerLimit::new(5_000, 100).scale(150, 0), 0);
+        assert_eq!(PowerLimit::new(5_000, 100).scale(150, white), 0);
+    }
+
+    #[test]
+    fn thermal() {
+        let limit = ThermalLimit::new(450, 650);
+
+        assert_eq!(limit.scale(-100), 255);
+        assert_eq!(limit.scale(450), 255);
+        assert_eq!(limit.scale(550), 128);
+        assert_eq!(limit.scale(700), 0);
+        assert_eq!(ThermalLimit::new(450, 400).scale(451), 0);
+    }
+
+    #[test]
+    fn voltage_drop() {
+        let drop = VoltageDrop::new(11, (0, 40, 80));
+
+        assert_eq!(drop.scale(0), (255, 215, 175));
+        assert_eq!(drop.scale(5), (255, 235, 215));
+        assert_eq!(drop.scale(10), (255, 255, 255));
+        assert_eq!(drop.with_feed(FeedPoint::End).scale(10), (255, 215, 175));
+        assert_eq!(drop.with_feed(FeedPoint::Both).distance(5), 255);
+        assert_eq!(drop.with_feed(FeedPoint::Both).distance(10), 0);
+    }
+}
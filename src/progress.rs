@@ -0,0 +1,148 @@
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Bar filling the `len` LEDs from `start` as its value goes from 0 to 255.
+pub struct ProgressBar {
+    start: usize,
+    len: usize,
+    from: Color,
+    to: Color,
+    gradient: bool,
+    smooth: bool,
+    background: Color,
+    value: u8,
+}
+
+impl ProgressBar {
+    /// Bar from red (empty) to green (full).
+    pub fn new(start: usize, len: usize) -> ProgressBar {
+        ProgressBar {
+            start,
+            len,
+            from: Color::Red,
+            to: Color::Green,
+            gradient: false,
+            smooth: true,
+            background: Color::Black,
+            value: 0,
+        }
+    }
+
+    /// Color of the bar when nearly empty and when full; with a gradient,
+    /// colors of the first and last LED instead.
+    pub fn colors(mut self, from: Color, to: Color) -> ProgressBar {
+        self.from = from;
+        self.to = to;
+
+        self
+    }
+
+    pub fn gradient(mut self, gradient: bool) -> ProgressBar {
+        self.gradient = gradient;
+
+        self
+    }
+
+    /// Dim the leading LED according to how much of it is filled, instead of
+    /// lighting it once half filled.
+    pub fn smooth(mut self, smooth: bool) -> ProgressBar {
+        self.smooth = smooth;
+
+        self
+    }
+
+    /// Color of the unfilled LEDs of the segment.
+    pub fn background(mut self, background: Color) -> ProgressBar {
+        self.background = background;
+
+        self
+    }
+
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+
+    pub fn set(&mut self, value: u8) {
+        self.value = value;
+    }
+
+    /// Set the value from a percentage, above 100 is full.
+    pub fn set_percent(&mut self, percent: u8) {
+        self.value = (u16::from(percent.min(100)) * 255 / 100) as u8;
+    }
+
+    pub fn contains(&self, led_index: usize) -> bool {
+        (self.start..self.start + self.len).contains(&led_index)
+    }
+
+    /// Filled length in 1/256 of LEDs.
+    fn filled(&self) -> u32 {
+        u32::from(self.value) * self.len as u32 * 256 / 255
+    }
+
+    pub fn color(&self, led_index: usize) -> Color {
+        if !self.contains(led_index) {
+            return Color::Black;
+        }
+
+        let position = led_index - self.start;
+        let fill = self.filled().saturating_sub(position as u32 * 256).min(256);
+        let color = match self.gradient {
+            true => self.from.lerp(self.to, (position * 255 / self.len.saturating_sub(1).max(1)) as u8),
+            false => self.from.lerp(self.to, self.value),
+        };
+
+        match (fill, self.smooth) {
+            (256, _) => color,
+            (0, _) => self.background,
+            (fill, true) => color.lerp(self.background, (255 - fill) as u8),
+            (fill, false) if fill >= 128 => color,
+            _ => self.background,
+        }
+    }
+
+    pub fn show<T: Transmitter>(&self, led_strip: &mut LedStrip<T>) {
+        led_strip.each(|led_index| self.color(led_index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::led::Color;
+    use crate::progress::ProgressBar;
+
+    #[test]
+    fn fill() {
+        let mut bar = ProgressBar::new(2, 4).colors(Color::Blue, Color::Blue);
+
+        bar.set_percent(50);
+        assert_eq!(bar.value(), 127);
+        assert_eq!(bar.color(1), Color::Black);
+        assert_eq!(bar.color(2), Color::RGB(0, 0, 255));
+        assert_eq!(bar.color(3), Color::RGB(0, 0, 253));
+        assert_eq!(bar.color(4), Color::Black);
+
+        bar.set(160);
+        assert_eq!(bar.color(4), Color::RGB(0, 0, 130));
+        assert_eq!(bar.color(5), Color::Black);
+
+        let bar = ProgressBar::new(2, 4).colors(Color::Blue, Color::Blue).smooth(false);
+        let mut bar = bar.background(Color::RGB(0, 8, 0));
+        bar.set(160);
+        assert_eq!(bar.color(4), Color::RGB(0, 0, 255));
+        bar.set(140);
+        assert_eq!(bar.color(4), Color::RGB(0, 8, 0));
+    }
+
+    #[test]
+    fn colors() {
+        let mut bar = ProgressBar::new(0, 3);
+        bar.set(255);
+        assert_eq!(bar.color(0), Color::RGB(0, 255, 0));
+
+        let mut bar = ProgressBar::new(0, 3).gradient(true);
+        bar.set(255);
+        assert_eq!(bar.color(0), Color::RGB(255, 0, 0));
+        assert_eq!(bar.color(1), Color::RGB(128, 127, 0));
+        assert_eq!(bar.color(2), Color::RGB(0, 255, 0));
+    }
+}
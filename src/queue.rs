@@ -0,0 +1,119 @@
+use core::cell::UnsafeCell;
+use heapless::Deque;
+use crate::interrupt::without_interrupts;
+
+/// Fixed capacity queue shared between interrupts and the main loop, meant
+/// to be a `static` so ISRs (serial, IR, buttons) can post commands, for
+/// instance `ir::Action`, that the animation loop takes and applies:
+///
+/// ```ignore
+/// static ACTIONS: Queue<Action, 8> = Queue::new();
+///
+/// #[avr_device::interrupt(atmega328p)]
+/// fn PCINT2() {
+///     if let Some(action) = nec.falling_edge(micros()).and_then(remote_action) {
+///         ACTIONS.post(action).ok();
+///     }
+/// }
+///
+/// loop {
+///     while let Some(action) = ACTIONS.take() {
+///         apply(action, &mut scheduler, &mut led_strip);
+///     }
+///
+///     scheduler.show(&mut led_strip);
+/// }
+/// ```
+///
+/// Interrupts are masked for the few cycles each access takes, so `post()`
+/// and `take()` can be called from anywhere without `static mut`.
+pub struct Queue<T, const N: usize> {
+    items: UnsafeCell<Deque<T, N>>,
+}
+
+// Every access to `items` happens with interrupts masked.
+unsafe impl<T: Send, const N: usize> Sync for Queue<T, N> {}
+
+impl<T, const N: usize> Queue<T, N> {
+    pub const fn new() -> Queue<T, N> {
+        Queue {
+            items: UnsafeCell::new(Deque::new()),
+        }
+    }
+
+    /// Add an item after the others, give it back when the queue is full.
+    pub fn post(&self, item: T) -> Result<(), T> {
+        self.with(|items| items.push_back(item))
+    }
+
+    /// Remove the oldest item.
+    pub fn take(&self) -> Option<T> {
+        self.with(|items| items.pop_front())
+    }
+
+    pub fn len(&self) -> usize {
+        self.with(|items| items.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.with(|items| items.clear());
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut Deque<T, N>) -> R) -> R {
+        without_interrupts(|| f(unsafe { &mut *self.items.get() }))
+    }
+}
+
+impl<T, const N: usize> Default for Queue<T, N> {
+    fn default() -> Self {
+        Queue::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::effect::{Effect, Scheduler};
+    use crate::ir::{apply, Action};
+    use crate::led::{Color, LedStrip};
+    use crate::mock::MockPin;
+    use crate::queue::Queue;
+
+    #[test]
+    fn order() {
+        let queue: Queue<u8, 2> = Queue::new();
+
+        assert_eq!(queue.post(1), Ok(()));
+        assert_eq!(queue.post(2), Ok(()));
+        assert_eq!(queue.post(3), Err(3));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.take(), Some(1));
+        assert_eq!(queue.post(3), Ok(()));
+        assert_eq!(queue.take(), Some(2));
+        assert_eq!(queue.take(), Some(3));
+        assert_eq!(queue.take(), None);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn actions() {
+        static ACTIONS: Queue<Action, 4> = Queue::new();
+        let solid = |_, _| Color::Red;
+        let mut scheduler: Scheduler<1> = Scheduler::new();
+        let mut led_strip = LedStrip::with_transmitter(1, MockPin::<64>::new());
+        scheduler.add(Effect::new("solid", &solid));
+
+        ACTIONS.post(Action::Off).ok();
+        ACTIONS.post(Action::BrightnessDown).ok();
+
+        while let Some(action) = ACTIONS.take() {
+            apply(action, &mut scheduler, &mut led_strip);
+        }
+
+        assert!(!scheduler.is_enabled());
+        assert!(led_strip.brightness() < 255);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::led::Color;
+use crate::math::scale8;
+use crate::random::Random;
+
+const HEAD: Color = Color::RGB(180, 255, 180);
+
+/// Digital rain: bright drops falling down `COLUMNS` columns, leaving
+/// fading green trails, drawn over a pixel buffer
+/// (e.g. `BufferedStrip::pixels_mut()`).
+///
+/// The buffer is split in `COLUMNS` runs of equal length, each starting with
+/// its top LED, e.g. stacked vertical strips all wired from the top, or a
+/// single strip with `COLUMNS` being 1.
+pub struct DigitalRain<const COLUMNS: usize> {
+    random: Random,
+    drops: [Option<usize>; COLUMNS],
+    spawn_rate: u8,
+    fade: u8,
+    frames_per_step: u8,
+    frame: u8,
+}
+
+impl<const COLUMNS: usize> DigitalRain<COLUMNS> {
+    pub fn new(random: Random) -> DigitalRain<COLUMNS> {
+        DigitalRain {
+            random,
+            drops: [None; COLUMNS],
+            spawn_rate: 24,
+            fade: 40,
+            frames_per_step: 2,
+            frame: 0,
+        }
+    }
+
+    /// Chance for a drop to start in a free column each step, out of 256.
+    pub fn spawn_rate(mut self, spawn_rate: u8) -> DigitalRain<COLUMNS> {
+        self.spawn_rate = spawn_rate;
+
+        self
+    }
+
+    /// How much the trails dim each frame, red and blue fading twice as
+    /// fast so the white head leaves a green trail.
+    pub fn fade(mut self, fade: u8) -> DigitalRain<COLUMNS> {
+        self.fade = fade;
+
+        self
+    }
+
+    /// Frames before the drops move down one LED.
+    pub fn frames_per_step(mut self, frames: u8) -> DigitalRain<COLUMNS> {
+        self.frames_per_step = frames.max(1);
+
+        self
+    }
+
+    /// Row of the drop of each column, `None` for no drop.
+    pub fn drops(&self) -> &[Option<usize>; COLUMNS] {
+        &self.drops
+    }
+
+    pub fn draw(&mut self, pixels: &mut [Color]) {
+        let height = pixels.len() / COLUMNS.max(1);
+        let green_scale = 255 - self.fade;
+        let scale = 255 - self.fade.saturating_mul(2);
+
+        for pixel in pixels.iter_mut() {
+            let (red, green, blue) = pixel.to_rgb();
+            *pixel = Color::RGB(scale8(red, scale), scale8(green, green_scale), scale8(blue, scale));
+        }
+
+        self.frame = (self.frame + 1) % self.frames_per_step;
+
+        if self.frame == 0 {
+            for drop in &mut self.drops {
+                *drop = match *drop {
+                    Some(row) if row + 1 < height => Some(row + 1),
+                    Some(_) => None,
+                    None => match height > 0 && self.random.random8() < self.spawn_rate {
+                        true => Some(0),
+                        false => None,
+                    },
+                };
+            }
+        }
+
+        for (column, drop) in self.drops.iter().enumerate() {
+            if let Some(row) = *drop && row < height {
+                pixels[column * height + row] = HEAD;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::led::Color;
+    use crate::rain::DigitalRain;
+    use crate::random::Random;
+
+    #[test]
+    fn falls() {
+        let mut pixels = [Color::Black; 8];
+        let mut rain = DigitalRain::<2>::new(Random::new(3)).spawn_rate(255).frames_per_step(1).fade(100);
+
+        rain.draw(&mut pixels);
+        assert_eq!(rain.drops(), &[Some(0), Some(0)]);
+        assert_eq!(pixels[4], Color::RGB(180, 255, 180));
+
+        rain.draw(&mut pixels);
+        assert_eq!(rain.drops(), &[Some(1), Some(1)]);
+        assert_eq!(pixels[0], Color::RGB(39, 155, 39));
+        assert_eq!(pixels[5], Color::RGB(180, 255, 180));
+
+        rain.draw(&mut pixels);
+        rain.draw(&mut pixels);
+        rain.draw(&mut pixels);
+        assert_eq!(rain.drops(), &[None, None]);
+        assert_eq!(pixels[3].to_rgb(), (39, 155, 39));
+    }
+}
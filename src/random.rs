@@ -0,0 +1,113 @@
+/// Tiny xorshift pseudo-random generator for effects, not suitable for
+/// anything security related.
+///
+/// Seed it differently on each boot so effects don't repeat, e.g. from the
+/// lowest bit of a few readings of an unconnected analog pin:
+///
+/// ```ignore
+/// let mut random = Random::from_noise((0..16).map(|_| floating.analog_read(&mut adc)));
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Random {
+    state: u32,
+}
+
+impl Random {
+    pub const fn new(seed: u32) -> Random {
+        Random {
+            // Xorshift stays at 0 forever from a null state.
+            state: match seed {
+                0 => 0x2545_F491,
+                seed => seed,
+            },
+        }
+    }
+
+    /// Seed from noisy samples (ADC readings or timer values), keeping the
+    /// lowest, least predictable bit of each.
+    pub fn from_noise<I: IntoIterator<Item = u16>>(samples: I) -> Random {
+        let mut random = Random::new(0);
+        random.add_entropy(samples);
+
+        random
+    }
+
+    /// Mix more noisy samples into the state.
+    pub fn add_entropy<I: IntoIterator<Item = u16>>(&mut self, samples: I) {
+        for sample in samples {
+            self.state = Random::new(self.state.rotate_left(1) ^ u32::from(sample & 1)).state;
+            self.random16();
+        }
+    }
+
+    pub fn random16(&mut self) -> u16 {
+        let mut state = self.state;
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        self.state = state;
+
+        (state >> 16) as u16
+    }
+
+    pub fn random8(&mut self) -> u8 {
+        (self.random16() >> 8) as u8
+    }
+
+    /// Random value from 0 to `limit` (excluded), 0 if `limit` is 0.
+    pub fn random8_below(&mut self, limit: u8) -> u8 {
+        ((u16::from(self.random8()) * u16::from(limit)) >> 8) as u8
+    }
+
+    /// Random value from `min` to `max` (excluded).
+    pub fn random8_between(&mut self, min: u8, max: u8) -> u8 {
+        min + self.random8_below(max.saturating_sub(min))
+    }
+
+    /// Random value from 0 to `limit` (excluded), 0 if `limit` is 0.
+    pub fn random16_below(&mut self, limit: u16) -> u16 {
+        ((u32::from(self.random16()) * u32::from(limit)) >> 16) as u16
+    }
+}
+
+impl Default for Random {
+    fn default() -> Random {
+        Random::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::Random;
+
+    #[test]
+    fn ranges() {
+        let mut random = Random::new(42);
+        let mut seen = [false; 10];
+
+        for _ in 0..1000 {
+            let value = random.random8_below(10);
+            seen[usize::from(value)] = true;
+
+            assert!((5..9).contains(&random.random8_between(5, 9)));
+            assert!(random.random16_below(300) < 300);
+        }
+
+        assert_eq!(seen, [true; 10]);
+        assert_eq!(random.random8_below(0), 0);
+        assert_eq!(random.random8_between(9, 5), 9);
+    }
+
+    #[test]
+    fn seeding() {
+        let mut zero = Random::new(0);
+        assert_ne!(zero.random16(), 0);
+
+        let first = Random::from_noise([512, 513, 512, 515]);
+        let second = Random::from_noise([513, 513, 512, 515]);
+
+        assert_ne!(first, second);
+        assert_eq!(first, Random::from_noise([0, 1, 0, 1]));
+    }
+}
@@ -0,0 +1,106 @@
+use embedded_hal::delay::DelayNs;
+use pio::{Assembler, JmpCondition, OutDestination, SideSet};
+use rp2040_hal::pio::{
+    Buffers, InstallError, PIOBuilder, PIOExt, PinDir, Running, ShiftDirection, StateMachine, StateMachineIndex, Tx,
+    UninitStateMachine, PIO,
+};
+use crate::led::Transmitter;
+
+/// PIO cycles of a bit: high for `T1`, then high (1) or low (0) for `T2`,
+/// then low for `T3`.
+const T1: u8 = 2;
+const T2: u8 = 5;
+const T3: u8 = 3;
+/// Bits per second of the WS2812B signal.
+const BIT_RATE: u64 = 800_000;
+
+/// WS2812B signal generated by a PIO state machine on an RP2040, the CPU
+/// only filling the FIFO, so `LedStrip` and the effects work unchanged:
+///
+/// ```ignore
+/// let (mut pio, sm0, _, _, _) = pac.PIO0.split(&mut pac.RESETS);
+/// let pin = pins.gpio16.into_function::<FunctionPio0>();
+/// let pio_strip = Ws2812Pio::new(&mut pio, sm0, pin.id().num, clocks.system_clock.freq().to_Hz(), timer)?;
+/// let mut led_strip = LedStrip::with_transmitter(NUM_LEDS, pio_strip);
+/// ```
+pub struct Ws2812Pio<P: PIOExt, SM: StateMachineIndex, D: DelayNs> {
+    tx: Tx<(P, SM)>,
+    delay: D,
+    _state_machine: StateMachine<(P, SM), Running>,
+}
+
+impl<P: PIOExt, SM: StateMachineIndex, D: DelayNs> Ws2812Pio<P, SM, D> {
+    /// Install the program and start the state machine sending on the GPIO
+    /// `pin_id`, which must already be set to the PIO function.
+    pub fn new(
+        pio: &mut PIO<P>,
+        state_machine: UninitStateMachine<(P, SM)>,
+        pin_id: u8,
+        sys_clock_hz: u32,
+        delay: D,
+    ) -> Result<Ws2812Pio<P, SM, D>, InstallError> {
+        let mut assembler = Assembler::<32>::new_with_side_set(SideSet::new(false, 1, false));
+        let mut wrap_target = assembler.label();
+        let mut wrap_source = assembler.label();
+        let mut do_zero = assembler.label();
+        assembler.bind(&mut wrap_target);
+        assembler.out_with_delay_and_side_set(OutDestination::X, 1, T3 - 1, 0);
+        assembler.jmp_with_delay_and_side_set(JmpCondition::XIsZero, &mut do_zero, T1 - 1, 1);
+        assembler.jmp_with_delay_and_side_set(JmpCondition::Always, &mut wrap_target, T2 - 1, 1);
+        assembler.bind(&mut do_zero);
+        assembler.nop_with_delay_and_side_set(T2 - 1, 0);
+        assembler.bind(&mut wrap_source);
+        let program = pio.install(&assembler.assemble_with_wrap(wrap_source, wrap_target))?;
+
+        let (int, frac) = clock_divisor(sys_clock_hz);
+        let (mut state_machine, _, tx) = PIOBuilder::from_installed_program(program)
+            .side_set_pin_base(pin_id)
+            .out_shift_direction(ShiftDirection::Left)
+            .autopull(true)
+            .pull_threshold(8)
+            .buffers(Buffers::OnlyTx)
+            .clock_divisor_fixed_point(int, frac)
+            .build(state_machine);
+        state_machine.set_pindirs([(pin_id, PinDir::Output)]);
+
+        Ok(Ws2812Pio {
+            tx,
+            delay,
+            _state_machine: state_machine.start(),
+        })
+    }
+}
+
+impl<P: PIOExt, SM: StateMachineIndex, D: DelayNs> Transmitter for Ws2812Pio<P, SM, D> {
+    fn send_byte(&mut self, byte: u8) {
+        // Shifted out from the most significant bit, 8 bits per pull.
+        while !self.tx.write(u32::from(byte) << 24) {}
+    }
+
+    fn reset(&mut self, us: u32) {
+        while !self.tx.is_empty() {}
+
+        // The last byte may still be shifting out, 10 µs at most.
+        self.delay.delay_us(us + 10);
+    }
+}
+
+/// State machine clock divisor as an integer and a 1/256 fraction, so the
+/// `T1 + T2 + T3` cycles of a bit last 1.25 µs.
+fn clock_divisor(sys_clock_hz: u32) -> (u16, u8) {
+    let cycles_per_second = BIT_RATE * u64::from(T1 + T2 + T3);
+    let divisor = u64::from(sys_clock_hz) * 256 / cycles_per_second;
+
+    ((divisor >> 8) as u16, divisor as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rp2040::clock_divisor;
+
+    #[test]
+    fn divisor() {
+        assert_eq!(clock_divisor(125_000_000), (15, 160));
+        assert_eq!(clock_divisor(8_000_000), (1, 0));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::gradient::{palette_at, Gradient};
+use crate::led::Color;
+
+/// Colors repeated in turn, each for `run` LEDs, endlessly, e.g. candy cane
+/// stripes with `ColorCycle::new(&[Color::Red, Color::White]).with_run(3)`.
+#[derive(Clone)]
+pub struct ColorCycle<'a> {
+    colors: &'a [Color],
+    run: usize,
+    position: usize,
+}
+
+impl<'a> ColorCycle<'a> {
+    pub fn new(colors: &'a [Color]) -> ColorCycle<'a> {
+        ColorCycle {
+            colors,
+            run: 1,
+            position: 0,
+        }
+    }
+
+    pub fn with_run(mut self, run: usize) -> ColorCycle<'a> {
+        self.run = run.max(1);
+
+        self
+    }
+
+    /// Start `offset` LEDs into the cycle, e.g. the frame number to scroll it.
+    pub fn with_offset(mut self, offset: usize) -> ColorCycle<'a> {
+        self.position = offset;
+
+        self
+    }
+}
+
+impl Iterator for ColorCycle<'_> {
+    type Item = Color;
+
+    fn next(&mut self) -> Option<Color> {
+        let period = self.colors.len() * self.run;
+
+        if period == 0 {
+            return None;
+        }
+
+        let color = self.colors[self.position % period / self.run];
+        self.position = (self.position + 1) % period;
+
+        Some(color)
+    }
+}
+
+/// `len` colors of a gradient, from its first stop to its last.
+#[derive(Clone)]
+pub struct GradientIter<'a, const N: usize> {
+    gradient: &'a Gradient<N>,
+    index: usize,
+    len: usize,
+}
+
+impl<'a, const N: usize> GradientIter<'a, N> {
+    pub fn new(gradient: &'a Gradient<N>, len: usize) -> GradientIter<'a, N> {
+        GradientIter {
+            gradient,
+            index: 0,
+            len,
+        }
+    }
+}
+
+impl<const N: usize> Iterator for GradientIter<'_, N> {
+    type Item = Color;
+
+    fn next(&mut self) -> Option<Color> {
+        if self.index >= self.len {
+            return None;
+        }
+
+        let color = self.gradient.color(self.index, self.len);
+        self.index += 1;
+
+        Some(color)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const N: usize> ExactSizeIterator for GradientIter<'_, N> {}
+
+/// Colors of a palette spread over 0..=255, read from `start` moving `step`
+/// per LED and wrapping around, endlessly: with a `start` increasing each
+/// frame, the palette scrolls along the strip.
+#[derive(Clone)]
+pub struct PaletteIter<'a> {
+    palette: &'a [Color],
+    position: u8,
+    step: u8,
+}
+
+impl<'a> PaletteIter<'a> {
+    pub fn new(palette: &'a [Color], start: u8, step: u8) -> PaletteIter<'a> {
+        PaletteIter {
+            palette,
+            position: start,
+            step,
+        }
+    }
+}
+
+impl Iterator for PaletteIter<'_> {
+    type Item = Color;
+
+    fn next(&mut self) -> Option<Color> {
+        let color = palette_at(self.palette, self.position);
+        self.position = self.position.wrapping_add(self.step);
+
+        Some(color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gradient::Gradient;
+    use crate::led::Color;
+    use crate::sequence::{ColorCycle, GradientIter, PaletteIter};
+
+    #[test]
+    fn cycle() {
+        let colors = [Color::Red, Color::White];
+        let stripes: [Option<Color>; 5] = {
+            let mut cycle = ColorCycle::new(&colors).with_run(2).with_offset(1);
+            core::array::from_fn(|_| cycle.next())
+        };
+
+        assert_eq!(stripes, [Some(Color::Red), Some(Color::White), Some(Color::White), Some(Color::Red), Some(Color::Red)]);
+        assert_eq!(ColorCycle::new(&[]).next(), None);
+    }
+
+    #[test]
+    fn gradient() {
+        let gradient = Gradient::even([Color::Black, Color::White]);
+        let colors = GradientIter::new(&gradient, 3);
+
+        assert_eq!(colors.len(), 3);
+        assert!(colors.map(|color| color.to_rgb()).eq([(0, 0, 0), (127, 127, 127), (255, 255, 255)]));
+    }
+
+    #[test]
+    fn palette() {
+        let palette = [Color::RGB(0, 0, 0), Color::RGB(0, 0, 200)];
+        let mut colors = PaletteIter::new(&palette, 192, 64);
+
+        assert_eq!(colors.next(), Some(Color::RGB(0, 0, 150)));
+        assert_eq!(colors.next(), Some(Color::RGB(0, 0, 0)));
+        assert_eq!(colors.nth(1), Some(Color::RGB(0, 0, 100)));
+    }
+}
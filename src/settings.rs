@@ -0,0 +1,126 @@
+#[cfg(feature = "arduino-hal")]
+use arduino_hal::Eeprom;
+use crate::led::Color;
+
+const MAGIC: u8 = 0xA5;
+
+/// Bytes used by `Settings` in the storage.
+pub const SIZE: u16 = 7;
+
+/// Byte-addressed non-volatile memory.
+pub trait Storage {
+    fn read(&self, address: u16) -> u8;
+
+    fn write(&mut self, address: u16, value: u8);
+
+    /// Write only if the value changed, return whether it did, so saving settings
+    /// that did not change does not wear the memory out (~100 000 writes per cell on AVR).
+    fn update(&mut self, address: u16, value: u8) -> bool {
+        if self.read(address) == value {
+            return false;
+        }
+
+        self.write(address, value);
+
+        true
+    }
+}
+
+#[cfg(feature = "arduino-hal")]
+impl Storage for Eeprom {
+    fn read(&self, address: u16) -> u8 {
+        self.read_byte(address)
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        self.write_byte(address, value);
+    }
+}
+
+impl Storage for [u8] {
+    fn read(&self, address: u16) -> u8 {
+        self[address as usize]
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        self[address as usize] = value;
+    }
+}
+
+/// User settings to restore after a power cycle.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Settings {
+    pub brightness: u8,
+    /// Index of the effect in the scheduler.
+    pub effect: u8,
+    pub color: (u8, u8, u8),
+}
+
+impl Settings {
+    pub fn color(&self) -> Color {
+        let (red, green, blue) = self.color;
+
+        Color::RGB(red, green, blue)
+    }
+
+    fn bytes(&self) -> [u8; SIZE as usize] {
+        let (red, green, blue) = self.color;
+        let data = [self.brightness, self.effect, red, green, blue];
+        let checksum = data.iter().fold(MAGIC, |sum, byte| sum.rotate_left(1) ^ byte);
+
+        [MAGIC, data[0], data[1], data[2], data[3], data[4], checksum]
+    }
+
+    /// Settings saved at `address`, `None` if nothing valid was saved there
+    /// (blank or corrupted memory).
+    pub fn load<S: ?Sized + Storage>(storage: &S, address: u16) -> Option<Settings> {
+        let bytes: [u8; SIZE as usize] = core::array::from_fn(|index| storage.read(address + index as u16));
+        let settings = Settings {
+            brightness: bytes[1],
+            effect: bytes[2],
+            color: (bytes[3], bytes[4], bytes[5]),
+        };
+
+        match settings.bytes() == bytes {
+            true => Some(settings),
+            false => None,
+        }
+    }
+
+    /// Save at `address`, writing only the bytes that changed, return how many were written.
+    pub fn save<S: ?Sized + Storage>(&self, storage: &mut S, address: u16) -> usize {
+        self.bytes()
+            .iter()
+            .enumerate()
+            .filter(|&(index, &byte)| storage.update(address + index as u16, byte))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::settings::{Settings, Storage};
+
+    #[test]
+    fn persistence() {
+        let mut eeprom = [0xFF; 16];
+        let mut settings = Settings {
+            brightness: 128,
+            effect: 2,
+            color: (255, 136, 0),
+        };
+
+        assert_eq!(Settings::load(&eeprom[..], 4), None);
+        assert_eq!(settings.save(&mut eeprom[..], 4), 6); // red is already 0xFF on blank memory
+        assert_eq!(Settings::load(&eeprom[..], 4), Some(settings));
+        assert_eq!(settings.save(&mut eeprom[..], 4), 0);
+
+        settings.brightness = 64;
+        assert_eq!(settings.save(&mut eeprom[..], 4), 2);
+        assert_eq!(Settings::load(&eeprom[..], 4).map(|settings| settings.brightness), Some(64));
+
+        eeprom.write(6, 3);
+        assert_eq!(Settings::load(&eeprom[..], 4), None);
+    }
+}
@@ -0,0 +1,307 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+#[cfg(feature = "window")]
+use std::time::Instant;
+#[cfg(feature = "window")]
+use std::vec;
+use std::vec::Vec;
+use crate::led::{Color, ColorOrder, Transmitter};
+
+/// Colors latched by a simulated strip, LEDs not sent again keep their color.
+struct Pixels {
+    bytes: Vec<u8>,
+    pixels: Vec<Color>,
+    order: ColorOrder,
+}
+
+impl Pixels {
+    fn new() -> Pixels {
+        Pixels {
+            bytes: Vec::new(),
+            pixels: Vec::new(),
+            order: ColorOrder::Grb,
+        }
+    }
+
+    fn latch(&mut self) {
+        for (led_index, bytes) in self.bytes.chunks_exact(3).enumerate() {
+            let [red, green, blue] = self.order.unarrange([bytes[0], bytes[1], bytes[2]]);
+            let color = Color::RGB(red, green, blue);
+
+            match self.pixels.get_mut(led_index) {
+                Some(pixel) => *pixel = color,
+                None => self.pixels.push(color),
+            }
+        }
+
+        self.bytes.clear();
+    }
+}
+
+/// Strip drawn in a terminal as 24-bit ANSI colored blocks, redrawn in
+/// place each time a frame is latched. Like a real strip, LEDs not sent
+/// again keep their previous color.
+pub struct Terminal<W: Write> {
+    output: W,
+    pixels: Pixels,
+    realtime: bool,
+}
+
+impl Terminal<io::Stdout> {
+    pub fn stdout() -> Terminal<io::Stdout> {
+        Terminal::new(io::stdout())
+    }
+}
+
+impl<W> Terminal<W> where W: Write {
+    pub fn new(output: W) -> Terminal<W> {
+        Terminal {
+            output,
+            pixels: Pixels::new(),
+            realtime: true,
+        }
+    }
+
+    /// Sleep during resets so animations run at their real speed (default),
+    /// disable it in tests.
+    pub fn realtime(mut self, realtime: bool) -> Terminal<W> {
+        self.realtime = realtime;
+
+        self
+    }
+
+    /// Colors displayed since the last latched frame.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels.pixels
+    }
+
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        write!(self.output, "\r\x1b[2K")?;
+
+        for pixel in &self.pixels.pixels {
+            let (red, green, blue) = pixel.to_rgb();
+            write!(self.output, "\x1b[38;2;{red};{green};{blue}m\u{2588}")?;
+        }
+
+        write!(self.output, "\x1b[0m")?;
+        self.output.flush()
+    }
+}
+
+impl<W> Transmitter for Terminal<W> where W: Write {
+    fn send_byte(&mut self, byte: u8) {
+        self.pixels.bytes.push(byte);
+    }
+
+    fn reset(&mut self, us: u32) {
+        self.pixels.latch();
+        // Nowhere to report a broken terminal from inside the driver, keep animating.
+        let _ = self.draw();
+
+        if self.realtime {
+            thread::sleep(Duration::from_micros(u64::from(us)));
+        }
+    }
+
+    fn set_order(&mut self, order: ColorOrder) {
+        self.pixels.order = order;
+    }
+}
+
+#[cfg(feature = "window")]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Shape {
+    Square,
+    Circle,
+}
+
+/// Strip (or matrix, with `columns`) drawn in a desktop window, redrawn at
+/// most `fps` times per second so the animation keeps its own timing.
+#[cfg(feature = "window")]
+pub struct Window {
+    window: minifb::Window,
+    buffer: Vec<u32>,
+    pixels: Pixels,
+    led_count: usize,
+    columns: usize,
+    size: usize,
+    shape: Shape,
+    refresh: Duration,
+    last_draw: Option<Instant>,
+    realtime: bool,
+}
+
+#[cfg(feature = "window")]
+impl Window {
+    /// Window showing `led_count` LEDs on rows of `columns`, each LED drawn on
+    /// `size` pixels, with at least one column and one pixel.
+    pub fn new(title: &str, led_count: usize, columns: usize, size: usize) -> Result<Window, minifb::Error> {
+        let columns = columns.clamp(1, led_count.max(1));
+        let size = size.max(1);
+        let (width, height) = (columns * size, led_count.div_ceil(columns).max(1) * size);
+
+        Ok(Window {
+            window: minifb::Window::new(title, width, height, minifb::WindowOptions::default())?,
+            buffer: vec![0; width * height],
+            pixels: Pixels::new(),
+            led_count,
+            columns,
+            size,
+            shape: Shape::Circle,
+            refresh: Duration::from_millis(16),
+            last_draw: None,
+            realtime: true,
+        })
+    }
+
+    pub fn shape(mut self, shape: Shape) -> Window {
+        self.shape = shape;
+
+        self
+    }
+
+    pub fn fps(mut self, fps: u32) -> Window {
+        self.refresh = Duration::from_secs(1) / fps.max(1);
+
+        self
+    }
+
+    /// Sleep during resets so animations run at their real speed (default).
+    pub fn realtime(mut self, realtime: bool) -> Window {
+        self.realtime = realtime;
+
+        self
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.window.is_open()
+    }
+
+    /// Colors displayed since the last latched frame.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels.pixels
+    }
+
+    fn draw(&mut self) -> Result<(), minifb::Error> {
+        let width = self.columns * self.size;
+        let radius = (self.size / 2).saturating_sub(1);
+        self.buffer.fill(0);
+
+        for (led_index, pixel) in self.pixels.pixels.iter().take(self.led_count).enumerate() {
+            let (red, green, blue) = pixel.to_rgb();
+            let color = (u32::from(red) << 16) | (u32::from(green) << 8) | u32::from(blue);
+            let (left, top) = ((led_index % self.columns) * self.size, (led_index / self.columns) * self.size);
+
+            for y in 0..self.size {
+                for x in 0..self.size {
+                    let inside = match self.shape {
+                        Shape::Square => x > 0 && y > 0 && x + 1 < self.size && y + 1 < self.size,
+                        Shape::Circle => {
+                            let (dx, dy) = (x.abs_diff(self.size / 2), y.abs_diff(self.size / 2));
+
+                            dx * dx + dy * dy <= radius * radius
+                        },
+                    };
+
+                    if inside {
+                        self.buffer[(top + y) * width + left + x] = color;
+                    }
+                }
+            }
+        }
+
+        let height = self.buffer.len() / width;
+        self.window.update_with_buffer(&self.buffer, width, height)
+    }
+}
+
+#[cfg(feature = "window")]
+impl Transmitter for Window {
+    fn send_byte(&mut self, byte: u8) {
+        self.pixels.bytes.push(byte);
+    }
+
+    fn reset(&mut self, us: u32) {
+        self.pixels.latch();
+
+        if self.last_draw.is_none_or(|last_draw| last_draw.elapsed() >= self.refresh) {
+            self.last_draw = Some(Instant::now());
+            // A closed window just stops being updated, the animation keeps running.
+            let _ = self.draw();
+        }
+
+        if self.realtime {
+            thread::sleep(Duration::from_micros(u64::from(us)));
+        }
+    }
+
+    fn set_order(&mut self, order: ColorOrder) {
+        self.pixels.order = order;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::String;
+    use std::vec::Vec;
+    use crate::led::{Color, ColorOrder, LedStrip};
+    use crate::simulator::Terminal;
+
+    #[test]
+    fn render() {
+        let mut led_strip = LedStrip::with_transmitter(3, Terminal::new(Vec::new()).realtime(false));
+
+        led_strip.each(|led_index| match led_index {
+            0 => Color::Red,
+            1 => Color::Green,
+            _ => Color::Black,
+        });
+        led_strip.rest(50);
+
+        let terminal = led_strip.transmitter();
+        let output = String::from_utf8(terminal.output().clone()).unwrap();
+
+        assert_eq!(terminal.pixels(), &[Color::RGB(255, 0, 0), Color::RGB(0, 255, 0), Color::RGB(0, 0, 0)]);
+        assert!(output.starts_with("\r\x1b[2K\x1b[38;2;255;0;0m\u{2588}\x1b[38;2;0;255;0m\u{2588}"));
+        assert!(output.ends_with("\x1b[0m"));
+    }
+
+    #[test]
+    fn partial_frame() {
+        let mut led_strip = LedStrip::with_transmitter(3, Terminal::new(Vec::new()).realtime(false));
+
+        led_strip.each(|_| Color::Blue);
+        led_strip.rest(50);
+        led_strip.color(Color::Red);
+        led_strip.rest(50);
+
+        assert_eq!(led_strip.transmitter().pixels(), &[Color::RGB(255, 0, 0), Color::RGB(0, 0, 255), Color::RGB(0, 0, 255)]);
+    }
+
+    #[test]
+    fn output_stage() {
+        let mut led_strip = LedStrip::with_transmitter(2, Terminal::new(Vec::new()).realtime(false));
+
+        led_strip.set_brightness(127);
+        led_strip.each(|_| Color::White);
+        led_strip.rest(50);
+
+        assert_eq!(led_strip.transmitter().pixels(), &[Color::RGB(127, 127, 127), Color::RGB(127, 127, 127)]);
+    }
+
+    #[test]
+    fn order() {
+        let mut led_strip = LedStrip::with_transmitter(2, Terminal::new(Vec::new()).realtime(false));
+
+        led_strip.set_order(ColorOrder::Brg);
+        led_strip.each(|led_index| if led_index == 0 { Color::RGB(10, 20, 30) } else { Color::Blue });
+        led_strip.rest(50);
+
+        assert_eq!(led_strip.transmitter().pixels(), &[Color::RGB(10, 20, 30), Color::RGB(0, 0, 255)]);
+    }
+}
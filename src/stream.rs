@@ -0,0 +1,178 @@
+use crate::adalight::Adalight;
+use crate::buffer::DoubleBufferedStrip;
+use crate::command::{Command, CommandError, CommandLine};
+use crate::led::{Color, LedStrip, Transmitter};
+use crate::tpm2::{self, Tpm2};
+
+/// Reply to the host once a frame was verified and displayed.
+pub const ACK: u8 = 0x06;
+
+/// Reply to the host once a corrupted frame was dropped, so it can send it again.
+pub const NAK: u8 = 0x15;
+
+/// Draw a received pixel in the back buffer and, at the end of the frame,
+/// show it if `valid` or go back to the frame shown, returning the reply.
+pub(crate) fn stage<T: Transmitter, const N: usize>(
+    led_strip: &mut DoubleBufferedStrip<T, N>,
+    pixel: Option<(usize, Color)>,
+    valid: Option<bool>,
+) -> Option<u8> {
+    if let Some((led_index, color)) = pixel {
+        // Pixels beyond the strip length are dropped.
+        led_strip.set_pixel(led_index, color).ok();
+    }
+
+    let valid = valid?;
+
+    if valid {
+        led_strip.swap_and_show();
+    }
+
+    led_strip.copy_front();
+
+    match valid {
+        true => Some(ACK),
+        false => Some(NAK),
+    }
+}
+
+/// Serial protocol, detected from the first byte of a frame or line.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Protocol {
+    /// Frames starting with `Ada`.
+    Adalight,
+    /// Packets starting with `0xC9`.
+    Tpm2,
+    /// Lines starting with a command letter, see `parse_command()`.
+    Command,
+}
+
+impl Protocol {
+    pub fn detect(byte: u8) -> Option<Protocol> {
+        match byte {
+            b'A' => Some(Protocol::Adalight),
+            tpm2::START => Some(Protocol::Tpm2),
+            b'B' | b'C' | b'E' | b'F' | b'b' | b'c' | b'e' | b'f' => Some(Protocol::Command),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Received<'a> {
+    /// Adalight or TPM2 frame displayed.
+    Frame,
+    /// Line of the command protocol.
+    Command(Result<Command<'a>, CommandError>),
+}
+
+/// Receiver of the Adalight, TPM2 and command protocols on the same serial
+/// port, switching to the one matching the header of each frame or line,
+/// so one firmware works with all the host programs. Command lines are
+/// up to `N` bytes.
+///
+/// Bytes between frames matching none of the protocols are skipped.
+pub struct Receiver<const N: usize> {
+    protocol: Option<Protocol>,
+    adalight: Adalight,
+    tpm2: Tpm2,
+    command_line: CommandLine<N>,
+}
+
+impl<const N: usize> Receiver<N> {
+    pub fn new() -> Receiver<N> {
+        Receiver {
+            protocol: None,
+            adalight: Adalight::new(),
+            tpm2: Tpm2::new(),
+            command_line: CommandLine::new(),
+        }
+    }
+
+    /// Protocol of the frame or line being received, `None` between them.
+    pub fn protocol(&self) -> Option<Protocol> {
+        self.protocol
+    }
+
+    /// Parse the next byte, sending the pixels of frames to the strip.
+    pub fn push<T: Transmitter>(&mut self, byte: u8, led_strip: &mut LedStrip<T>) -> Option<Received<'_>> {
+        let protocol = match self.protocol {
+            Some(protocol) => protocol,
+            None => *self.protocol.insert(Protocol::detect(byte)?),
+        };
+
+        match protocol {
+            Protocol::Adalight => {
+                let shown = self.adalight.push(byte, led_strip);
+
+                if self.adalight.is_idle() {
+                    self.protocol = None;
+                }
+
+                shown.then_some(Received::Frame)
+            },
+            Protocol::Tpm2 => {
+                let shown = self.tpm2.push(byte, led_strip);
+
+                if self.tpm2.is_idle() {
+                    self.protocol = None;
+                }
+
+                shown.then_some(Received::Frame)
+            },
+            Protocol::Command => {
+                if byte == b'\n' {
+                    self.protocol = None;
+                }
+
+                self.command_line.feed(byte).map(Received::Command)
+            },
+        }
+    }
+}
+
+impl<const N: usize> Default for Receiver<N> {
+    fn default() -> Self {
+        Receiver::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::Command;
+    use crate::led::LedStrip;
+    use crate::mock::MockPin;
+    use crate::stream::{Protocol, Received, Receiver};
+
+    #[test]
+    fn detect() {
+        let mut receiver: Receiver<8> = Receiver::new();
+        let mut led_strip = LedStrip::with_transmitter(1, MockPin::<256>::new());
+        let mut frames = 0;
+        let mut commands = 0;
+        let stream: [&[u8]; 4] = [
+            b"\0Ada\0\0\x55\x0a\x14\x1e",
+            b"B128\r\n",
+            &[0xC9, 0xDA, 0x00, 0x03, 1, 2, 3, 0x36],
+            b"\nE rain\n",
+        ];
+
+        for byte in stream.concat() {
+            match receiver.push(byte, &mut led_strip) {
+                Some(Received::Frame) => frames += 1,
+                Some(Received::Command(command)) => {
+                    assert_eq!(command, [Ok(Command::Brightness(128)), Ok(Command::Effect("rain"))][commands]);
+                    commands += 1;
+                },
+                None => {},
+            }
+        }
+
+        assert_eq!((frames, commands), (2, 2));
+        assert_eq!(receiver.protocol(), None);
+        assert!(led_strip.transmitter().bytes().eq([20, 10, 30, 2, 1, 3]));
+        assert_eq!(Protocol::detect(0xC9), Some(Protocol::Tpm2));
+        assert_eq!(Protocol::detect(b'x'), None);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::color16::Color16;
+use crate::led::{LedStrip, Transmitter};
+
+/// Colors of the dawn at 16 bits per channel, the progress from 0 to 65535
+/// they're reached at, the red phases lasting longer as they're brightening
+/// less.
+const DAWN: [(u16, Color16); 4] = [
+    (0, Color16 { red: 0, green: 0, blue: 0 }),
+    (24_000, Color16 { red: 28_000, green: 1_000, blue: 0 }),
+    (46_000, Color16 { red: 65_535, green: 20_000, blue: 0 }),
+    (65_535, Color16 { red: 65_535, green: 50_000, blue: 28_000 }),
+];
+
+/// Wake-up light, morphing from black through deep red and orange to warm
+/// white over `duration_ms` (e.g. 30 minutes), or back for a sunset.
+///
+/// Colors are sent with 16 bits per channel so, with `set_dithering()`,
+/// the first steps out of black don't visibly flicker from one level to
+/// the next.
+pub struct Sunrise {
+    start_ms: u32,
+    duration_ms: u32,
+    sunset: bool,
+}
+
+impl Sunrise {
+    pub fn new(start_ms: u32, duration_ms: u32) -> Sunrise {
+        Sunrise {
+            start_ms,
+            duration_ms,
+            sunset: false,
+        }
+    }
+
+    /// From warm white back to black, e.g. to fall asleep.
+    pub fn sunset(start_ms: u32, duration_ms: u32) -> Sunrise {
+        Sunrise {
+            sunset: true,
+            ..Sunrise::new(start_ms, duration_ms)
+        }
+    }
+
+    /// Start over from `now_ms`.
+    pub fn restart(&mut self, now_ms: u32) {
+        self.start_ms = now_ms;
+    }
+
+    /// Progress from 0 to 65535, counters wrapping around.
+    pub fn progress(&self, now_ms: u32) -> u16 {
+        let elapsed_ms = now_ms.wrapping_sub(self.start_ms).min(self.duration_ms);
+
+        match self.duration_ms {
+            0 => 65_535,
+            _ => (u64::from(elapsed_ms) * 65_535 / u64::from(self.duration_ms)) as u16,
+        }
+    }
+
+    pub fn is_finished(&self, now_ms: u32) -> bool {
+        self.progress(now_ms) == 65_535
+    }
+
+    pub fn color16(&self, now_ms: u32) -> Color16 {
+        let progress = match self.sunset {
+            true => 65_535 - self.progress(now_ms),
+            false => self.progress(now_ms),
+        };
+
+        for pair in DAWN.windows(2) {
+            let ((from_position, from), (to_position, to)) = (pair[0], pair[1]);
+
+            if progress <= to_position {
+                let fraction = u32::from(progress - from_position) * 65_535 / u32::from(to_position - from_position);
+
+                return from.lerp(to, fraction as u16);
+            }
+        }
+
+        DAWN[DAWN.len() - 1].1
+    }
+
+    /// Fill the strip with the color at `now_ms` and latch it.
+    pub fn show<T: Transmitter>(&self, led_strip: &mut LedStrip<T>, now_ms: u32) {
+        let color = self.color16(now_ms);
+
+        led_strip.latch();
+        led_strip.fill16(color);
+        led_strip.latch();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color16::Color16;
+    use crate::led::LedStrip;
+    use crate::mock::MockPin;
+    use crate::sunrise::Sunrise;
+
+    #[test]
+    fn sunrise() {
+        let sunrise = Sunrise::new(1_000, 30 * 60_000);
+
+        assert_eq!(sunrise.color16(1_000), Color16::new(0, 0, 0));
+        assert_eq!(sunrise.progress(1_000 + 15 * 60_000), 32_767);
+        assert_eq!(sunrise.color16(1_000 + 15 * 60_000).green, 8_571);
+        assert!(!sunrise.is_finished(1_000 + 15 * 60_000));
+        assert_eq!(sunrise.color16(1_000 + 40 * 60_000), Color16::new(65_535, 50_000, 28_000));
+        assert!(sunrise.is_finished(1_000 + 40 * 60_000));
+
+        // Smooth below one 8-bit level.
+        let step = sunrise.color16(1_000 + 60_000).red - sunrise.color16(1_000 + 59_000).red;
+        assert!(step < 257);
+    }
+
+    #[test]
+    fn sunset() {
+        let sunset = Sunrise::sunset(0, 1_000);
+        let mut led_strip = LedStrip::with_transmitter(2, MockPin::<256>::new());
+
+        assert_eq!(sunset.color16(0), Color16::new(65_535, 50_000, 28_000));
+        assert_eq!(sunset.color16(1_000), Color16::new(0, 0, 0));
+
+        sunset.show(&mut led_strip, 0);
+        assert!(led_strip.is_latched());
+        assert_eq!(led_strip.transmitter().bytes().count(), 6);
+    }
+
+    #[test]
+    fn power_limit() {
+        use crate::led::LedStripBuilder;
+        use crate::power::PowerLimit;
+
+        let sunrise = Sunrise::new(0, 1_000);
+        let mut led_strip = LedStripBuilder::new(MockPin::<2048>::new()).leds(30)
+            .power_limit(PowerLimit::new(5_000, 150)).build();
+
+        sunrise.show(&mut led_strip, 1_000);
+
+        assert!(led_strip.stats().milliamps <= 150);
+        assert!(led_strip.stats().milliamps >= 140);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::buffer::DoubleBufferedStrip;
+use crate::led::{Color, LedStrip, Transmitter, LATCH_TIME};
+use crate::stream::stage;
+
+pub(crate) const START: u8 = 0xC9;
+const DATA: u8 = 0xDA;
+const END: u8 = 0x36;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Event {
+    /// LED index and color, sent as soon as its 3 channels are received.
+    Pixel(usize, Color),
+    /// End of a data packet with a valid end byte (and checksum).
+    Frame,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum State {
+    Start,
+    Type,
+    SizeHigh,
+    SizeLow,
+    Payload,
+    Checksum,
+    End,
+}
+
+/// Incremental parser of TPM2 serial packets: `0xC9`, the packet type
+/// (`0xDA` for data), the payload size (2 bytes, big-endian), the payload,
+/// then `0x36`. Other packet types are skipped.
+///
+/// With `with_checksum()`, the payload is followed by its sum (wrapping
+/// around) before the end byte, checked by `push_checked()`.
+///
+/// Each call does a constant amount of work so it can be fed from the UART interrupt.
+pub struct Tpm2 {
+    state: State,
+    data: bool,
+    size: u16,
+    received: u16,
+    channels: [u8; 3],
+    checksum: bool,
+    sum: u8,
+    valid: Option<bool>,
+}
+
+impl Tpm2 {
+    pub fn new() -> Tpm2 {
+        Tpm2 {
+            state: State::Start,
+            data: false,
+            size: 0,
+            received: 0,
+            channels: [0; 3],
+            checksum: false,
+            sum: 0,
+            valid: None,
+        }
+    }
+
+    /// Expect a checksum byte between the payload and the end byte of each packet.
+    pub fn with_checksum(mut self) -> Tpm2 {
+        self.checksum = true;
+
+        self
+    }
+
+    /// Whether the parser is between packets, waiting for the start byte.
+    pub fn is_idle(&self) -> bool {
+        self.state == State::Start
+    }
+
+    fn payload_end(&self) -> State {
+        match self.checksum {
+            true => State::Checksum,
+            false => State::End,
+        }
+    }
+
+    pub fn feed(&mut self, byte: u8) -> Option<Event> {
+        match self.state {
+            State::Start => {
+                if byte == START {
+                    self.state = State::Type;
+                }
+            },
+            State::Type => {
+                self.data = byte == DATA;
+                self.state = State::SizeHigh;
+            },
+            State::SizeHigh => {
+                self.size = u16::from(byte) << 8;
+                self.state = State::SizeLow;
+            },
+            State::SizeLow => {
+                self.size |= u16::from(byte);
+                self.received = 0;
+                self.sum = 0;
+                self.state = match self.size {
+                    0 => self.payload_end(),
+                    _ => State::Payload,
+                };
+            },
+            State::Payload => {
+                let received = self.received;
+                self.received += 1;
+                self.sum = self.sum.wrapping_add(byte);
+
+                if self.received == self.size {
+                    self.state = self.payload_end();
+                }
+
+                if self.data {
+                    self.channels[usize::from(received % 3)] = byte;
+
+                    if received % 3 == 2 {
+                        let [red, green, blue] = self.channels;
+
+                        return Some(Event::Pixel(usize::from(received / 3), Color::RGB(red, green, blue)));
+                    }
+                }
+            },
+            State::Checksum => {
+                // Wrong sums turn into a wrong end byte.
+                self.sum ^= byte;
+                self.state = State::End;
+            },
+            State::End => {
+                self.state = State::Start;
+
+                if self.data {
+                    let valid = byte == END && (!self.checksum || self.sum == 0);
+                    self.valid = Some(valid);
+
+                    if valid {
+                        return Some(Event::Frame);
+                    }
+                }
+            },
+        }
+
+        None
+    }
+
+    /// Parse the next byte, send each complete pixel to the strip and latch
+    /// at the end of the packet, return `true` when it had a valid end byte.
+    pub fn push<T: Transmitter>(&mut self, byte: u8, led_strip: &mut LedStrip<T>) -> bool {
+        match self.feed(byte) {
+            Some(Event::Pixel(_, color)) => {
+                led_strip.color(color);
+
+                false
+            },
+            event => {
+                // Pixels of a packet with a wrong end byte are already sent,
+                // latch them anyway so the next packet starts at the first LED.
+                if self.valid.take().is_some() {
+                    led_strip.rest(LATCH_TIME);
+                }
+
+                event == Some(Event::Frame)
+            },
+        }
+    }
+
+    /// Parse the next byte into the back buffer, showing the frame at the
+    /// end of the packet, or dropping it if its end byte or checksum is
+    /// wrong; return the `ACK` or `NAK` byte to reply to the host at the end
+    /// of each data packet.
+    pub fn push_checked<T: Transmitter, const N: usize>(
+        &mut self,
+        byte: u8,
+        led_strip: &mut DoubleBufferedStrip<T, N>,
+    ) -> Option<u8> {
+        let pixel = match self.feed(byte) {
+            Some(Event::Pixel(led_index, color)) => Some((led_index, color)),
+            _ => None,
+        };
+
+        stage(led_strip, pixel, self.valid.take())
+    }
+}
+
+impl Default for Tpm2 {
+    fn default() -> Self {
+        Tpm2::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::DoubleBufferedStrip;
+    use crate::led::{Color, LedStrip};
+    use crate::mock::MockPin;
+    use crate::stream::{ACK, NAK};
+    use crate::tpm2::{Event, Tpm2};
+
+    #[test]
+    fn packets() {
+        let mut tpm2 = Tpm2::new();
+        let mut events = [None; 4];
+        let mut count = 0;
+        let stream = [
+            0x00, 0xC9, 0xC0, 0x00, 0x02, 0xC9, 0xDA, 0x36,
+            0xC9, 0xDA, 0x00, 0x07, 1, 2, 3, 4, 5, 6, 7, 0x36,
+            0xC9, 0xDA, 0x00, 0x03, 8, 9, 10, 0x00,
+        ];
+
+        for byte in stream {
+            if let Some(event) = tpm2.feed(byte) {
+                events[count] = Some(event);
+                count += 1;
+            }
+        }
+
+        assert_eq!(events, [
+            Some(Event::Pixel(0, Color::RGB(1, 2, 3))),
+            Some(Event::Pixel(1, Color::RGB(4, 5, 6))),
+            Some(Event::Frame),
+            Some(Event::Pixel(0, Color::RGB(8, 9, 10))),
+        ]);
+    }
+
+    #[test]
+    fn push() {
+        let mut tpm2 = Tpm2::new();
+        let mut led_strip = LedStrip::with_transmitter(2, MockPin::<256>::new());
+        let stream = [
+            0xC9, 0xDA, 0x00, 0x06, 1, 2, 3, 4, 5, 6, 0x00,
+            0xC9, 0xDA, 0x00, 0x06, 7, 8, 9, 10, 11, 12, 0x36,
+        ];
+        let frames: [bool; 22] = core::array::from_fn(|index| tpm2.push(stream[index], &mut led_strip));
+
+        assert_eq!(frames.iter().filter(|&&frame| frame).count(), 1);
+        assert!(frames[21]);
+        assert_eq!(led_strip.transmitter().latches().count(), 2);
+        assert!(led_strip.transmitter().bytes().skip(6).eq([8, 7, 9, 11, 10, 12]));
+    }
+
+    #[test]
+    fn checksum() {
+        let mut tpm2 = Tpm2::new().with_checksum();
+        let mut led_strip = DoubleBufferedStrip::<_, 1>::new(LedStrip::with_transmitter(1, MockPin::<128>::new()));
+        let stream = [
+            0xC9, 0xDA, 0x00, 0x03, 10, 20, 30, 60, 0x36,
+            0xC9, 0xDA, 0x00, 0x03, 10, 99, 30, 60, 0x36,
+            0xC9, 0xDA, 0x00, 0x03, 40, 50, 60, 150, 0x00,
+        ];
+        let replies: [Option<u8>; 27] = core::array::from_fn(|index| tpm2.push_checked(stream[index], &mut led_strip));
+
+        assert!(replies.iter().flatten().eq(&[ACK, NAK, NAK]));
+        assert_eq!(led_strip.front(), &[Color::RGB(10, 20, 30)]);
+        assert_eq!(led_strip.strip().transmitter().latches().count(), 1);
+    }
+}
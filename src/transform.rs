@@ -0,0 +1,112 @@
+use crate::flash::ByteSource;
+use crate::gamma::gamma16;
+
+/// Step of the output pipeline, changing the 16-bit channels of a pixel
+/// (in RGB order) just before it's sent.
+///
+/// The strip applies its user transform (`LedStrip::set_transform()`), then
+/// gamma, the voltage drop compensation, brightness, color correction, the
+/// power limit and dithering, so effects can stay pure functions of the LED
+/// index:
+///
+/// ```ignore
+/// struct Redshift(Cell<bool>);
+///
+/// impl PixelTransform for Redshift {
+///     fn transform(&self, led_index: usize, channels: [u16; 3]) -> [u16; 3] {
+///         match self.0.get() {
+///             true => ChannelScale(255, 160, 80).transform(led_index, channels),
+///             false => channels,
+///         }
+///     }
+/// }
+/// ```
+pub trait PixelTransform {
+    fn transform(&self, led_index: usize, channels: [u16; 3]) -> [u16; 3];
+}
+
+/// Scale all channels, 255 leaving them unchanged.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Brightness(pub u8);
+
+impl PixelTransform for Brightness {
+    fn transform(&self, _led_index: usize, channels: [u16; 3]) -> [u16; 3] {
+        channels.map(|channel| scale16(channel, self.0))
+    }
+}
+
+/// Scale each channel, e.g. for color correction or temperature.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ChannelScale(pub u8, pub u8, pub u8);
+
+impl PixelTransform for ChannelScale {
+    fn transform(&self, _led_index: usize, [red, green, blue]: [u16; 3]) -> [u16; 3] {
+        [scale16(red, self.0), scale16(green, self.1), scale16(blue, self.2)]
+    }
+}
+
+/// Gamma correction from a 256 entries table, see `gamma::gamma_table()`.
+pub struct Gamma(pub &'static dyn ByteSource);
+
+impl PixelTransform for Gamma {
+    fn transform(&self, _led_index: usize, channels: [u16; 3]) -> [u16; 3] {
+        channels.map(|channel| gamma16(self.0, channel))
+    }
+}
+
+/// Transform from a function or closure.
+pub struct FnTransform<F: Fn(usize, [u16; 3]) -> [u16; 3]>(pub F);
+
+impl<F: Fn(usize, [u16; 3]) -> [u16; 3]> PixelTransform for FnTransform<F> {
+    fn transform(&self, led_index: usize, channels: [u16; 3]) -> [u16; 3] {
+        (self.0)(led_index, channels)
+    }
+}
+
+/// Apply the first transform, then the second.
+impl<A: PixelTransform, B: PixelTransform> PixelTransform for (A, B) {
+    fn transform(&self, led_index: usize, channels: [u16; 3]) -> [u16; 3] {
+        self.1.transform(led_index, self.0.transform(led_index, channels))
+    }
+}
+
+/// Apply the transforms in order.
+impl<const N: usize> PixelTransform for [&dyn PixelTransform; N] {
+    fn transform(&self, led_index: usize, channels: [u16; 3]) -> [u16; 3] {
+        self.iter().fold(channels, |channels, step| step.transform(led_index, channels))
+    }
+}
+
+/// Scale `value` by `scale / 256`, with 255 keeping the value unchanged
+/// and 0 turning it off, fractional part included.
+fn scale16(value: u16, scale: u8) -> u16 {
+    match scale {
+        0 => 0,
+        _ => ((u32::from(value) * u32::from(scale) + u32::from(value)) >> 8) as u16,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gamma::GAMMA_2_2;
+    use crate::transform::{Brightness, ChannelScale, FnTransform, Gamma, PixelTransform};
+
+    #[test]
+    fn chain() {
+        let white = [65_535; 3];
+
+        assert_eq!(Brightness(255).transform(0, white), white);
+        assert_eq!(Brightness(127).transform(0, white), [32_767; 3]);
+        assert_eq!(ChannelScale(255, 0, 63).transform(0, white), [65_535, 0, 16_383]);
+        assert_eq!((Gamma(&GAMMA_2_2), Brightness(0)).transform(0, [128 * 257; 3]), [0; 3]);
+
+        let odd = FnTransform(|led_index, channels: [u16; 3]| match led_index % 2 {
+            0 => channels,
+            _ => [0; 3],
+        });
+        let steps: [&dyn PixelTransform; 2] = [&Brightness(127), &odd];
+
+        assert_eq!(steps.transform(0, white), [32_767; 3]);
+        assert_eq!(steps.transform(1, white), [0; 3]);
+    }
+}
@@ -0,0 +1,189 @@
+use core::ops::Range;
+use heapless::Vec;
+use crate::effect::Effect;
+use crate::gradient::palette_at;
+use crate::led::{Color, LedStrip, Transmitter};
+
+/// Effect played on a segment of the strip, as if the segment was a strip
+/// of its own: LED indexes start at 0 at the beginning of the segment.
+#[derive(Clone)]
+pub struct Zone<'a> {
+    segment: Range<usize>,
+    effect: Effect<'a>,
+    speed: u16,
+    palette: Option<&'a [Color]>,
+    position: u32,
+}
+
+impl<'a> Zone<'a> {
+    pub fn new(segment: Range<usize>, effect: Effect<'a>) -> Zone<'a> {
+        Zone {
+            segment,
+            effect,
+            speed: 256,
+            palette: None,
+            position: 0,
+        }
+    }
+
+    /// Frames of the effect per frame shown, in 1/256 (256 = unchanged),
+    /// e.g. 128 to play it at half speed.
+    pub fn with_speed(mut self, speed: u16) -> Zone<'a> {
+        self.speed = speed;
+
+        self
+    }
+
+    /// Colors the brightest channel of the effect is mapped to, from its
+    /// first color when unlit to its last at full intensity.
+    pub fn with_palette(mut self, palette: &'a [Color]) -> Zone<'a> {
+        self.palette = Some(palette);
+
+        self
+    }
+
+    pub fn segment(&self) -> &Range<usize> {
+        &self.segment
+    }
+
+    pub fn effect(&self) -> &Effect<'a> {
+        &self.effect
+    }
+
+    pub fn set_speed(&mut self, speed: u16) {
+        self.speed = speed;
+    }
+
+    pub fn set_palette(&mut self, palette: Option<&'a [Color]>) {
+        self.palette = palette;
+    }
+
+    /// Frame of the effect, advancing at the speed of the zone.
+    pub fn frame(&self) -> u32 {
+        self.position >> 8
+    }
+
+    /// Color of the LED at `led_index` of the strip, `None` outside the segment.
+    pub fn color(&self, led_index: usize) -> Option<Color> {
+        if !self.segment.contains(&led_index) {
+            return None;
+        }
+
+        let color = self.effect.color(self.frame(), led_index - self.segment.start);
+
+        Some(match self.palette {
+            Some(palette) => {
+                let (red, green, blue) = color.to_rgb();
+
+                palette_at(palette, red.max(green).max(blue))
+            },
+            None => color,
+        })
+    }
+
+    fn advance(&mut self) {
+        self.position = self.position.wrapping_add(u32::from(self.speed));
+    }
+}
+
+/// Up to `N` zones each playing its own effect, composited into one frame,
+/// e.g. fire on the bottom of a lamp and twinkles on the top.
+///
+/// Where zones overlap, the first added wins; LEDs out of any zone are black.
+pub struct Zones<'a, const N: usize> {
+    zones: Vec<Zone<'a>, N>,
+}
+
+impl<'a, const N: usize> Zones<'a, N> {
+    pub fn new() -> Zones<'a, N> {
+        Zones {
+            zones: Vec::new(),
+        }
+    }
+
+    /// Add a zone after the others, return its index or `None` when full.
+    pub fn add(&mut self, zone: Zone<'a>) -> Option<usize> {
+        self.zones.push(zone).ok()?;
+
+        Some(self.zones.len() - 1)
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Zone<'a>> {
+        (index < self.zones.len()).then(|| self.zones.remove(index))
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Zone<'a>> {
+        self.zones.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.zones.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.zones.is_empty()
+    }
+
+    pub fn color(&self, led_index: usize) -> Color {
+        self.zones.iter()
+            .find_map(|zone| zone.color(led_index))
+            .unwrap_or(Color::Black)
+    }
+
+    /// Send the current frame of all the zones and move each to its next frame.
+    pub fn show<T: Transmitter>(&mut self, led_strip: &mut LedStrip<T>) {
+        led_strip.each(|led_index| self.color(led_index));
+
+        for zone in self.zones.iter_mut() {
+            zone.advance();
+        }
+    }
+}
+
+impl<const N: usize> Default for Zones<'_, N> {
+    fn default() -> Self {
+        Zones::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::effect::Effect;
+    use crate::led::{Color, LedStrip};
+    use crate::mock::MockPin;
+    use crate::zone::{Zone, Zones};
+
+    #[test]
+    fn segments() {
+        let chase = |frame, led_index| match frame as usize % 2 == led_index {
+            true => Color::White,
+            false => Color::Black,
+        };
+        let solid = |_, _| Color::Red;
+        let mut zones: Zones<2> = Zones::new();
+        let mut led_strip = LedStrip::with_transmitter(5, MockPin::<512>::new());
+
+        assert_eq!(zones.add(Zone::new(0..2, Effect::new("chase", &chase)).with_speed(128)), Some(0));
+        assert_eq!(zones.add(Zone::new(2..4, Effect::new("solid", &solid))), Some(1));
+        assert_eq!(zones.add(Zone::new(4..5, Effect::new("solid", &solid))), None);
+
+        zones.show(&mut led_strip);
+        zones.show(&mut led_strip);
+        assert_eq!(zones.get_mut(0).map(|zone| zone.frame()), Some(1));
+        assert_eq!(zones.color(0), Color::Black);
+        assert_eq!(zones.color(1), Color::White);
+        assert_eq!(zones.color(3), Color::Red);
+        assert_eq!(zones.color(4), Color::Black);
+    }
+
+    #[test]
+    fn palette() {
+        let fade = |_, led_index| Color::RGB(0, 0, [0, 255][led_index]);
+        let palette = [Color::RGB(10, 0, 0), Color::RGB(250, 200, 0)];
+        let zone = Zone::new(3..5, Effect::new("fade", &fade)).with_palette(&palette);
+
+        assert_eq!(zone.color(2), None);
+        assert_eq!(zone.color(3), Some(Color::RGB(10, 0, 0)));
+        assert_eq!(zone.color(4), Some(Color::RGB(250, 200, 0)));
+    }
+}
@@ -0,0 +1,175 @@
+//! Built-in effects run for many frames on the host, checking every frame
+//! sent has one color per LED within the power budget, and that effects
+//! using `Random` render the same frames from the same seed.
+
+use led_strip::led::{Color, LedStrip, LedStripBuilder};
+use led_strip::mock::MockPin;
+use led_strip::power::{channel_draw, frame_milliamps, PowerLimit};
+#[cfg(any(feature = "effects-confetti", feature = "effects-lightning", feature = "effects-rain"))]
+use led_strip::random::Random;
+
+const LEDS: usize = 30;
+const FRAMES: u32 = 200;
+const MILLIAMPS: u16 = 150;
+
+/// Enough runs for a frame, two per bit.
+type Pin = MockPin<2048>;
+
+/// Render `FRAMES` frames with `render`, receiving the frame number, and
+/// return their bytes (GRB) once checked.
+fn run(mut render: impl FnMut(&mut LedStrip<Pin>, u32)) -> Vec<Vec<u8>> {
+    let mut led_strip = LedStripBuilder::new(Pin::new())
+        .leds(LEDS)
+        .power_limit(PowerLimit::new(5_000, MILLIAMPS))
+        .build();
+    let mut frames = Vec::new();
+
+    for frame in 0..FRAMES {
+        render(&mut led_strip, frame);
+        led_strip.latch();
+
+        let pin = led_strip.transmitter();
+        let bytes: Vec<u8> = pin.bytes().collect();
+        let draw = bytes.chunks(3).map(|grb| channel_draw(grb[1], grb[0], grb[2])).sum();
+
+        assert!(!pin.overflowed());
+        assert_eq!(pin.latches().count(), 1, "frame {frame}");
+        assert_eq!(bytes.len(), 3 * LEDS, "frame {frame}");
+        assert!(frame_milliamps(LEDS, draw) <= u32::from(MILLIAMPS), "frame {frame}");
+
+        frames.push(bytes);
+        led_strip.transmitter_mut().clear();
+    }
+
+    frames
+}
+
+/// Frames of a buffer based effect drawn with `draw`.
+fn run_buffered(mut draw: impl FnMut(&mut [Color])) -> Vec<Vec<u8>> {
+    let mut pixels = [Color::Black; LEDS];
+
+    run(|led_strip, _| {
+        draw(&mut pixels);
+        led_strip.write(pixels);
+    })
+}
+
+/// Whether some frames light at least one LED, so the checks above aren't
+/// only passing on a black strip.
+fn lit(frames: &[Vec<u8>]) -> bool {
+    frames.iter().flatten().any(|&byte| byte != 0)
+}
+
+#[test]
+#[cfg(feature = "effects-balls")]
+fn balls() {
+    use led_strip::balls::BouncingBalls;
+
+    let mut balls = BouncingBalls::<3>::new(LEDS);
+    let frames = run(|led_strip, _| {
+        balls.next_frame();
+        balls.show(led_strip);
+    });
+
+    assert!(lit(&frames));
+}
+
+#[test]
+#[cfg(feature = "effects-confetti")]
+fn confetti() {
+    use led_strip::confetti::Confetti;
+
+    let render = |seed| {
+        let mut confetti = Confetti::new(Random::new(seed)).spawn_rate(128);
+
+        run_buffered(|pixels| confetti.draw(pixels))
+    };
+    let frames = render(7);
+
+    assert!(lit(&frames));
+    assert_eq!(frames, render(7));
+    assert_ne!(frames, render(8));
+}
+
+#[test]
+#[cfg(feature = "effects-flasher")]
+fn flasher() {
+    use led_strip::flasher::AlternatingFlash;
+
+    let flash = AlternatingFlash::new(LEDS).colors(Color::White, Color::Blue);
+    let frames = run(|led_strip, frame| flash.show(led_strip, frame * 20));
+
+    assert!(lit(&frames));
+}
+
+#[test]
+#[cfg(feature = "effects-lightning")]
+fn lightning() {
+    use led_strip::lightning::Lightning;
+
+    let render = |seed| {
+        let mut lightning = Lightning::new(Random::new(seed), 5..LEDS + 5).intervals(50, 400);
+
+        run(|led_strip, frame| lightning.show(led_strip, frame * 20))
+    };
+    let frames = render(3);
+
+    assert!(lit(&frames));
+    assert_eq!(frames, render(3));
+}
+
+#[test]
+#[cfg(feature = "effects-ocean")]
+fn ocean() {
+    use led_strip::ocean::Ocean;
+
+    let ocean = Ocean::new();
+    let frames = run(|led_strip, frame| ocean.show(led_strip, frame));
+
+    assert!(lit(&frames));
+    assert_eq!(frames, run(|led_strip, frame| ocean.show(led_strip, frame)));
+}
+
+#[test]
+#[cfg(feature = "effects-rain")]
+fn rain() {
+    use led_strip::rain::DigitalRain;
+
+    let render = |seed| {
+        let mut rain = DigitalRain::<3>::new(Random::new(seed)).spawn_rate(64);
+
+        run_buffered(|pixels| rain.draw(pixels))
+    };
+    let frames = render(11);
+
+    assert!(lit(&frames));
+    assert_eq!(frames, render(11));
+    assert_ne!(frames, render(12));
+}
+
+#[test]
+#[cfg(feature = "effects-sunrise")]
+fn sunrise() {
+    use led_strip::sunrise::Sunrise;
+
+    let sunrise = Sunrise::new(0, FRAMES * 10);
+    let frames = run(|led_strip, frame| sunrise.show(led_strip, frame * 10));
+
+    assert!(frames[0].iter().all(|&byte| byte == 0));
+    assert!(lit(&frames));
+}
+
+#[test]
+fn gradient() {
+    use led_strip::gradient::Gradient;
+    use led_strip::sequence::PaletteIter;
+
+    const FIRE: Gradient<3> = Gradient::even([Color::Black, Color::Red, Color::Yellow]);
+    let palette = FIRE.palette::<8>();
+    let frames = run(|led_strip, frame| match frame % 2 {
+        0 => FIRE.show(led_strip),
+        _ => led_strip.write(PaletteIter::new(&palette, frame as u8, 8)),
+    });
+
+    assert!(lit(&frames));
+}